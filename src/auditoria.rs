@@ -0,0 +1,304 @@
+// 🔹 Trilha de auditoria do catálogo ("<catalogo>.auditoria.jsonl"): quem
+// alterou ou consultou o quê, e quando. Cada entrada leva o hash SHA-256 da
+// anterior, então editar ou apagar uma entrada quebra a corrente a partir dela.
+// O último número e hash ficam também em "<...>.ancora", para que apagar as
+// últimas entradas não passe despercebido. A corrente não tem chave: quem pode
+// gravar na pasta consegue regravar trilha e âncora inteiras.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::produto::Produto;
+
+// Hash "anterior" da primeira entrada
+const INICIO: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// 🔹 Uma entrada da trilha. O hash é o SHA-256 do JSON da entrada sem o campo
+// "hash", que vai no fim da linha: a conferência usa o texto gravado, sem
+// depender de o JSON ser regerado igual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entrada {
+    pub seq: u64,
+    pub quando: DateTime<Local>,
+    pub operador: String,
+    // "incluir", "renomear", "alterar", "remover", "desfazer", "refazer", "consultar", "exportar",
+    // "operador-incluir"...
+    pub operacao: String,
+    pub descricao: String,
+    pub antes: Option<Produto>,
+    pub depois: Option<Produto>,
+    pub anterior: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
+}
+
+// Linha da trilha: o JSON sem o hash, com o hash acrescentado antes do '}' final
+fn linha_com_hash(entrada: &Entrada) -> io::Result<(String, String)> {
+    let conteudo = serde_json::to_string(entrada).map_err(io::Error::other)?;
+    let hash = hex(&Sha256::digest(conteudo.as_bytes()));
+    let corpo = conteudo.strip_suffix('}').unwrap_or(&conteudo);
+    Ok((format!("{},\"hash\":\"{}\"}}", corpo, hash), hash))
+}
+
+// Hash gravado na linha e o hash do texto que ele deveria cobrir
+fn hashes_da_linha(linha: &str) -> Option<(&str, String)> {
+    let pos = linha.rfind(",\"hash\":\"")?;
+    let gravado = linha[pos..]
+        .strip_prefix(",\"hash\":\"")?
+        .strip_suffix("\"}")?;
+    let conteudo = format!("{}}}", &linha[..pos]);
+    Some((gravado, hex(&Sha256::digest(conteudo.as_bytes()))))
+}
+
+// O que vai para a trilha; `seq`, `quando`, `anterior` e `hash` são preenchidos ao gravar
+#[derive(Debug, Clone, Default)]
+pub struct Evento {
+    pub operacao: String,
+    pub descricao: String,
+    pub antes: Option<Produto>,
+    pub depois: Option<Produto>,
+}
+
+pub fn caminho_da_auditoria(caminho: &str) -> String {
+    format!("{}.auditoria.jsonl", caminho)
+}
+
+fn caminho_da_ancora(caminho: &str) -> String {
+    format!("{}.ancora", caminho_da_auditoria(caminho))
+}
+
+// 🔹 Usuário do sistema operacional, enquanto ninguém se identifica no programa
+pub fn operador_do_sistema() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "desconhecido".to_string())
+}
+
+// Última entrada gravada: número e hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Ancora {
+    seq: u64,
+    hash: String,
+}
+
+// 🔹 Trilha aberta para gravação
+pub struct Auditoria {
+    caminho: String,
+    arquivo: File,
+    // Tamanho do arquivo e última entrada depois da nossa última gravação; se o
+    // tamanho mudou, outro programa gravou e a última entrada é relida
+    tamanho: u64,
+    ultima: Ancora,
+}
+
+impl Auditoria {
+    pub fn abrir(caminho: &str) -> io::Result<Auditoria> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_da_auditoria(caminho))?;
+        Ok(Auditoria {
+            caminho: caminho.to_string(),
+            arquivo,
+            tamanho: u64::MAX,
+            ultima: Ancora {
+                seq: 0,
+                hash: INICIO.to_string(),
+            },
+        })
+    }
+
+    // 🔹 Acrescenta as entradas, encadeadas à última da trilha. O arquivo fica
+    // travado durante a gravação para outro programa não encadear na mesma.
+    pub fn registrar(&mut self, operador: &str, eventos: Vec<Evento>) -> io::Result<()> {
+        self.arquivo.lock()?;
+        let resultado = self.registrar_travado(operador, eventos);
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    // Uma linha incompleta no fim (queda no meio de uma gravação) é cortada,
+    // para a próxima entrada não emendar nela; a âncora ainda aponta para a
+    // entrada anterior, então a corrente continua conferindo
+    fn registrar_travado(&mut self, operador: &str, eventos: Vec<Evento>) -> io::Result<()> {
+        let tamanho = self.arquivo.metadata()?.len();
+        if tamanho != self.tamanho {
+            let (ultima, completas) = ultima_entrada(&self.caminho)?;
+            if tamanho > completas {
+                self.arquivo.set_len(completas)?;
+            }
+            self.ultima = ultima;
+        }
+
+        let mut linhas = Vec::new();
+        for evento in eventos {
+            let entrada = Entrada {
+                seq: self.ultima.seq + 1,
+                quando: Local::now(),
+                operador: operador.to_string(),
+                operacao: evento.operacao,
+                descricao: evento.descricao,
+                antes: evento.antes,
+                depois: evento.depois,
+                anterior: self.ultima.hash.clone(),
+                hash: String::new(),
+            };
+            let (linha, hash) = linha_com_hash(&entrada)?;
+            linhas.extend_from_slice(linha.as_bytes());
+            linhas.push(b'\n');
+            self.ultima = Ancora {
+                seq: entrada.seq,
+                hash,
+            };
+        }
+
+        self.arquivo.write_all(&linhas)?;
+        self.arquivo.sync_data()?;
+        self.tamanho = self.arquivo.metadata()?.len();
+
+        // Temporário e troca: uma queda não deixa a âncora pela metade
+        let ancora = serde_json::to_vec(&self.ultima).map_err(io::Error::other)?;
+        let destino = caminho_da_ancora(&self.caminho);
+        let temporario = format!("{}.tmp", destino);
+        let mut arquivo = File::create(&temporario)?;
+        arquivo.write_all(&ancora)?;
+        arquivo.sync_all()?;
+        fs::rename(&temporario, &destino)
+    }
+}
+
+// Última entrada válida da trilha (a corrente é conferida por `verificar`) e
+// quantos bytes ocupam as linhas completas
+fn ultima_entrada(caminho: &str) -> io::Result<(Ancora, u64)> {
+    let bytes = match fs::read(caminho_da_auditoria(caminho)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let completas = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let ultima = bytes[..completas]
+        .split(|&b| b == b'\n')
+        .rev()
+        .find_map(|linha| serde_json::from_slice::<Entrada>(linha).ok())
+        .map_or(
+            Ancora {
+                seq: 0,
+                hash: INICIO.to_string(),
+            },
+            |e| Ancora {
+                seq: e.seq,
+                hash: e.hash,
+            },
+        );
+    Ok((ultima, completas as u64))
+}
+
+// 🔹 As entradas da trilha, na ordem em que foram gravadas (sem conferir a
+// corrente; as linhas ilegíveis ficam de fora)
+pub fn ler(caminho: &str) -> io::Result<Vec<Entrada>> {
+    let texto = match fs::read_to_string(caminho_da_auditoria(caminho)) {
+        Ok(texto) => texto,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(texto
+        .lines()
+        .filter_map(|linha| serde_json::from_str(linha).ok())
+        .collect())
+}
+
+// 🔹 Resultado da verificação: entradas conferidas e problemas encontrados
+#[derive(Debug, Default)]
+pub struct Verificacao {
+    pub entradas: u64,
+    pub ultimo_hash: String,
+    // (linha do arquivo, problema); linha 0 = a trilha como um todo
+    pub problemas: Vec<(usize, String)>,
+}
+
+// 🔹 Confere a corrente inteira: cada entrada precisa ter o hash do próprio
+// conteúdo, apontar para o hash da anterior e ter o número seguinte ao dela
+pub fn verificar(caminho: &str) -> io::Result<Verificacao> {
+    let texto = fs::read_to_string(caminho_da_auditoria(caminho))?;
+    let mut verificacao = Verificacao {
+        ultimo_hash: INICIO.to_string(),
+        ..Verificacao::default()
+    };
+    let mut seq = 0;
+
+    for (i, linha) in texto.lines().enumerate() {
+        let numero = i + 1;
+        let entrada: Entrada = match serde_json::from_str(linha) {
+            Ok(entrada) => entrada,
+            Err(e) => {
+                verificacao
+                    .problemas
+                    .push((numero, format!("entrada ilegível: {}", e)));
+                continue;
+            }
+        };
+
+        if hashes_da_linha(linha).is_none_or(|(gravado, calculado)| gravado != calculado) {
+            verificacao.problemas.push((
+                numero,
+                format!("entrada {} foi alterada (hash não confere)", entrada.seq),
+            ));
+        }
+        if entrada.anterior != verificacao.ultimo_hash {
+            verificacao.problemas.push((
+                numero,
+                format!(
+                    "entrada {} não aponta para a anterior (entrada removida ou alterada antes dela)",
+                    entrada.seq
+                ),
+            ));
+        }
+        if entrada.seq != seq + 1 {
+            verificacao.problemas.push((
+                numero,
+                format!(
+                    "esperada a entrada {}, encontrada a {}",
+                    seq + 1,
+                    entrada.seq
+                ),
+            ));
+        }
+
+        seq = entrada.seq;
+        verificacao.entradas += 1;
+        verificacao.ultimo_hash = entrada.hash;
+    }
+
+    // A âncora pega a remoção das últimas entradas, que não quebra a corrente
+    match fs::read(caminho_da_ancora(caminho)) {
+        Ok(bytes) => match serde_json::from_slice::<Ancora>(&bytes) {
+            Ok(ancora) if ancora.seq == seq && ancora.hash == verificacao.ultimo_hash => {}
+            Ok(ancora) => verificacao.problemas.push((
+                0,
+                format!(
+                    "a última entrada gravada foi a {}, mas a trilha termina na {} (entradas do fim removidas ou alteradas)",
+                    ancora.seq, seq
+                ),
+            )),
+            Err(_) => verificacao
+                .problemas
+                .push((0, "âncora da trilha ilegível".to_string())),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound && seq == 0 => {}
+        Err(_) => verificacao
+            .problemas
+            .push((0, "âncora da trilha não encontrada".to_string())),
+    }
+
+    Ok(verificacao)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
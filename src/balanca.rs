@@ -0,0 +1,173 @@
+// 🔹 Etiquetas de balança: códigos EAN-13 de uso interno (começados em 2) que a
+// balança imprime com o código do produto e o peso ou o preço embutidos. Cada
+// modelo de balança monta o código de um jeito; os formatos aceitos ficam em
+// "balanca.json".
+
+use std::fs;
+use std::io;
+
+use serde::Deserialize;
+
+use crate::catalogo::{Catalogo, IdProduto};
+use crate::dinheiro::Reais;
+use crate::gtin;
+
+pub const ARQUIVO_BALANCA: &str = "balanca.json";
+
+// 🔹 Formato de fábrica das balanças mais comuns: 2, código do produto com 4
+// dígitos, um 0, preço total em centavos com 6 dígitos e o dígito verificador.
+// Em cada formato: C = código do produto, P = preço em centavos, W = peso em
+// gramas, D = dígito verificador, X = ignorado e algarismos precisam bater.
+pub const LAYOUTS_PADRAO: &[&str] = &["2CCCC0PPPPPPD"];
+
+// O que a balança mediu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Valor {
+    // Em quilos
+    Peso(f64),
+    // Preço total da etiqueta
+    Preco(Reais),
+}
+
+// 🔹 Etiqueta lida: o código do produto como está no código de barras
+#[derive(Debug, Clone, PartialEq)]
+pub struct Etiqueta {
+    pub codigo: String,
+    pub valor: Valor,
+}
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    padrao: Vec<u8>,
+}
+
+impl Layout {
+    // 🔹 Confere o formato: 13 posições, terminando no dígito verificador, com
+    // o código e exatamente um valor (preço ou peso)
+    pub fn novo(padrao: &str) -> Result<Layout, String> {
+        let invalido = |motivo: &str| Err(format!("formato de balança '{}': {}", padrao, motivo));
+        let bytes = padrao.as_bytes();
+        if bytes.len() != 13 {
+            return invalido("precisa ter 13 posições");
+        }
+        if let Some(c) = padrao.chars().find(|c| !"CPWDX0123456789".contains(*c)) {
+            return invalido(&format!("letra '{}' desconhecida", c));
+        }
+        if bytes[12] != b'D' || bytes[..12].contains(&b'D') {
+            return invalido("o dígito verificador (D) é a última posição");
+        }
+        if !bytes.contains(&b'C') {
+            return invalido("falta o código do produto (C)");
+        }
+        if bytes.contains(&b'P') == bytes.contains(&b'W') {
+            return invalido("use o preço (P) ou o peso (W), um dos dois");
+        }
+        Ok(Layout {
+            padrao: bytes.to_vec(),
+        })
+    }
+
+    // Etiqueta, se o código de barras segue este formato
+    pub fn ler(&self, codigo_de_barras: &str) -> Option<Etiqueta> {
+        let mut codigo = String::new();
+        let mut valor = String::new();
+        for (&p, c) in self.padrao.iter().zip(codigo_de_barras.chars()) {
+            match p {
+                b'C' => codigo.push(c),
+                b'P' | b'W' => valor.push(c),
+                b'D' | b'X' => {}
+                literal if literal as char != c => return None,
+                _ => {}
+            }
+        }
+        let valor: i64 = valor.parse().ok()?;
+        let valor = if self.padrao.contains(&b'P') {
+            Valor::Preco(Reais::de_centavos(valor))
+        } else {
+            Valor::Peso(valor as f64 / 1000.0)
+        };
+        Some(Etiqueta { codigo, valor })
+    }
+}
+
+#[derive(Deserialize)]
+struct Arquivo {
+    layouts: Vec<String>,
+}
+
+// 🔹 Os formatos de etiqueta aceitos, na ordem em que são tentados
+#[derive(Debug, Clone)]
+pub struct Balanca {
+    layouts: Vec<Layout>,
+}
+
+impl Balanca {
+    // Arquivo inexistente = formatos de fábrica
+    pub fn abrir(caminho: &str) -> io::Result<Balanca> {
+        let padroes = match fs::read_to_string(caminho) {
+            Ok(texto) => {
+                let arquivo: Arquivo = serde_json::from_str(&texto)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                arquivo.layouts
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                LAYOUTS_PADRAO.iter().map(|p| p.to_string()).collect()
+            }
+            Err(e) => return Err(e),
+        };
+        let layouts = padroes
+            .iter()
+            .map(|p| Layout::novo(p))
+            .collect::<Result<_, _>>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Balanca { layouts })
+    }
+
+    // 🔹 Etiqueta do código de barras (None se não é de balança ou nenhum
+    // formato bate); Err se o dígito verificador não confere
+    pub fn ler(&self, entrada: &str) -> Result<Option<Etiqueta>, String> {
+        let entrada = entrada.trim();
+        if entrada.len() != 13 || !entrada.starts_with('2') || !gtin::parece_gtin(entrada) {
+            return Ok(None);
+        }
+        let entrada = gtin::validar(entrada)?;
+        Ok(self.layouts.iter().find_map(|l| l.ler(&entrada)))
+    }
+}
+
+// 🔹 O produto lido e, se veio de uma etiqueta de balança, o peso ou o preço
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leitura {
+    pub id: IdProduto,
+    pub valor: Option<Valor>,
+}
+
+// 🔹 Como `gtin::localizar`, mas também lê as etiquetas de balança. O código
+// interno e os códigos de barras cadastrados vêm antes; só depois a entrada é
+// lida como etiqueta, e o código embutido é procurado no catálogo (também sem
+// os zeros à esquerda, para formatos com mais dígitos que o código interno).
+pub fn localizar(
+    catalogo: &Catalogo,
+    balanca: &Balanca,
+    entrada: &str,
+) -> Result<Option<Leitura>, String> {
+    if let Some(id) = gtin::localizar(catalogo, entrada)? {
+        return Ok(Some(Leitura { id, valor: None }));
+    }
+    let Some(etiqueta) = balanca.ler(entrada)? else {
+        return Ok(None);
+    };
+    let id = catalogo
+        .buscar_por_codigo(&etiqueta.codigo)
+        .or_else(|| catalogo.buscar_por_codigo(etiqueta.codigo.trim_start_matches('0')));
+    match id {
+        Some(id) => Ok(Some(Leitura {
+            id,
+            valor: Some(etiqueta.valor),
+        })),
+        None => Err(format!(
+            "etiqueta de balança com o produto {}, que não está no catálogo",
+            etiqueta.codigo
+        )),
+    }
+}
@@ -0,0 +1,93 @@
+use std::env;
+use std::fs::File;
+use std::time::Instant;
+
+use projeto_rust::gerador::{self, ConfigGerador, Modelo};
+use projeto_rust::produto::carregar_produtos;
+
+const USO: &str = "Uso: gerar_catalogo <quantidade> [--modelo produtos.txt] [--saida catalogo_gerado.txt]
+                      [--semente 42] [--duplicados 0.05] [--sem-acento 0.10]";
+
+fn main() {
+    let mut config = ConfigGerador::default();
+    let mut modelo = "produtos.txt".to_string();
+    let mut saida = "catalogo_gerado.txt".to_string();
+    let mut quantidade = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--modelo" => args.next().map(|v| modelo = v).is_some(),
+            "--saida" => args.next().map(|v| saida = v).is_some(),
+            "--semente" => args
+                .next()
+                .and_then(|v| v.parse().ok())
+                .map(|s| config.semente = s)
+                .is_some(),
+            "--duplicados" => args
+                .next()
+                .and_then(|v| ler_taxa(&v))
+                .map(|t| config.taxa_duplicados = t)
+                .is_some(),
+            "--sem-acento" => args
+                .next()
+                .and_then(|v| ler_taxa(&v))
+                .map(|t| config.taxa_sem_acento = t)
+                .is_some(),
+            _ => {
+                quantidade = arg.parse::<u64>().ok();
+                quantidade.is_some()
+            }
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO);
+            return;
+        }
+    }
+
+    let Some(quantidade) = quantidade else {
+        println!("{}", USO);
+        return;
+    };
+    config.quantidade = quantidade;
+
+    let produtos = carregar_produtos(&modelo);
+    let Some(modelo_aprendido) = Modelo::aprender(&produtos) else {
+        println!("Erro: nenhum nome aproveitável em '{}' para aprender o modelo.", modelo);
+        return;
+    };
+
+    let arquivo = match File::create(&saida) {
+        Ok(f) => f,
+        Err(e) => {
+            println!("⚠️ Não foi possível criar '{}': {}", saida, e);
+            return;
+        }
+    };
+
+    println!(
+        "Gerando {} produtos a partir de {} nomes de '{}' (semente {})...",
+        quantidade,
+        produtos.len(),
+        modelo,
+        config.semente
+    );
+
+    let inicio = Instant::now();
+    match gerador::gerar(&modelo_aprendido, &config, arquivo) {
+        Ok(total) => println!(
+            "✅ {} produtos gravados em '{}'. Operação concluída em {:.3} segundos.",
+            total,
+            saida,
+            inicio.elapsed().as_secs_f64()
+        ),
+        Err(e) => println!("⚠️ Erro ao gravar '{}': {}", saida, e),
+    }
+}
+
+// Taxas são aceitas entre 0.0 e 1.0
+fn ler_taxa(valor: &str) -> Option<f64> {
+    valor.parse::<f64>().ok().filter(|t| (0.0..=1.0).contains(t))
+}
@@ -115,7 +115,7 @@ fn listar_por_nome(produtos: &[Produto]) {
     let inicio = Instant::now(); // inicia o cronômetro
 
     let mut lista = produtos.to_vec();
-    lista.sort_by(|a, b| a.nome.to_lowercase().cmp(&b.nome.to_lowercase()));
+    lista.sort_by_key(|a| a.nome.to_lowercase());
 
     println!("Produtos em ordem alfabética:");
     for p in lista {
@@ -29,13 +29,15 @@ fn carregar_produtos(caminho: &str) -> Vec<Produto> {
         let reader = io::BufReader::new(file);
 
         for linha in reader.lines() {
-            if let Ok(linha) = linha {
-                let partes: Vec<&str> = linha.splitn(2, ';').collect();
-                if partes.len() == 2 {
-                    let codigo = partes[0].trim();
-                    let nome = partes[1].trim();
-                    produtos.push(Produto::new(codigo, nome));
-                }
+            // Linha ilegível (fora do UTF-8) é pulada; as seguintes continuam
+            let Ok(linha) = linha else {
+                continue;
+            };
+            let partes: Vec<&str> = linha.splitn(2, ';').collect();
+            if partes.len() == 2 {
+                let codigo = partes[0].trim();
+                let nome = partes[1].trim();
+                produtos.push(Produto::new(codigo, nome));
             }
         }
     } else {
@@ -66,7 +68,7 @@ fn main() {
 
     // Vetores já ordenados para simplificar e otimizar a busca pelo usuário
     let mut ordenado_por_nome = produtos.clone();
-    ordenado_por_nome.sort_by(|a, b| a.nome.to_lowercase().cmp(&b.nome.to_lowercase()));
+    ordenado_por_nome.sort_by_key(|a| a.nome.to_lowercase());
 
     let mut ordenado_por_codigo = produtos.clone();
     ordenado_por_codigo.sort_by(|a, b| a.codigo.cmp(&b.codigo));
@@ -174,10 +176,10 @@ fn listar_por_letra_inicial(lista: &[Produto]) {
 
     //numerar os resultados para facilitar a visualização
     for (i, p) in lista.iter().enumerate() {
-        if let Some(primeira) = p.nome.chars().next() {
-            if primeira.to_lowercase().to_string() == letra {
-                encontrados.push((i + 1, p));
-            }
+        if let Some(primeira) = p.nome.chars().next()
+            && primeira.to_lowercase().to_string() == letra
+        {
+            encontrados.push((i + 1, p));
         }
     }
 
@@ -0,0 +1,71 @@
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::auditoria::{self, Auditoria, Evento};
+use projeto_rust::operadores::Sessao;
+
+// 🔹 Grava uma entrada na trilha do catálogo em nome de quem entrou
+pub fn registrar(
+    caminho: &str,
+    sessao: &Sessao,
+    operacao: &str,
+    descricao: String,
+) -> io::Result<()> {
+    let evento = Evento {
+        operacao: operacao.to_string(),
+        descricao,
+        ..Evento::default()
+    };
+    Auditoria::abrir(caminho)?.registrar(&sessao.nome, vec![evento])
+}
+
+// 🔹 megastore verificar-auditoria: sai com 0 se a trilha está íntegra, 1 se
+// alguma entrada foi alterada ou removida e 2 em caso de erro
+pub fn verificar(args: &[String]) -> ExitCode {
+    let caminho = match args {
+        [] => "produtos.txt",
+        [opcao, caminho] if opcao == "--catalogo" => caminho.as_str(),
+        _ => {
+            println!("Uso: megastore verificar-auditoria [--catalogo produtos.txt]");
+            return ExitCode::from(2);
+        }
+    };
+
+    let inicio = Instant::now();
+    let verificacao = match auditoria::verificar(caminho) {
+        Ok(verificacao) => verificacao,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                auditoria::caminho_da_auditoria(caminho),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+
+    for (linha, problema) in &verificacao.problemas {
+        match linha {
+            0 => println!("  ❌ {}", problema),
+            _ => println!("  ❌ linha {}: {}", linha, problema),
+        }
+    }
+
+    if verificacao.problemas.is_empty() {
+        println!(
+            "✅ {} entradas conferidas, trilha íntegra (último hash {}). Operação concluída em {:.6} segundos.",
+            verificacao.entradas,
+            verificacao.ultimo_hash,
+            inicio.elapsed().as_secs_f64()
+        );
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "⚠️ {} problemas em {} entradas: a trilha de auditoria foi adulterada.",
+            verificacao.problemas.len(),
+            verificacao.entradas
+        );
+        ExitCode::from(1)
+    }
+}
@@ -0,0 +1,177 @@
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::{Catalogo, IdProduto};
+use projeto_rust::categorias;
+use projeto_rust::codificacao::Codificacao;
+use projeto_rust::formato::Formato;
+use projeto_rust::gtin;
+use projeto_rust::operadores::Sessao;
+use projeto_rust::produto::Produto;
+use projeto_rust::texto::comparar_sem_caixa;
+
+const USO_BUSCAR: &str = "Uso: megastore buscar [--catalogo produtos.txt|produtos.db] (--codigo <código ou código de barras> | <termo>...) [--categoria nome]";
+
+// 🔹 megastore buscar: procura por código (o interno ou o de barras) ou por
+// palavras do nome, só na categoria se pedido (sem código nem termo, lista a
+// categoria inteira). Num banco SQLite a busca usa os índices do próprio banco,
+// sem carregar o catálogo. A consulta vai para a trilha de auditoria.
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((args, codificacao)) = crate::separar_codificacao(args) else {
+        return ExitCode::from(2);
+    };
+    let mut caminho = "produtos.txt".to_string();
+    let mut codigo = None;
+    let mut categoria = None;
+    let mut palavras = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--codigo" => args.next().map(|v| codigo = Some(v.clone())).is_some(),
+            "--categoria" => args.next().map(|v| categoria = Some(v.clone())).is_some(),
+            a if a.starts_with("--") => false,
+            a => {
+                palavras.push(a.to_string());
+                true
+            }
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_BUSCAR);
+            return ExitCode::from(2);
+        }
+    }
+    let termo = palavras.join(" ");
+    // Código ou termo, não os dois; sem nenhum, só com a categoria
+    if (codigo.is_some() && !termo.is_empty())
+        || (codigo.is_none() && termo.is_empty() && categoria.is_none())
+    {
+        println!("{}", USO_BUSCAR);
+        return ExitCode::from(2);
+    }
+
+    let inicio = Instant::now();
+    let resultado = if Formato::de_caminho(&caminho) == Some(Formato::Sqlite) {
+        buscar_no_banco(&caminho, codigo.as_deref(), &termo)
+    } else {
+        buscar_no_catalogo(&caminho, codificacao, codigo.as_deref(), &termo)
+    };
+    let resultado = match &categoria {
+        Some(categoria) => resultado.map(|p| na_categoria(p, categoria)),
+        None => resultado,
+    };
+    let encontrados = match resultado {
+        Ok(encontrados) => encontrados,
+        Err(e) => {
+            println!("⚠️ Não foi possível consultar '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut descricao = match &codigo {
+        Some(codigo) => format!("busca pelo código '{}'", codigo),
+        None if termo.is_empty() => "lista".to_string(),
+        None => format!("busca por '{}'", termo),
+    };
+    if let Some(categoria) = &categoria {
+        descricao.push_str(&format!(" na categoria '{}'", categoria));
+    }
+    if let Err(e) = crate::auditoria::registrar(&caminho, sessao, "consultar", descricao) {
+        println!("⚠️ Consulta não registrada na auditoria: {}", e);
+    }
+
+    for p in &encontrados {
+        println!("{} - {}", p.codigo, p.nome);
+    }
+    println!(
+        "✅ {} produtos encontrados. Operação concluída em {:.6} segundos.",
+        encontrados.len(),
+        inicio.elapsed().as_secs_f64()
+    );
+
+    if encontrados.is_empty() {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn buscar_no_banco(caminho: &str, codigo: Option<&str>, termo: &str) -> io::Result<Vec<Produto>> {
+    if !std::path::Path::new(caminho).exists() {
+        return Err(io::Error::from(io::ErrorKind::NotFound));
+    }
+    let banco = projeto_rust::sqlite::Banco::abrir(caminho)?;
+    let mut produtos = match codigo {
+        Some(codigo) => match banco.buscar_por_codigo(codigo)? {
+            Some(produto) => vec![produto],
+            // Sem produto com esse código, tenta como código de barras
+            None if gtin::parece_gtin(codigo) => {
+                let gtin = gtin::validar(codigo)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                banco.buscar_por_gtin(&gtin)?.into_iter().collect()
+            }
+            None => Vec::new(),
+        },
+        None if termo.is_empty() => {
+            let mut produtos = banco.produtos()?;
+            produtos.sort_by(|a, b| comparar_sem_caixa(&a.nome, &b.nome));
+            produtos
+        }
+        None => banco.buscar_por_nome(termo)?,
+    };
+    projeto_rust::cadastro::completar(caminho, &mut produtos)?;
+    Ok(produtos)
+}
+
+// Sem a feature "sqlite", o erro de `ler_produtos` explica como compilar
+#[cfg(not(feature = "sqlite"))]
+fn buscar_no_banco(caminho: &str, _codigo: Option<&str>, _termo: &str) -> io::Result<Vec<Produto>> {
+    projeto_rust::sqlite::ler_produtos(caminho)
+}
+
+// Catálogo em arquivo: cada palavra do termo precisa aparecer no nome (sem
+// termo, vale o catálogo inteiro)
+fn buscar_no_catalogo(
+    caminho: &str,
+    codificacao: Option<Codificacao>,
+    codigo: Option<&str>,
+    termo: &str,
+) -> io::Result<Vec<Produto>> {
+    let catalogo = Catalogo::abrir(caminho, codificacao)?;
+    catalogo.conversao().relatar(caminho);
+
+    let mut ids: Vec<IdProduto> = match codigo {
+        Some(codigo) => gtin::localizar(&catalogo, codigo)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .into_iter()
+            .collect(),
+        None => {
+            let mut palavras = termo.split_whitespace();
+            let mut ids = palavras
+                .next()
+                .map(|p| catalogo.buscar_por_palavra(p))
+                .unwrap_or_else(|| catalogo.ordem_por_codigo().to_vec());
+            for palavra in palavras {
+                let outros = catalogo.buscar_por_palavra(palavra);
+                ids.retain(|id| outros.binary_search(id).is_ok());
+            }
+            ids
+        }
+    };
+    ids.sort_by(|&a, &b| comparar_sem_caixa(catalogo.nome(a), catalogo.nome(b)));
+
+    Ok(ids.into_iter().map(|id| catalogo.produto(id)).collect())
+}
+
+// Só os produtos da categoria (ou de baixo dela), na mesma ordem
+fn na_categoria(produtos: Vec<Produto>, categoria: &str) -> Vec<Produto> {
+    produtos
+        .into_iter()
+        .filter(|p| categorias::pertence(p.complemento.categoria.as_deref(), categoria))
+        .collect()
+}
@@ -0,0 +1,368 @@
+use std::process::ExitCode;
+
+use projeto_rust::cadastro::{self, Cadastro, Niveis};
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::categorias;
+use projeto_rust::dinheiro::Reais;
+use projeto_rust::estoque;
+use projeto_rust::gtin;
+use projeto_rust::margem::{self, MARKUP_PADRAO};
+use projeto_rust::operadores::{Permissao, Sessao};
+
+const USO_PRODUTO: &str = "Uso: megastore produto [--catalogo produtos.txt] <código>
+        [--preco 12,90] [--custo 7,50] [--gtin 7891234567895]
+        [--categoria \"Departamento > Categoria > Subcategoria\"]
+        [--fornecedor nome]
+        [--minimo N] [--maximo N]
+      (\"-\" como valor apaga o campo)";
+
+const USO_CATEGORIA: &str =
+    "Uso: megastore categoria [--catalogo produtos.txt] <categoria> [--minimo N] [--maximo N]";
+
+// Valor de uma opção: "-" apaga o campo (Some(None))
+fn texto_ou_apagar(valor: &str) -> Option<String> {
+    (valor != "-").then(|| valor.to_string())
+}
+
+fn quantidade_ou_apagar(valor: &str) -> Option<Option<f64>> {
+    if valor == "-" {
+        Some(None)
+    } else {
+        estoque::ler_quantidade(valor).map(Some)
+    }
+}
+
+fn preco_ou_apagar(valor: &str) -> Option<Option<Reais>> {
+    if valor == "-" {
+        Some(None)
+    } else {
+        Reais::de_texto(valor)
+            .filter(|p| *p >= Reais::ZERO)
+            .map(Some)
+    }
+}
+
+fn abrir_cadastro(caminho: &str) -> Option<Cadastro> {
+    match Cadastro::abrir(caminho) {
+        Ok(cadastro) => Some(cadastro),
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                cadastro::caminho_do_cadastro(caminho),
+                e
+            );
+            None
+        }
+    }
+}
+
+// A mudança vai para a auditoria antes de o cadastro ser gravado
+fn gravar(caminho: &str, sessao: &Sessao, cadastro: &Cadastro, descricao: String) -> ExitCode {
+    let resultado = crate::auditoria::registrar(caminho, sessao, "cadastro", descricao.clone())
+        .and_then(|()| cadastro.salvar());
+    match resultado {
+        Ok(()) => {
+            println!("✅ {}", descricao);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Cadastro não gravado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn niveis_em_texto(niveis: &Niveis) -> String {
+    let formatar = |n: Option<f64>| n.map_or("-".to_string(), estoque::formatar_quantidade);
+    format!(
+        "mínimo {}, máximo {}",
+        formatar(niveis.minimo),
+        formatar(niveis.maximo)
+    )
+}
+
+// 🔹 megastore produto: mostra ou altera os dados complementares do produto
+// (preço, categoria, fornecedor, estoque mínimo e máximo)
+pub fn produto(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = crate::editar::separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let mut codigo = None;
+    let mut preco = None;
+    let mut custo = None;
+    let mut gtin = None;
+    let mut categoria = None;
+    let mut fornecedor = None;
+    let mut minimo = None;
+    let mut maximo = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--preco" => args
+                .next()
+                .and_then(|v| preco_ou_apagar(v))
+                .map(|p| preco = Some(p)),
+            "--custo" => args
+                .next()
+                .and_then(|v| preco_ou_apagar(v))
+                .map(|c| custo = Some(c)),
+            "--gtin" => args.next().map(|v| gtin = Some(texto_ou_apagar(v))),
+            "--categoria" => args.next().map(|v| categoria = Some(texto_ou_apagar(v))),
+            "--fornecedor" => args.next().map(|v| fornecedor = Some(texto_ou_apagar(v))),
+            "--minimo" => args
+                .next()
+                .and_then(|v| quantidade_ou_apagar(v))
+                .map(|q| minimo = Some(q)),
+            "--maximo" => args
+                .next()
+                .and_then(|v| quantidade_ou_apagar(v))
+                .map(|q| maximo = Some(q)),
+            a if a.starts_with("--") || codigo.is_some() => None,
+            a => {
+                codigo = Some(a.to_string());
+                Some(())
+            }
+        }
+        .is_some();
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_PRODUTO);
+            return ExitCode::from(2);
+        }
+    }
+    let Some(codigo) = codigo else {
+        println!("{}", USO_PRODUTO);
+        return ExitCode::from(2);
+    };
+    if let Some(Some(texto)) = &categoria {
+        match categorias::normalizar(texto) {
+            Ok(normalizada) => categoria = Some(Some(normalizada)),
+            Err(motivo) => {
+                println!("⚠️ {}", motivo);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let alterar = preco.is_some()
+        || custo.is_some()
+        || gtin.is_some()
+        || categoria.is_some()
+        || fornecedor.is_some()
+        || minimo.is_some()
+        || maximo.is_some();
+    if !alterar {
+        return mostrar(&caminho, &codigo, sessao);
+    }
+
+    // As mudanças vão pelo diário, como as do nome: ficam no histórico (e
+    // podem ser desfeitas) e na auditoria com o produto antes e depois
+    let Some(vivo) = crate::editar::abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+    let catalogo = vivo.atual();
+    let Some(id) = catalogo.buscar_por_codigo(&codigo) else {
+        println!("Nenhum produto encontrado com este código.");
+        return ExitCode::from(1);
+    };
+    let permissao = if preco.is_some() || custo.is_some() {
+        Permissao::AlterarPreco
+    } else {
+        Permissao::Cadastrar
+    };
+    if let Err(motivo) = sessao.exigir(permissao) {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+
+    let mut produto = catalogo.produto(id);
+    let complemento = &mut produto.complemento;
+    if let Some(gtin) = gtin {
+        let conferido = gtin.map(|g| gtin::validar(&g)).transpose().and_then(|g| {
+            if let Some(g) = &g {
+                gtin::conferir_dono(&catalogo, &codigo, g)?;
+            }
+            Ok(g)
+        });
+        match conferido {
+            Ok(gtin) => complemento.gtin = gtin,
+            Err(motivo) => {
+                println!("⚠️ {}", motivo);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if let Some(preco) = preco {
+        complemento.preco = preco;
+    }
+    if let Some(custo) = custo {
+        complemento.custo = custo;
+    }
+    if let Some(categoria) = categoria {
+        complemento.categoria = categoria;
+    }
+    if let Some(fornecedor) = fornecedor {
+        complemento.fornecedor = fornecedor;
+    }
+    if let Some(minimo) = minimo {
+        complemento.niveis.minimo = minimo;
+    }
+    if let Some(maximo) = maximo {
+        complemento.niveis.maximo = maximo;
+    }
+    if let Err(motivo) = complemento.niveis.validar() {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+
+    let descricao = format!(
+        "[{}] preço {}, custo {}, código de barras {}, categoria {}, fornecedor {}, {}",
+        codigo,
+        complemento
+            .preco
+            .map_or("-".to_string(), |p| p.com_simbolo()),
+        complemento
+            .custo
+            .map_or("-".to_string(), |c| c.com_simbolo()),
+        complemento.gtin.as_deref().unwrap_or("-"),
+        complemento.categoria.as_deref().unwrap_or("-"),
+        complemento.fornecedor.as_deref().unwrap_or("-"),
+        niveis_em_texto(&complemento.niveis)
+    );
+    match vivo.substituir(&descricao, vec![produto]) {
+        Ok(0) => {
+            println!("Nada mudou no produto [{}].", codigo);
+            ExitCode::SUCCESS
+        }
+        Ok(_) => {
+            println!("✅ {}", descricao);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Cadastro não gravado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Dados complementares do produto, com os níveis que valem para ele
+fn mostrar(caminho: &str, codigo: &str, sessao: &Sessao) -> ExitCode {
+    let catalogo = match Catalogo::abrir(caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+    let Some(id) = catalogo.buscar_por_codigo(codigo) else {
+        println!("Nenhum produto encontrado com este código.");
+        return ExitCode::from(1);
+    };
+    let Some(cadastro) = abrir_cadastro(caminho) else {
+        return ExitCode::from(2);
+    };
+
+    let complemento = catalogo.complemento(id);
+    println!("[{}] {}", codigo, catalogo.nome(id));
+    println!(
+        "  Preço: {}",
+        complemento
+            .preco
+            .map_or("-".to_string(), |p| p.com_simbolo())
+    );
+    // Custo e margem só para quem pode mexer no preço
+    if sessao.pode(Permissao::AlterarPreco) {
+        println!(
+            "  Custo: {}",
+            complemento.custo.map_or("-".to_string(), |c| {
+                margem::resumo_do_custo(c, complemento.preco, MARKUP_PADRAO)
+            })
+        );
+    }
+    println!(
+        "  Código de barras: {}",
+        complemento.gtin.as_deref().unwrap_or("-")
+    );
+    println!(
+        "  Categoria: {}",
+        complemento.categoria.as_deref().unwrap_or("-")
+    );
+    println!(
+        "  Fornecedor: {}",
+        complemento.fornecedor.as_deref().unwrap_or("-")
+    );
+    println!(
+        "  Estoque: {} (valendo: {})",
+        niveis_em_texto(&complemento.niveis),
+        niveis_em_texto(&cadastro.niveis(complemento))
+    );
+    ExitCode::SUCCESS
+}
+
+// 🔹 megastore categoria: níveis de estoque que valem para os produtos da
+// categoria que não têm níveis próprios
+pub fn categoria(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = crate::editar::separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let Some((categoria, opcoes)) = args.split_first() else {
+        println!("{}", USO_CATEGORIA);
+        return ExitCode::from(2);
+    };
+    let categoria = match categorias::normalizar(categoria) {
+        Ok(categoria) => categoria,
+        Err(motivo) => {
+            println!("⚠️ {}", motivo);
+            return ExitCode::from(2);
+        }
+    };
+    let Some(mut cadastro) = abrir_cadastro(&caminho) else {
+        return ExitCode::from(2);
+    };
+    let mut niveis = cadastro.niveis_da_categoria(&categoria);
+
+    let mut opcoes = opcoes.iter();
+    let mut alterar = false;
+    while let Some(arg) = opcoes.next() {
+        let valor = opcoes.next().and_then(|v| quantidade_ou_apagar(v));
+        let ok = match (arg.as_str(), valor) {
+            ("--minimo", Some(q)) => {
+                niveis.minimo = q;
+                true
+            }
+            ("--maximo", Some(q)) => {
+                niveis.maximo = q;
+                true
+            }
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_CATEGORIA);
+            return ExitCode::from(2);
+        }
+        alterar = true;
+    }
+
+    if !alterar {
+        println!("Categoria '{}': {}", categoria, niveis_em_texto(&niveis));
+        return ExitCode::SUCCESS;
+    }
+    if let Err(motivo) = sessao.exigir(Permissao::Cadastrar) {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+    if let Err(motivo) = niveis.validar() {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+
+    cadastro.definir_niveis_da_categoria(&categoria, niveis);
+    let descricao = format!("categoria '{}': {}", categoria, niveis_em_texto(&niveis));
+    gravar(&caminho, sessao, &cadastro, descricao)
+}
@@ -0,0 +1,165 @@
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::categorias::{self, No};
+use projeto_rust::operadores::Sessao;
+use projeto_rust::produto::Produto;
+
+use crate::selecao::Selecao;
+
+const USO_CATEGORIAS: &str =
+    "Uso: megastore categorias [--catalogo produtos.txt] [categoria] [--produtos]";
+
+const USO_CATEGORIZAR: &str = "Uso: megastore categorizar [--catalogo produtos.txt]
+        [--busca termo | --prefixo texto | --letra L | --de código --ate código
+        | <código>...] [--categoria nome]
+        --para \"Departamento > Categoria > Subcategoria\"|-";
+
+// 🔹 megastore categorias: a árvore de categorias com quantos produtos há em
+// cada uma, inteira ou a partir de uma categoria, e com --produtos também os
+// produtos. Sai com 1 se a categoria não existe.
+pub fn executar(args: &[String]) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut categoria = None;
+    let mut com_produtos = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--produtos" => {
+                com_produtos = true;
+                true
+            }
+            a if a.starts_with("--") || categoria.is_some() => false,
+            a => {
+                categoria = Some(a.to_string());
+                true
+            }
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_CATEGORIAS);
+            return ExitCode::from(2);
+        }
+    }
+
+    let inicio = Instant::now();
+    let Some(catalogo) = abrir(&caminho) else {
+        return ExitCode::from(2);
+    };
+    let arvore = No::montar(&catalogo);
+    let Some(no) = arvore.buscar(categoria.as_deref().unwrap_or_default()) else {
+        println!("Nenhuma categoria '{}'.", categoria.unwrap_or_default());
+        return ExitCode::from(1);
+    };
+
+    if let Err(e) = categorias::escrever_arvore(io::stdout().lock(), no, &catalogo, com_produtos) {
+        println!("⚠️ Não foi possível mostrar as categorias: {}", e);
+        return ExitCode::from(2);
+    }
+    let duracao = inicio.elapsed().as_secs_f64();
+    if no.raiz() {
+        println!(
+            "✅ {} produtos, {} sem categoria. Operação concluída em {:.6} segundos.",
+            no.total,
+            no.produtos.len(),
+            duracao
+        );
+    } else {
+        println!(
+            "✅ {} produtos em '{}'. Operação concluída em {:.6} segundos.",
+            no.total, no.caminho, duracao
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+// 🔹 megastore categorizar: põe os produtos escolhidos numa categoria (ou
+// tira, com "-"), de uma vez. Sai com 1 se nenhum produto muda.
+pub fn categorizar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut selecao = Selecao::default();
+    let mut para = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--para" => args.next().map(|v| para = Some(v.clone())).is_some(),
+            a => selecao.ler(a, &mut args),
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_CATEGORIZAR);
+            return ExitCode::from(2);
+        }
+    }
+    // Como no reajuste, o catálogo inteiro precisa ser pedido com uma faixa
+    let Some(para) = para.filter(|_| !selecao.vazia()) else {
+        println!("{}", USO_CATEGORIZAR);
+        return ExitCode::from(2);
+    };
+    let nova = match para.as_str() {
+        "-" => None,
+        texto => match categorias::normalizar(texto) {
+            Ok(categoria) => Some(categoria),
+            Err(motivo) => {
+                println!("⚠️ {}", motivo);
+                return ExitCode::from(2);
+            }
+        },
+    };
+
+    let inicio = Instant::now();
+    let Some(vivo) = crate::editar::abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+    let catalogo = vivo.atual();
+    let produtos: Vec<Produto> = selecao
+        .produtos(&catalogo)
+        .into_iter()
+        .filter(|&id| catalogo.categoria(id) != nova.as_deref())
+        .map(|id| {
+            let mut produto = catalogo.produto(id);
+            produto.complemento.categoria = nova.clone();
+            produto
+        })
+        .collect();
+    if produtos.is_empty() {
+        println!("Nenhum produto muda de categoria.");
+        return ExitCode::from(1);
+    }
+
+    // Uma operação só no histórico: o desfazer volta todos de uma vez
+    let descricao = format!("categoria {}", nova.as_deref().unwrap_or("-"));
+    match vivo.substituir(&descricao, produtos) {
+        Ok(total) => {
+            println!(
+                "✅ {} produtos em '{}'. Operação concluída em {:.6} segundos.",
+                total,
+                nova.as_deref().unwrap_or("(sem categoria)"),
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Categorias não gravadas: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn abrir(caminho: &str) -> Option<Catalogo> {
+    match Catalogo::abrir(caminho, None) {
+        Ok(catalogo) => Some(catalogo),
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            None
+        }
+    }
+}
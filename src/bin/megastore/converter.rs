@@ -0,0 +1,56 @@
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::formato::Formato;
+use projeto_rust::produto::{ler_produtos, salvar_produtos};
+
+// 🔹 megastore converter: regrava o catálogo em outro formato ("codigo;nome",
+// JSON, JSON Lines ou banco SQLite, pela extensão do destino)
+pub fn executar(args: &[String]) -> ExitCode {
+    let Some((arquivos, codificacao)) = crate::separar_codificacao(args) else {
+        return ExitCode::from(2);
+    };
+    let [origem, destino] = arquivos.as_slice() else {
+        println!(
+            "Uso: megastore converter <origem> <destino.txt|.json|.jsonl|.db> [--encoding windows-1252]"
+        );
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let leitura = match ler_produtos(origem, codificacao) {
+        Ok(leitura) => leitura,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", origem, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    leitura.conversao.relatar(origem);
+    for linha in &leitura.invalidas {
+        println!(
+            "  ⚠️ linha {} ignorada ({}): {}",
+            linha.numero, linha.motivo, linha.conteudo
+        );
+    }
+
+    let formato = Formato::de_caminho(destino).unwrap_or_default();
+    if formato == Formato::Texto && leitura.produtos.iter().any(|p| !p.extras.is_empty()) {
+        println!(
+            "⚠️ Os campos extras do JSON não cabem no formato \"codigo;nome\" e ficaram de fora."
+        );
+    }
+
+    if let Err(e) = salvar_produtos(destino, &leitura.produtos) {
+        println!("⚠️ Erro ao gravar '{}': {}", destino, e);
+        return ExitCode::from(2);
+    }
+
+    println!(
+        "✅ {} produtos gravados em '{}'. Operação concluída em {:.6} segundos.",
+        leitura.produtos.len(),
+        destino,
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
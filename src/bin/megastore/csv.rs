@@ -0,0 +1,215 @@
+use std::fs::File;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::{Catalogo, IdProduto};
+use projeto_rust::categorias;
+use projeto_rust::csv::{self, Colunas};
+use projeto_rust::operadores::Sessao;
+use projeto_rust::produto::salvar_produtos;
+use projeto_rust::texto::comparar_sem_caixa;
+
+const USO_IMPORTAR: &str =
+    "Uso: megastore importar-csv <planilha.csv> [--col-codigo codigo] [--col-nome nome]
+                             [--delimitador ';'] [--saida produtos_importados.txt]
+                             [--encoding windows-1252]";
+
+const USO_EXPORTAR: &str =
+    "Uso: megastore exportar-csv [--catalogo produtos.txt] [--busca termo | --prefixo texto]
+                             [--categoria nome] [--ordem codigo|nome] [--delimitador ';'] [--saida produtos.csv]
+                             [--sem-bom] [--encoding windows-1252]";
+
+// 🔹 megastore importar-csv: converte a planilha do fornecedor em "codigo;nome"
+pub fn importar(args: &[String]) -> ExitCode {
+    let Some((args, codificacao)) = crate::separar_codificacao(args) else {
+        return ExitCode::from(2);
+    };
+    let mut planilha = None;
+    let mut colunas = Colunas::default();
+    let mut delimitador = None;
+    let mut saida = "produtos_importados.txt".to_string();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--col-codigo" => args.next().map(|v| colunas.codigo = v.clone()).is_some(),
+            "--col-nome" => args.next().map(|v| colunas.nome = v.clone()).is_some(),
+            "--delimitador" => args
+                .next()
+                .and_then(|v| ler_delimitador(v))
+                .map(|d| delimitador = Some(d))
+                .is_some(),
+            "--saida" => args.next().map(|v| saida = v.clone()).is_some(),
+            _ if arg.starts_with("--") || planilha.is_some() => false,
+            _ => {
+                planilha = Some(arg.clone());
+                true
+            }
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_IMPORTAR);
+            return ExitCode::from(2);
+        }
+    }
+
+    let Some(planilha) = planilha else {
+        println!("{}", USO_IMPORTAR);
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let leitura = match csv::ler_csv(&planilha, codificacao, delimitador, &colunas) {
+        Ok(leitura) => leitura,
+        Err(e) => {
+            println!("⚠️ Não foi possível importar '{}': {}", planilha, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    leitura.conversao.relatar(&planilha);
+    for linha in &leitura.invalidas {
+        println!(
+            "  ⚠️ linha {} ignorada ({}): {}",
+            linha.numero, linha.motivo, linha.conteudo
+        );
+    }
+
+    if let Err(e) = salvar_produtos(&saida, &leitura.produtos) {
+        println!("⚠️ Erro ao gravar '{}': {}", saida, e);
+        return ExitCode::from(2);
+    }
+
+    println!(
+        "✅ {} produtos importados para '{}' ({} linhas ignoradas). Operação concluída em {:.6} segundos.",
+        leitura.produtos.len(),
+        saida,
+        leitura.invalidas.len(),
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
+
+// 🔹 megastore exportar-csv: grava o catálogo inteiro ou o resultado de uma
+// busca, só da categoria se pedido
+pub fn exportar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((args, codificacao)) = crate::separar_codificacao(args) else {
+        return ExitCode::from(2);
+    };
+    let mut caminho = "produtos.txt".to_string();
+    let mut busca = None;
+    let mut prefixo = None;
+    let mut categoria = None;
+    let mut por_nome = false;
+    let mut delimitador = ';';
+    let mut saida = "produtos.csv".to_string();
+    let mut bom = true;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--busca" => args.next().map(|v| busca = Some(v.clone())).is_some(),
+            "--prefixo" => args.next().map(|v| prefixo = Some(v.clone())).is_some(),
+            "--categoria" => args.next().map(|v| categoria = Some(v.clone())).is_some(),
+            "--ordem" => match args.next().map(String::as_str) {
+                Some("codigo" | "código") => {
+                    por_nome = false;
+                    true
+                }
+                Some("nome") => {
+                    por_nome = true;
+                    true
+                }
+                _ => false,
+            },
+            "--delimitador" => args
+                .next()
+                .and_then(|v| ler_delimitador(v))
+                .map(|d| delimitador = d)
+                .is_some(),
+            "--saida" => args.next().map(|v| saida = v.clone()).is_some(),
+            "--sem-bom" => {
+                bom = false;
+                true
+            }
+            _ => false,
+        };
+
+        if !ok || (busca.is_some() && prefixo.is_some()) {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_EXPORTAR);
+            return ExitCode::from(2);
+        }
+    }
+
+    let inicio = Instant::now();
+    let catalogo = match Catalogo::abrir(&caminho, codificacao) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+    catalogo.conversao().relatar(&caminho);
+
+    let mut ids: Vec<IdProduto> = match (&busca, &prefixo) {
+        (Some(termo), _) => catalogo.buscar_por_palavra(termo),
+        (_, Some(prefixo)) => catalogo.buscar_por_prefixo(prefixo).to_vec(),
+        _ if por_nome => catalogo.ordem_por_nome().to_vec(),
+        _ => catalogo.ordem_por_codigo().to_vec(),
+    };
+    if let Some(categoria) = &categoria {
+        ids = categorias::filtrar(&catalogo, &ids, categoria);
+    }
+    if por_nome {
+        ids.sort_by(|&a, &b| comparar_sem_caixa(catalogo.nome(a), catalogo.nome(b)));
+    } else {
+        ids.sort_by(|&a, &b| catalogo.codigo(a).cmp(catalogo.codigo(b)));
+    }
+
+    let arquivo = match File::create(&saida) {
+        Ok(f) => f,
+        Err(e) => {
+            println!("⚠️ Não foi possível criar '{}': {}", saida, e);
+            return ExitCode::from(2);
+        }
+    };
+    let pares = ids
+        .iter()
+        .map(|&id| (catalogo.codigo(id), catalogo.nome(id)));
+
+    match csv::escrever_csv(arquivo, pares, delimitador, bom) {
+        Ok(total) => {
+            let descricao = format!("{} produtos exportados para '{}'", total, saida);
+            let auditado = crate::auditoria::registrar(&caminho, sessao, "exportar", descricao);
+            if let Err(e) = auditado {
+                println!("⚠️ Exportação não registrada na auditoria: {}", e);
+            }
+            println!(
+                "✅ {} produtos exportados para '{}'. Operação concluída em {:.6} segundos.",
+                total,
+                saida,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Erro ao gravar '{}': {}", saida, e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Um único caractere; "tab" e "\t" valem tabulação
+fn ler_delimitador(valor: &str) -> Option<char> {
+    match valor {
+        "tab" | "\\t" => Some('\t'),
+        _ => {
+            let mut chars = valor.chars();
+            let c = chars.next()?;
+            (chars.next().is_none() && c != '"' && c != '\n').then_some(c)
+        }
+    }
+}
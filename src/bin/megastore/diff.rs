@@ -0,0 +1,100 @@
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::codificacao::Codificacao;
+use projeto_rust::diario;
+use projeto_rust::diferencas;
+
+// 🔹 megastore diff: sai com 0 se os catálogos são iguais, 1 se há diferenças
+// e 2 em caso de erro (como o diff do sistema). Compara os arquivos como estão:
+// edições ainda no diário ("<arquivo>.log") não entram.
+pub fn executar(args: &[String]) -> ExitCode {
+    let Some((arquivos, codificacao)) = crate::separar_codificacao(args) else {
+        return ExitCode::from(2);
+    };
+    let [antigo, novo] = arquivos.as_slice() else {
+        println!("Uso: megastore diff <antigo.txt> <novo.txt> [--encoding windows-1252]");
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let (Some(a), Some(b)) = (abrir(antigo, codificacao), abrir(novo, codificacao)) else {
+        return ExitCode::from(2);
+    };
+
+    println!(
+        "Comparando '{}' ({} produtos) com '{}' ({} produtos)",
+        antigo,
+        a.len(),
+        novo,
+        b.len()
+    );
+    let diferencas = diferencas::comparar(&a, &b);
+
+    if !diferencas.adicionados.is_empty() {
+        println!("\nAdicionados ({}):", diferencas.adicionados.len());
+        for p in &diferencas.adicionados {
+            println!("  + [{}] {}", p.codigo, p.nome);
+        }
+    }
+    if !diferencas.removidos.is_empty() {
+        println!("\nRemovidos ({}):", diferencas.removidos.len());
+        for p in &diferencas.removidos {
+            println!("  - [{}] {}", p.codigo, p.nome);
+        }
+    }
+    if !diferencas.renomeados.is_empty() {
+        println!(
+            "\nRenomeados, mesmo código ({}):",
+            diferencas.renomeados.len()
+        );
+        for (antes, depois) in &diferencas.renomeados {
+            println!("  ~ [{}] {} -> {}", antes.codigo, antes.nome, depois.nome);
+        }
+    }
+    if !diferencas.recodificados.is_empty() {
+        println!(
+            "\nCom novo código, mesmo nome ({}):",
+            diferencas.recodificados.len()
+        );
+        for (antes, depois) in &diferencas.recodificados {
+            println!(
+                "  # {} [{}] -> [{}]",
+                antes.nome, antes.codigo, depois.codigo
+            );
+        }
+    }
+
+    println!(
+        "\n✅ {} diferenças encontradas. Operação concluída em {:.6} segundos.",
+        diferencas.total(),
+        inicio.elapsed().as_secs_f64()
+    );
+
+    if diferencas.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+fn abrir(caminho: &str, codificacao: Option<Codificacao>) -> Option<Catalogo> {
+    match Catalogo::abrir_sem_diario(caminho, codificacao) {
+        Ok(catalogo) => {
+            catalogo.conversao().relatar(caminho);
+            let pendentes = diario::ler(caminho).map_or(0, |r| r.alteracoes.len());
+            if pendentes > 0 {
+                println!(
+                    "⚠️ '{}' tem {} alterações no diário ainda não compactadas; elas não entram na comparação (use 'megastore compactar' antes).",
+                    caminho, pendentes
+                );
+            }
+            Some(catalogo)
+        }
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::historico;
+use projeto_rust::operadores::Sessao;
+use projeto_rust::produto::{Produto, ler_produtos};
+use projeto_rust::recarga::CatalogoVivo;
+
+// Separa "--catalogo <arquivo>" (padrão produtos.txt) dos demais argumentos
+pub fn separar_catalogo(args: &[String]) -> Option<(String, Vec<String>)> {
+    let mut caminho = "produtos.txt".to_string();
+    let mut resto = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--catalogo" {
+            caminho = args.next()?.clone();
+        } else {
+            resto.push(arg.clone());
+        }
+    }
+    Some((caminho, resto))
+}
+
+// As alterações ficam no histórico e na auditoria em nome do operador da sessão
+pub fn abrir(caminho: &str, sessao: &Sessao) -> Option<CatalogoVivo> {
+    match CatalogoVivo::abrir(caminho, None) {
+        Ok(vivo) => {
+            vivo.recuperacao().relatar(caminho);
+            vivo.definir_operador(&sessao.nome);
+            Some(vivo)
+        }
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            None
+        }
+    }
+}
+
+// 🔹 megastore gravar: inclui o produto ou troca o nome do que tem o código
+pub fn gravar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let Some((codigo, nome)) = args.split_first().filter(|(_, nome)| !nome.is_empty()) else {
+        println!("Uso: megastore gravar [--catalogo produtos.txt] <código> <nome>");
+        return ExitCode::from(2);
+    };
+    let nome = nome.join(" ");
+    let Some(vivo) = abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    match vivo.gravar(Produto::new(codigo, &nome)) {
+        Ok(()) => {
+            println!(
+                "✅ Produto [{}] {} gravado. Operação concluída em {:.6} segundos.",
+                codigo,
+                nome,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Produto não gravado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// 🔹 megastore remover: sai com 1 se não havia produto com o código
+pub fn remover(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let [codigo] = args.as_slice() else {
+        println!("Uso: megastore remover [--catalogo produtos.txt] <código>");
+        return ExitCode::from(2);
+    };
+    let Some(vivo) = abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    match vivo.remover(codigo) {
+        Ok(true) => {
+            println!(
+                "✅ Produto [{}] removido. Operação concluída em {:.6} segundos.",
+                codigo,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Ok(false) => {
+            println!("Nenhum produto encontrado com este código.");
+            ExitCode::from(1)
+        }
+        Err(e) => {
+            println!("⚠️ Produto não removido: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// 🔹 megastore compactar: regrava o catálogo com as alterações do diário
+pub fn compactar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    if !args.is_empty() {
+        println!("Uso: megastore compactar [--catalogo produtos.txt]");
+        return ExitCode::from(2);
+    }
+    let Some(vivo) = abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    match vivo.compactar() {
+        Ok(total) => {
+            println!(
+                "✅ {} alterações gravadas em '{}'. Operação concluída em {:.6} segundos.",
+                total,
+                caminho,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Erro ao compactar '{}': {}", caminho, e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// 🔹 megastore atualizar: grava todos os produtos da lista numa operação só
+// (novos códigos são incluídos e os demais, renomeados)
+pub fn atualizar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let [lista] = args.as_slice() else {
+        println!("Uso: megastore atualizar [--catalogo produtos.txt] <lista.txt|.json|.jsonl>");
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let leitura = match ler_produtos(lista, None) {
+        Ok(leitura) => leitura,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", lista, e);
+            return ExitCode::from(2);
+        }
+    };
+    for linha in &leitura.invalidas {
+        println!(
+            "  ⚠️ linha {} ignorada ({}): {}",
+            linha.numero, linha.motivo, linha.conteudo
+        );
+    }
+    let Some(vivo) = abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+
+    let descricao = format!("atualização com '{}'", lista);
+    match vivo.gravar_lote(&descricao, leitura.produtos) {
+        Ok(total) => {
+            println!(
+                "✅ {} produtos incluídos ou renomeados. Operação concluída em {:.6} segundos.",
+                total,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Nenhum produto gravado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// 🔹 megastore desfazer / refazer: sai com 1 se não havia o que desfazer ou refazer
+pub fn desfazer(args: &[String], sessao: &Sessao, refazer: bool) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let comando = if refazer { "refazer" } else { "desfazer" };
+    if !args.is_empty() {
+        println!("Uso: megastore {} [--catalogo produtos.txt]", comando);
+        return ExitCode::from(2);
+    }
+    let Some(vivo) = abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+
+    let resultado = if refazer {
+        vivo.refazer()
+    } else {
+        vivo.desfazer()
+    };
+    match resultado {
+        Ok(Some(operacao)) => {
+            let feito = if refazer { "Refeito" } else { "Desfeito" };
+            println!("✅ {}: {}", feito, operacao.descricao);
+            ExitCode::SUCCESS
+        }
+        Ok(None) => {
+            println!("Nada a {}.", comando);
+            ExitCode::from(1)
+        }
+        Err(e) => {
+            println!("⚠️ Não foi possível {}: {}", comando, e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// 🔹 megastore historico: todos os nomes que o código já teve, com data e hora
+pub fn historico(args: &[String]) -> ExitCode {
+    let Some((caminho, args)) = separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let [codigo] = args.as_slice() else {
+        println!("Uso: megastore historico [--catalogo produtos.txt] <código>");
+        return ExitCode::from(2);
+    };
+
+    let nomes = match historico::nomes_do_produto(&caminho, codigo) {
+        Ok(nomes) => nomes,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o histórico: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if nomes.is_empty() {
+        println!("Nenhuma alteração registrada para o código '{}'.", codigo);
+        return ExitCode::from(1);
+    }
+
+    for n in nomes {
+        println!(
+            "{}  {}  {}  ({})",
+            n.quando.format("%d/%m/%Y %H:%M:%S"),
+            if n.operador.is_empty() {
+                "-"
+            } else {
+                &n.operador
+            },
+            n.nome.as_deref().unwrap_or("(removido)"),
+            n.motivo
+        );
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,200 @@
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chrono::Local;
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::categorias;
+use projeto_rust::estoque::{self, Estoque, TipoMovimento};
+use projeto_rust::operadores::{Permissao, Sessao};
+use projeto_rust::orcamento;
+
+const USO_ESTOQUE: &str = "Uso: megastore estoque [--catalogo produtos.txt] lancar <código> entrada|saida|ajuste|devolucao <quantidade> [motivo...]
+       megastore estoque [--catalogo produtos.txt] ficha <código>
+       megastore estoque [--catalogo produtos.txt] saldos [--categoria nome]";
+
+// 🔹 megastore estoque: lança movimentos, mostra a ficha de um produto ou os
+// saldos de todos (ou só os de uma categoria)
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = crate::editar::separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["lancar" | "lançar", codigo, tipo, quantidade, motivo @ ..] => {
+            if let Err(motivo) = sessao.exigir(Permissao::MovimentarEstoque) {
+                println!("⚠️ {}", motivo);
+                return ExitCode::from(2);
+            }
+            let Some(tipo) = TipoMovimento::de_nome(tipo) else {
+                println!("⚠️ Tipo inválido. Use entrada, saida, ajuste ou devolucao.");
+                return ExitCode::from(2);
+            };
+            let Some(quantidade) = estoque::ler_quantidade(quantidade) else {
+                println!("⚠️ Quantidade inválida: '{}'", quantidade);
+                return ExitCode::from(2);
+            };
+            lancar(
+                &caminho,
+                sessao,
+                codigo,
+                tipo,
+                quantidade,
+                &motivo.join(" "),
+            )
+        }
+        ["ficha", codigo] => ficha(&caminho, codigo),
+        ["saldos"] => saldos(&caminho, None),
+        ["saldos", "--categoria", categoria] => saldos(&caminho, Some(categoria)),
+        _ => {
+            println!("{}", USO_ESTOQUE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// O código precisa estar no catálogo; o movimento vai antes para a auditoria
+fn lancar(
+    caminho: &str,
+    sessao: &Sessao,
+    codigo: &str,
+    tipo: TipoMovimento,
+    quantidade: f64,
+    motivo: &str,
+) -> ExitCode {
+    let inicio = Instant::now();
+    let catalogo = match Catalogo::abrir(caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+    if catalogo.buscar_por_codigo(codigo).is_none() {
+        println!("Nenhum produto encontrado com este código.");
+        return ExitCode::from(1);
+    }
+
+    let descricao = format!(
+        "[{}] {} de {} ({})",
+        codigo,
+        tipo.nome(),
+        estoque::formatar_quantidade(quantidade),
+        motivo
+    );
+    let resultado = crate::auditoria::registrar(caminho, sessao, "estoque", descricao)
+        .and_then(|()| Estoque::abrir(caminho))
+        .and_then(|mut e| e.lancar(&sessao.nome, codigo, tipo, quantidade, motivo));
+    match resultado {
+        Ok(saldo) => {
+            if saldo < 0.0 {
+                println!("⚠️ O saldo ficou negativo: confira o estoque do produto.");
+            }
+            println!(
+                "✅ Movimento de {} lançado. Novo saldo: {}. Operação concluída em {:.6} segundos.",
+                tipo.nome(),
+                estoque::formatar_quantidade(saldo),
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Movimento não lançado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn ficha(caminho: &str, codigo: &str) -> ExitCode {
+    let ficha = match estoque::ficha(caminho, codigo) {
+        Ok(ficha) => ficha,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if ficha.is_empty() {
+        println!("Nenhum movimento de estoque para o código '{}'.", codigo);
+        return ExitCode::from(1);
+    }
+
+    for linha in &ficha {
+        let m = &linha.movimento;
+        println!(
+            "{}  {:<10} {:>10}  saldo {:>10}  {}  ({})",
+            m.quando.format("%d/%m/%Y %H:%M:%S"),
+            m.tipo.nome(),
+            estoque::formatar_quantidade(m.quantidade),
+            estoque::formatar_quantidade(linha.saldo),
+            m.operador,
+            m.motivo
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+// Saldo de cada produto que já teve movimento, por código
+fn saldos(caminho: &str, categoria: Option<&str>) -> ExitCode {
+    let catalogo = match Catalogo::abrir(caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+    let mut estoque = match Estoque::abrir(caminho) {
+        Ok(estoque) => estoque,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let mut saldos: Vec<(String, f64)> = match estoque.saldos() {
+        Ok(saldos) => saldos.iter().map(|(c, &s)| (c.clone(), s)).collect(),
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    saldos.sort_by(|a, b| a.0.cmp(&b.0));
+    if let Some(categoria) = categoria {
+        saldos.retain(|(codigo, _)| {
+            let da_categoria = catalogo
+                .buscar_por_codigo(codigo)
+                .and_then(|id| catalogo.categoria(id));
+            categorias::pertence(da_categoria, categoria)
+        });
+    }
+    let reservado = match orcamento::reservado(caminho, Local::now().date_naive()) {
+        Ok(reservado) => reservado,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler os orçamentos: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    for (codigo, saldo) in &saldos {
+        let nome = catalogo
+            .buscar_por_codigo(codigo)
+            .map_or("(fora do catálogo)", |id| catalogo.nome(id));
+        match reservado.get(codigo) {
+            Some(&reservado) => println!(
+                "{} - {}: {} (reservado {}, disponível {})",
+                codigo,
+                nome,
+                estoque::formatar_quantidade(*saldo),
+                estoque::formatar_quantidade(reservado),
+                estoque::formatar_quantidade(saldo - reservado)
+            ),
+            None => println!(
+                "{} - {}: {}",
+                codigo,
+                nome,
+                estoque::formatar_quantidade(*saldo)
+            ),
+        }
+    }
+    println!("✅ {} produtos com movimento de estoque.", saldos.len());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::etiquetas::{self, Etiqueta};
+use projeto_rust::operadores::Sessao;
+
+use crate::selecao::Selecao;
+
+const USO_ETIQUETAS: &str = "Uso: megastore etiquetas [--catalogo produtos.txt]
+        [--busca termo | --prefixo texto | --letra L | --de código --ate código
+        | <código>...] [--categoria nome]
+        [--saida etiquetas.zpl|etiquetas.svg|etiquetas.pdf]";
+
+// 🔹 megastore etiquetas: etiquetas de gôndola dos produtos escolhidos, em ZPL
+// ou em folhas A4, num PDF só ou em SVG (uma por arquivo: etiquetas.svg,
+// etiquetas_2.svg...). Sai com 1 se nenhum produto foi escolhido.
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut selecao = Selecao::default();
+    let mut saida = "etiquetas.zpl".to_string();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--saida" => args.next().map(|v| saida = v.clone()).is_some(),
+            a => selecao.ler(a, &mut args),
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_ETIQUETAS);
+            return ExitCode::from(2);
+        }
+    }
+    let extensao = saida.to_lowercase();
+    let Some(formato) = [".zpl", ".svg", ".pdf"]
+        .into_iter()
+        .find(|e| extensao.ends_with(e))
+    else {
+        println!("⚠️ A saída precisa terminar em .zpl, .svg ou .pdf.");
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let catalogo = match Catalogo::abrir(&caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let ids = selecao.produtos(&catalogo);
+    if ids.is_empty() {
+        println!("Nenhum produto escolhido.");
+        return ExitCode::from(1);
+    }
+
+    let lista: Vec<Etiqueta> = ids
+        .iter()
+        .map(|&id| Etiqueta::do_produto(&catalogo, id))
+        .collect();
+    let sem_preco = lista.iter().filter(|e| e.preco.is_none()).count();
+
+    let arquivos: Vec<(String, Vec<u8>)> = match formato {
+        ".svg" => {
+            let folhas = etiquetas::svg(&lista);
+            let base = &saida[..saida.len() - ".svg".len()];
+            folhas
+                .into_iter()
+                .enumerate()
+                .map(|(i, folha)| match i {
+                    0 => (saida.clone(), folha.into_bytes()),
+                    _ => (format!("{}_{}.svg", base, i + 1), folha.into_bytes()),
+                })
+                .collect()
+        }
+        ".pdf" => vec![(saida.clone(), etiquetas::pdf(&lista))],
+        _ => vec![(saida.clone(), etiquetas::zpl(&lista).into_bytes())],
+    };
+    for (arquivo, conteudo) in &arquivos {
+        if let Err(e) = fs::write(arquivo, conteudo) {
+            println!("⚠️ Não foi possível gravar '{}': {}", arquivo, e);
+            return ExitCode::from(2);
+        }
+    }
+
+    let descricao = format!("{} etiquetas gravadas em '{}'", lista.len(), saida);
+    let auditado = crate::auditoria::registrar(&caminho, sessao, "exportar", descricao);
+    if let Err(e) = auditado {
+        println!("⚠️ Etiquetas não registradas na auditoria: {}", e);
+    }
+
+    if sem_preco > 0 {
+        println!(
+            "⚠️ {} produtos sem preço cadastrado (etiqueta sem preço).",
+            sem_preco
+        );
+    }
+    let nomes: Vec<&str> = arquivos.iter().map(|(a, _)| a.as_str()).collect();
+    println!(
+        "✅ {} etiquetas gravadas em {}. Operação concluída em {:.6} segundos.",
+        lista.len(),
+        nomes.join(", "),
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
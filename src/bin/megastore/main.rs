@@ -0,0 +1,201 @@
+// 🔹 Ferramentas de linha de comando para manutenção do catálogo
+mod auditoria;
+mod buscar;
+mod cadastro;
+mod categorias;
+mod converter;
+mod csv;
+mod diff;
+mod editar;
+mod estoque;
+mod etiquetas;
+mod margens;
+mod merge;
+mod operador;
+mod orcamento;
+mod pix;
+mod reajuste;
+mod reposicao;
+mod selecao;
+mod venda;
+
+use std::env;
+use std::process::ExitCode;
+
+use projeto_rust::codificacao::Codificacao;
+
+const USO: &str = "Uso: megastore <comando> [argumentos]
+
+Comandos:
+  diff <antigo.txt> <novo.txt> [--encoding windows-1252]
+      Mostra produtos adicionados, removidos, renomeados (mesmo código)
+      e com novo código (mesmo nome); compara os arquivos como estão, sem as
+      edições ainda no diário (.log)
+  merge <a.txt> <b.txt> [...] [--saida catalogo_mesclado.txt]
+        [--conflito primeiro|ultimo|falhar] [--mesmo-nome manter|descartar]
+        [--encoding windows-1252]
+      Junta catálogos num arquivo ordenado por código
+  importar-csv <planilha.csv> [--col-codigo SKU] [--col-nome Descricao]
+        [--delimitador ';'] [--saida produtos_importados.txt]
+      Converte uma planilha CSV em catálogo no formato codigo;nome
+  exportar-csv [--catalogo produtos.txt] [--busca termo | --prefixo texto]
+        [--categoria nome] [--ordem codigo|nome] [--delimitador ';'] [--saida produtos.csv] [--sem-bom]
+      Grava o catálogo, ou o resultado de uma busca, em CSV
+  etiquetas [--catalogo produtos.txt] <produtos> [--saida etiquetas.zpl|.svg|.pdf]
+      Etiquetas de gôndola com preço e código de barras, em ZPL ou folhas A4 em
+      SVG ou PDF
+  reajuste [--catalogo produtos.txt] <produtos> --variacao +10%|-5%|+1,50
+        [--final 90|99] [--sim]
+      Muda o preço dos produtos em lote, com prévia antes de gravar
+  converter <origem> <destino.txt|.json|.jsonl|.db>
+      Regrava o catálogo em outro formato
+  buscar [--catalogo produtos.db] (--codigo <código> | <termo>...) [--categoria nome]
+      Procura produtos pelo código (o interno ou o de barras) ou por palavras do
+      nome, só na categoria se pedido
+  gravar [--catalogo produtos.txt] <código> <nome>
+      Inclui o produto ou troca o nome do que tem o código
+  remover [--catalogo produtos.txt] <código>
+      Remove o produto
+  atualizar [--catalogo produtos.txt] <lista>
+      Inclui ou renomeia todos os produtos da lista numa única operação
+  desfazer | refazer [--catalogo produtos.txt]
+      Desfaz a última operação (ou refaz a última desfeita)
+  historico [--catalogo produtos.txt] <código>
+      Mostra todos os nomes que o produto já teve, com data e hora
+  compactar [--catalogo produtos.txt]
+      Regrava o catálogo com as alterações guardadas no diário (.log)
+  estoque [--catalogo produtos.txt] lancar <código> entrada|saida|ajuste|devolucao
+        <quantidade> [motivo] | ficha <código> | saldos [--categoria nome]
+      Lança movimentos de estoque e mostra a ficha ou os saldos
+  produto [--catalogo produtos.txt] <código> [--preco 12,90] [--custo 7,50]
+        [--gtin 7891234567895] [--categoria nome] [--fornecedor nome] [--minimo N]
+        [--maximo N]
+      Mostra ou altera preço, custo, código de barras, categoria, fornecedor e
+      estoque mínimo e máximo
+  categoria [--catalogo produtos.txt] <categoria> [--minimo N] [--maximo N]
+      Estoque mínimo e máximo dos produtos da categoria sem níveis próprios
+  categorias [--catalogo produtos.txt] [categoria] [--produtos]
+      Árvore de departamentos, categorias e subcategorias, com quantos produtos
+      há em cada uma
+  categorizar [--catalogo produtos.txt] <produtos> --para <categoria>|-
+      Põe os produtos escolhidos numa categoria (ou tira, com \"-\")
+  margens [--catalogo produtos.txt] [--categoria nome] [--meta 30] [--markup 60]
+        [--abaixo] [--saida margens.txt|.csv]
+      Margem e markup por produto e categoria, abaixo da meta e preço sugerido
+  reposicao [--catalogo produtos.txt] [--dias 30] [--categoria nome]
+        [--saida compras.txt|.csv]
+      Produtos abaixo do mínimo e quanto comprar, agrupados por fornecedor
+  cupom [--catalogo produtos.txt] <número> [--saida cupom.txt]
+        [--escpos /dev/usb/lp0] [--colunas 48] [--pagina cp860|cp850]
+      Reimprime o cupom de uma venda, em texto ou na impressora térmica
+  pix <valor> [--txid identificador] [--saida pix.svg]
+      Código PIX \"copia e cola\" e QR code do valor, com a chave da loja (pix.json)
+  orcamento [--catalogo produtos.txt] novo [--cliente nome] [--validade 10]
+        <[quantidade*]código ou nome>... | mostrar <número> [--saida arquivo]
+        [--escpos destino] | listar | reservar <número> | vender <número>
+        [--pago valor] [--pix]
+      Orçamentos com validade e preços congelados, que viram reserva ou venda
+  verificar-auditoria [--catalogo produtos.txt]
+      Confere se alguma entrada da trilha de auditoria foi alterada ou removida
+      (a corrente não usa chave: quem pode gravar na pasta do catálogo consegue
+      regravar a trilha e a âncora inteiras sem a conferência perceber)
+  operador [--catalogo produtos.txt] adicionar <nome> caixa|estoquista|gerente
+        | senha <nome> | remover <nome> | listar
+      Cadastra as contas de operador da pasta do catálogo (a primeira precisa
+      ser de gerente)
+
+Os <produtos> de etiquetas, reajuste e categorizar são escolhidos por --busca termo,
+--prefixo texto, --letra L, --de código --ate código ou uma lista de códigos,
+e --categoria nome filtra a escolha (ou escolhe a categoria inteira).
+Categorias são caminhos \"Departamento > Categoria > Subcategoria\"; filtrar
+por um nível inclui os de baixo dele.
+
+Os catálogos podem estar em texto (codigo;nome), JSON, JSON Lines ou num banco
+SQLite (.db, só com a feature \"sqlite\"), pela extensão ou pelo conteúdo. A codificação dos arquivos é detectada; --encoding
+força utf-8, windows-1252, latin-1, utf-16le ou utf-16be.
+
+Com operadores cadastrados, todo comando pede --operador <nome> (ou
+MEGASTORE_OPERADOR) e a senha (digitada ou em MEGASTORE_SENHA), e só roda se o
+perfil do operador permitir.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((args, nome)) = operador::separar_operador(&args) else {
+        return ExitCode::from(2);
+    };
+    let Some((comando, resto)) = args.split_first() else {
+        println!("{}", USO);
+        return ExitCode::from(2);
+    };
+    // Comando desconhecido só mostra o uso, sem pedir senha
+    let Some(permissao) = operador::permissao_do_comando(comando) else {
+        println!("⚠️ Comando desconhecido: '{}'", comando);
+        println!("{}", USO);
+        return ExitCode::from(2);
+    };
+    // As contas são as da pasta do catálogo (produtos.txt sem "--catalogo")
+    let catalogo = editar::separar_catalogo(resto).map_or("produtos.txt".to_string(), |(c, _)| c);
+    let Some(sessao) = operador::identificar(nome, &catalogo) else {
+        return ExitCode::from(2);
+    };
+    if let Err(motivo) = sessao.exigir(permissao) {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+
+    match comando.as_str() {
+        "diff" => diff::executar(resto),
+        "merge" => merge::executar(resto),
+        "importar-csv" => csv::importar(resto),
+        "exportar-csv" => csv::exportar(resto, &sessao),
+        "etiquetas" => etiquetas::executar(resto, &sessao),
+        "reajuste" => reajuste::executar(resto, &sessao),
+        "converter" => converter::executar(resto),
+        "buscar" => buscar::executar(resto, &sessao),
+        "gravar" => editar::gravar(resto, &sessao),
+        "remover" => editar::remover(resto, &sessao),
+        "atualizar" => editar::atualizar(resto, &sessao),
+        "desfazer" => editar::desfazer(resto, &sessao, false),
+        "refazer" => editar::desfazer(resto, &sessao, true),
+        "historico" | "histórico" => editar::historico(resto),
+        "compactar" => editar::compactar(resto, &sessao),
+        "estoque" => estoque::executar(resto, &sessao),
+        "produto" => cadastro::produto(resto, &sessao),
+        "categoria" => cadastro::categoria(resto, &sessao),
+        "categorias" => categorias::executar(resto),
+        "categorizar" => categorias::categorizar(resto, &sessao),
+        "reposicao" | "reposição" => reposicao::executar(resto),
+        "margens" => margens::executar(resto),
+        "cupom" => venda::cupom(resto),
+        "pix" => pix::executar(resto),
+        "orcamento" | "orçamento" => orcamento::executar(resto, &sessao),
+        "verificar-auditoria" => auditoria::verificar(resto),
+        "operador" => operador::executar(resto, &sessao),
+        _ => unreachable!("comando sem permissão definida"),
+    }
+}
+
+// 🔹 Tira "--encoding <nome>" dos argumentos; None se o nome não for reconhecido
+fn separar_codificacao(args: &[String]) -> Option<(Vec<String>, Option<Codificacao>)> {
+    let mut resto = Vec::new();
+    let mut codificacao = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg != "--encoding" {
+            resto.push(arg.clone());
+            continue;
+        }
+        match args.next().and_then(|v| Codificacao::de_nome(v)) {
+            Some(c) => codificacao = Some(c),
+            None => {
+                println!(
+                    "⚠️ Codificação inválida. Use utf-8, windows-1252, latin-1, utf-16le ou utf-16be."
+                );
+                return None;
+            }
+        }
+    }
+
+    Some((resto, codificacao))
+}
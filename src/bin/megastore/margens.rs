@@ -0,0 +1,165 @@
+use std::fs::File;
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::categorias;
+use projeto_rust::csv;
+use projeto_rust::margem::{
+    self, LinhaDeMargem, MARGEM_META, MARKUP_PADRAO, VendaAbaixo, formatar_percentual,
+};
+use projeto_rust::venda;
+
+const USO_MARGENS: &str =
+    "Uso: megastore margens [--catalogo produtos.txt] [--categoria nome] [--meta 30]
+        [--markup 60] [--abaixo] [--saida margens.txt|margens.csv]";
+
+// 🔹 megastore margens: margem e markup por produto e por categoria, os
+// produtos e as vendas abaixo da meta e o preço sugerido pelo markup. Sai com
+// 1 se nenhum produto tem custo e preço (ou, com --abaixo, se nenhum está
+// abaixo da meta).
+pub fn executar(args: &[String]) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut categoria = None;
+    let mut meta = MARGEM_META;
+    let mut markup = MARKUP_PADRAO;
+    let mut so_abaixo = false;
+    let mut saida = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--categoria" => args.next().map(|v| categoria = Some(v.clone())).is_some(),
+            "--meta" => args
+                .next()
+                .and_then(|v| ler_percentual(v))
+                .filter(|&m| m < 100.0)
+                .map(|m| meta = m)
+                .is_some(),
+            "--markup" => args
+                .next()
+                .and_then(|v| ler_percentual(v))
+                .filter(|&m| m > -100.0)
+                .map(|m| markup = m)
+                .is_some(),
+            "--abaixo" => {
+                so_abaixo = true;
+                true
+            }
+            "--saida" => args.next().map(|v| saida = Some(v.clone())).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_MARGENS);
+            return ExitCode::from(2);
+        }
+    }
+
+    let inicio = Instant::now();
+    let (mut linhas, mut vendidos) = match calcular(&caminho, meta) {
+        Ok(calculado) => calculado,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível montar o relatório de '{}': {}",
+                caminho, e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    if let Some(categoria) = &categoria {
+        linhas.retain(|l| categorias::pertence(l.categoria.as_deref(), categoria));
+        vendidos.retain(|v| linhas.iter().any(|l| l.codigo == v.codigo));
+    }
+    if so_abaixo {
+        linhas.retain(|l| l.margem < meta);
+    }
+    if linhas.is_empty() && vendidos.is_empty() {
+        println!("Nenhum produto com custo e preço para o relatório.");
+        return ExitCode::from(1);
+    }
+
+    let gravado = match saida.as_deref() {
+        None => margem::escrever_relatorio(io::stdout().lock(), &linhas, &vendidos, meta, markup),
+        Some(destino) if destino.to_lowercase().ends_with(".csv") => {
+            File::create(destino).and_then(|f| escrever_csv(f, &linhas, meta, markup))
+        }
+        Some(destino) => File::create(destino)
+            .and_then(|f| margem::escrever_relatorio(f, &linhas, &vendidos, meta, markup)),
+    };
+    if let Err(e) = gravado {
+        println!("⚠️ Não foi possível gravar o relatório: {}", e);
+        return ExitCode::from(2);
+    }
+
+    if let Some(destino) = &saida {
+        println!("Relatório gravado em '{}'.", destino);
+    }
+    let abaixo = linhas.iter().filter(|l| l.margem < meta).count();
+    println!(
+        "✅ {} produtos, {} abaixo da meta de {}. Operação concluída em {:.6} segundos.",
+        linhas.len(),
+        abaixo,
+        formatar_percentual(meta),
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
+
+// "30", "30%" ou "32,5"
+fn ler_percentual(texto: &str) -> Option<f64> {
+    texto
+        .trim()
+        .trim_end_matches('%')
+        .replace(',', ".")
+        .parse()
+        .ok()
+        .filter(|p: &f64| p.is_finite())
+}
+
+fn calcular(caminho: &str, meta: f64) -> io::Result<(Vec<LinhaDeMargem>, Vec<VendaAbaixo>)> {
+    let catalogo = Catalogo::abrir(caminho, None)?;
+    let vendas = venda::ler(caminho)?;
+    Ok((
+        margem::por_produto(&catalogo),
+        margem::vendidos_abaixo(&vendas, &catalogo, meta),
+    ))
+}
+
+// Planilha por produto, com a coluna que marca os abaixo da meta
+fn escrever_csv(arquivo: File, linhas: &[LinhaDeMargem], meta: f64, markup: f64) -> io::Result<()> {
+    let linhas = linhas.iter().map(|l| {
+        vec![
+            l.codigo.clone(),
+            l.nome.clone(),
+            l.categoria.clone().unwrap_or_default(),
+            l.custo.to_string(),
+            l.preco.to_string(),
+            formatar_percentual(l.margem),
+            l.markup.map(formatar_percentual).unwrap_or_default(),
+            margem::preco_sugerido(l.custo, markup).to_string(),
+            if l.margem < meta { "sim" } else { "" }.to_string(),
+        ]
+    });
+    csv::escrever_tabela(
+        arquivo,
+        &[
+            "codigo",
+            "nome",
+            "categoria",
+            "custo",
+            "preco",
+            "margem",
+            "markup",
+            "preco_sugerido",
+            "abaixo_da_meta",
+        ],
+        linhas,
+        ';',
+        true,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::codificacao::Codificacao;
+use projeto_rust::mesclagem::{self, Conflito, RegraConflito, RegraMesmoNome, TipoConflito};
+use projeto_rust::produto::{ler_produtos, salvar_produtos};
+
+const USO: &str = "Uso: megastore merge <a.txt> <b.txt> [...] [--saida catalogo_mesclado.txt]
+                      [--conflito primeiro|ultimo|falhar] [--mesmo-nome manter|descartar]
+                      [--encoding windows-1252]";
+
+// 🔹 megastore merge: junta os catálogos na ordem dada e grava o resultado
+// validado e ordenado por código
+pub fn executar(args: &[String]) -> ExitCode {
+    let mut entradas = Vec::new();
+    let mut saida = "catalogo_mesclado.txt".to_string();
+    let mut conflito = RegraConflito::default();
+    let mut mesmo_nome = RegraMesmoNome::default();
+    let mut codificacao = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--saida" => args.next().map(|v| saida = v.clone()).is_some(),
+            "--conflito" => args
+                .next()
+                .and_then(|v| ler_regra_conflito(v))
+                .map(|r| conflito = r)
+                .is_some(),
+            "--mesmo-nome" => args
+                .next()
+                .and_then(|v| ler_regra_mesmo_nome(v))
+                .map(|r| mesmo_nome = r)
+                .is_some(),
+            "--encoding" => args
+                .next()
+                .and_then(|v| Codificacao::de_nome(v))
+                .map(|c| codificacao = Some(c))
+                .is_some(),
+            _ if arg.starts_with("--") => false,
+            _ => {
+                entradas.push(arg.clone());
+                true
+            }
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO);
+            return ExitCode::from(2);
+        }
+    }
+
+    if entradas.len() < 2 {
+        println!("{}", USO);
+        return ExitCode::from(2);
+    }
+
+    let inicio = Instant::now();
+    let mut fontes = Vec::with_capacity(entradas.len());
+
+    for caminho in &entradas {
+        let leitura = match ler_produtos(caminho, codificacao) {
+            Ok(leitura) => leitura,
+            Err(e) => {
+                println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+                return ExitCode::from(2);
+            }
+        };
+
+        println!("'{}': {} produtos", caminho, leitura.produtos.len());
+        leitura.conversao.relatar(caminho);
+        for linha in &leitura.invalidas {
+            println!(
+                "  ⚠️ linha {} ignorada ({}): {}",
+                linha.numero, linha.motivo, linha.conteudo
+            );
+        }
+        fontes.push(leitura.produtos);
+    }
+
+    let mesclagem = match mesclagem::mesclar(&fontes, conflito, mesmo_nome) {
+        Ok(m) => m,
+        Err(c) => {
+            println!(
+                "⚠️ Código {} com nomes diferentes ('{}' e '{}'). Nada foi gravado.",
+                c.mantido.codigo, c.mantido.nome, c.descartado.nome
+            );
+            return ExitCode::from(1);
+        }
+    };
+
+    if !mesclagem.conflitos.is_empty() {
+        println!("\nConflitos resolvidos ({}):", mesclagem.conflitos.len());
+        for c in &mesclagem.conflitos {
+            println!("{}", descrever(c));
+        }
+    }
+    if mesclagem.repetidos > 0 {
+        println!(
+            "\n{} linhas repetidas foram unificadas.",
+            mesclagem.repetidos
+        );
+    }
+
+    if let Err(e) = salvar_produtos(&saida, &mesclagem.produtos) {
+        println!("⚠️ Erro ao gravar '{}': {}", saida, e);
+        return ExitCode::from(2);
+    }
+
+    println!(
+        "\n✅ {} produtos gravados em '{}'. Operação concluída em {:.6} segundos.",
+        mesclagem.produtos.len(),
+        saida,
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
+
+fn descrever(c: &Conflito) -> String {
+    match c.tipo {
+        TipoConflito::MesmoCodigo => format!(
+            "  [{}] '{}' mantido, '{}' descartado",
+            c.mantido.codigo, c.mantido.nome, c.descartado.nome
+        ),
+        TipoConflito::MesmoNome => format!(
+            "  '{}' [{}] mantido, [{}] descartado",
+            c.mantido.nome, c.mantido.codigo, c.descartado.codigo
+        ),
+    }
+}
+
+fn ler_regra_conflito(valor: &str) -> Option<RegraConflito> {
+    match valor {
+        "primeiro" => Some(RegraConflito::Primeiro),
+        "ultimo" | "último" => Some(RegraConflito::Ultimo),
+        "falhar" => Some(RegraConflito::Falhar),
+        _ => None,
+    }
+}
+
+fn ler_regra_mesmo_nome(valor: &str) -> Option<RegraMesmoNome> {
+    match valor {
+        "manter" => Some(RegraMesmoNome::Manter),
+        "descartar" => Some(RegraMesmoNome::Descartar),
+        _ => None,
+    }
+}
@@ -0,0 +1,189 @@
+use std::env;
+use std::process::ExitCode;
+
+use projeto_rust::operadores::{self, Operadores, Papel, Permissao, Sessao};
+
+const USO_OPERADOR: &str =
+    "Uso: megastore operador [--catalogo produtos.txt] adicionar <nome> caixa|estoquista|gerente
+       megastore operador [--catalogo produtos.txt] senha <nome>
+       megastore operador [--catalogo produtos.txt] remover <nome>
+       megastore operador [--catalogo produtos.txt] listar";
+
+// 🔹 Permissão que cada comando exige (None = comando desconhecido)
+pub fn permissao_do_comando(comando: &str) -> Option<Permissao> {
+    let permissao = match comando {
+        "diff" | "buscar" | "historico" | "histórico" | "reposicao" | "reposição" | "cupom"
+        | "categorias" => Permissao::Consultar,
+        // Só consultam; para alterar, conferem a permissão por conta própria
+        "estoque" | "produto" | "categoria" | "orcamento" | "orçamento" => Permissao::Consultar,
+        "pix" => Permissao::Vender,
+        // Custos só para quem pode mexer no preço
+        "reajuste" | "margens" => Permissao::AlterarPreco,
+        "merge" | "importar-csv" | "gravar" | "atualizar" | "categorizar" => Permissao::Cadastrar,
+        "exportar-csv" | "etiquetas" => Permissao::Exportar,
+        "remover" => Permissao::Remover,
+        "desfazer" | "refazer" => Permissao::Desfazer,
+        "converter" | "compactar" | "verificar-auditoria" => Permissao::Manutencao,
+        "operador" => Permissao::GerenciarOperadores,
+        _ => return None,
+    };
+    Some(permissao)
+}
+
+// Senha da variável de ambiente (para scripts) ou digitada sem eco
+fn ler_senha(variavel: &str, pergunta: &str) -> String {
+    env::var(variavel)
+        .or_else(|_| rpassword::prompt_password(pergunta))
+        .unwrap_or_default()
+}
+
+// 🔹 Tira "--operador <nome>" dos argumentos, em qualquer posição; None se
+// faltar o nome
+pub fn separar_operador(args: &[String]) -> Option<(Vec<String>, Option<String>)> {
+    let mut resto = Vec::new();
+    let mut nome = env::var("MEGASTORE_OPERADOR").ok();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg != "--operador" {
+            resto.push(arg.clone());
+            continue;
+        }
+        match args.next() {
+            Some(v) => nome = Some(v.clone()),
+            None => {
+                println!("⚠️ Falta o nome depois de '--operador'.");
+                return None;
+            }
+        }
+    }
+    Some((resto, nome))
+}
+
+// 🔹 Confere a senha do operador (de MEGASTORE_SENHA ou digitada) nas contas
+// da pasta do catálogo. Sem contas cadastradas, não pede nada.
+pub fn identificar(nome: Option<String>, catalogo: &str) -> Option<Sessao> {
+    let operadores = match Operadores::abrir(catalogo) {
+        Ok(operadores) => operadores,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                operadores::caminho_dos_operadores(catalogo),
+                e
+            );
+            return None;
+        }
+    };
+    if operadores.is_empty() {
+        return Some(Sessao::sem_cadastro());
+    }
+
+    let Some(nome) = nome else {
+        println!("⚠️ Identifique-se com '--operador <nome>' (ou MEGASTORE_OPERADOR).");
+        return None;
+    };
+    let senha = ler_senha("MEGASTORE_SENHA", "Senha: ");
+    let sessao = operadores.autenticar(&nome, &senha);
+    if sessao.is_none() {
+        println!("⚠️ Operador ou senha incorretos.");
+    }
+    sessao
+}
+
+// 🔹 megastore operador: cadastra, remove e lista as contas e troca senhas.
+// A senha nova vem de MEGASTORE_NOVA_SENHA ou é digitada duas vezes.
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = crate::editar::separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let mut operadores = match Operadores::abrir(&caminho) {
+        Ok(operadores) => operadores,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                operadores::caminho_dos_operadores(&caminho),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let resultado = match args.as_slice() {
+        ["listar"] => {
+            if operadores.is_empty() {
+                println!("Nenhum operador cadastrado.");
+            }
+            for o in operadores.listar() {
+                println!("{} - {}", o.nome, o.papel.nome());
+            }
+            return ExitCode::SUCCESS;
+        }
+        ["adicionar", nome, papel] => {
+            let Some(papel) = Papel::de_nome(papel) else {
+                println!(
+                    "⚠️ Perfil inválido: '{}'. Use caixa, estoquista ou gerente.",
+                    papel
+                );
+                return ExitCode::from(2);
+            };
+            let Some(senha) = nova_senha() else {
+                return ExitCode::from(2);
+            };
+            operadores
+                .adicionar(nome, papel, &senha)
+                .map(|()| format!("Operador '{}' ({}) cadastrado.", nome, papel.nome()))
+        }
+        ["senha", nome] => {
+            let Some(senha) = nova_senha() else {
+                return ExitCode::from(2);
+            };
+            operadores
+                .trocar_senha(nome, &senha)
+                .map(|()| format!("Senha de '{}' trocada.", nome))
+        }
+        ["remover", nome] => operadores
+            .remover(nome)
+            .map(|()| format!("Operador '{}' removido.", nome)),
+        _ => {
+            println!("{}", USO_OPERADOR);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mensagem = match resultado {
+        Ok(mensagem) => mensagem,
+        Err(e) => {
+            println!("⚠️ {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    match operadores.salvar(&sessao.nome) {
+        Ok(()) => {
+            println!("✅ {}", mensagem);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível gravar '{}': {}",
+                operadores.caminho(),
+                e
+            );
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn nova_senha() -> Option<String> {
+    if let Ok(senha) = env::var("MEGASTORE_NOVA_SENHA") {
+        return Some(senha);
+    }
+    let senha = rpassword::prompt_password("Nova senha: ").unwrap_or_default();
+    let confirmacao = rpassword::prompt_password("Repita a senha: ").unwrap_or_default();
+    if senha != confirmacao {
+        println!("⚠️ As senhas não conferem.");
+        return None;
+    }
+    Some(senha)
+}
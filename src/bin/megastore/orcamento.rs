@@ -0,0 +1,463 @@
+use std::fs;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chrono::Local;
+use projeto_rust::catalogo::{Catalogo, IdProduto};
+use projeto_rust::dinheiro::Reais;
+use projeto_rust::escpos::{self, ARQUIVO_IMPRESSORA, Documento, Impressora};
+use projeto_rust::estoque::{self, Estoque};
+use projeto_rust::gtin;
+use projeto_rust::operadores::{Permissao, Sessao};
+use projeto_rust::orcamento::{self, DIAS_DE_VALIDADE, Orcamento, Orcamentos, Situacao};
+use projeto_rust::venda::{Carrinho, Item, Pagamento, Vendas};
+
+const USO_ORCAMENTO: &str =
+    "Uso: megastore orcamento [--catalogo produtos.txt] novo [--cliente nome] [--validade 10]
+            <[quantidade*]código ou nome>...
+       megastore orcamento [--catalogo produtos.txt] mostrar <número> [--saida orcamento.txt]
+            [--escpos /dev/usb/lp0]
+       megastore orcamento [--catalogo produtos.txt] listar
+       megastore orcamento [--catalogo produtos.txt] reservar <número>
+       megastore orcamento [--catalogo produtos.txt] vender <número> [--pago valor] [--pix]";
+
+// 🔹 megastore orcamento: monta, mostra e lista orçamentos e converte um
+// orçamento em reserva de estoque ou em venda, pelos preços dele
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let Some((caminho, args)) = crate::editar::separar_catalogo(args) else {
+        println!("⚠️ Falta o arquivo depois de '--catalogo'.");
+        return ExitCode::from(2);
+    };
+    let Some((subcomando, resto)) = args.split_first() else {
+        println!("{}", USO_ORCAMENTO);
+        return ExitCode::from(2);
+    };
+
+    // Mostrar e listar só consultam; o resto é trabalho de vendedor
+    if !matches!(subcomando.as_str(), "mostrar" | "listar")
+        && let Err(motivo) = sessao.exigir(Permissao::Vender)
+    {
+        println!("⚠️ {}", motivo);
+        return ExitCode::from(2);
+    }
+
+    match (subcomando.as_str(), resto) {
+        ("novo", resto) => novo(&caminho, sessao, resto),
+        ("mostrar", resto) => mostrar(&caminho, resto),
+        ("listar", []) => listar(&caminho),
+        ("reservar", [numero]) => match numero.parse() {
+            Ok(numero) => reservar(&caminho, sessao, numero),
+            Err(_) => {
+                println!("⚠️ Número de orçamento inválido: '{}'", numero);
+                ExitCode::from(2)
+            }
+        },
+        ("vender", resto) => vender(&caminho, sessao, resto),
+        _ => {
+            println!("{}", USO_ORCAMENTO);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Audita a mudança e só então a grava
+fn auditar(caminho: &str, sessao: &Sessao, descricao: String) -> std::io::Result<()> {
+    crate::auditoria::registrar(caminho, sessao, "orcamento", descricao)
+}
+
+// Produto pelo código (interno ou de barras) ou por todas as palavras do nome;
+// um nome que serve para mais de um produto precisa ser mais específico
+fn localizar(catalogo: &Catalogo, termo: &str) -> Result<IdProduto, String> {
+    if let Some(id) = gtin::localizar(catalogo, termo)? {
+        return Ok(id);
+    }
+    let mut palavras = termo.split_whitespace();
+    let mut ids = palavras
+        .next()
+        .map(|p| catalogo.buscar_por_palavra(p))
+        .unwrap_or_default();
+    for palavra in palavras {
+        let outros = catalogo.buscar_por_palavra(palavra);
+        ids.retain(|id| outros.binary_search(id).is_ok());
+    }
+    match ids.as_slice() {
+        [] => Err(format!("nenhum produto encontrado com '{}'", termo)),
+        [id] => Ok(*id),
+        _ => {
+            let exemplos: Vec<String> = ids
+                .iter()
+                .take(5)
+                .map(|&id| format!("[{}] {}", catalogo.codigo(id), catalogo.nome(id)))
+                .collect();
+            Err(format!(
+                "'{}' serve para {} produtos ({}{}); use o código",
+                termo,
+                ids.len(),
+                exemplos.join(", "),
+                if ids.len() > exemplos.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            ))
+        }
+    }
+}
+
+fn novo(caminho: &str, sessao: &Sessao, args: &[String]) -> ExitCode {
+    let mut cliente = None;
+    let mut dias = DIAS_DE_VALIDADE;
+    let mut pedidos = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--cliente" => args.next().map(|v| cliente = Some(v.clone())).is_some(),
+            "--validade" => args
+                .next()
+                .and_then(|v| v.parse().ok())
+                .map(|d| dias = d)
+                .is_some(),
+            a if !a.starts_with("--") => {
+                pedidos.push(a.to_string());
+                true
+            }
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_ORCAMENTO);
+            return ExitCode::from(2);
+        }
+    }
+    if pedidos.is_empty() {
+        println!("{}", USO_ORCAMENTO);
+        return ExitCode::from(2);
+    }
+
+    let inicio = Instant::now();
+    let catalogo = match Catalogo::abrir(caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+    // Os preços de hoje ficam gravados no orçamento
+    let mut carrinho = Carrinho::default();
+    for pedido in &pedidos {
+        let (quantidade, termo) = match pedido.split_once('*') {
+            Some((quantidade, termo)) => (estoque::ler_quantidade(quantidade), termo.trim()),
+            None => (Some(1.0), pedido.as_str()),
+        };
+        let Some(quantidade) = quantidade.filter(|&q| q > 0.0) else {
+            println!("⚠️ Quantidade inválida em '{}'.", pedido);
+            return ExitCode::from(2);
+        };
+        let id = match localizar(&catalogo, termo) {
+            Ok(id) => id,
+            Err(motivo) => {
+                println!("⚠️ {}.", motivo);
+                return ExitCode::from(1);
+            }
+        };
+        let codigo = catalogo.codigo(id);
+        let Some(preco) = catalogo.preco(id).filter(|&p| p > Reais::ZERO) else {
+            println!(
+                "⚠️ O produto [{}] {} não tem preço cadastrado (megastore produto {} --preco ...).",
+                codigo,
+                catalogo.nome(id),
+                codigo
+            );
+            return ExitCode::from(2);
+        };
+        carrinho.adicionar(Item {
+            codigo: codigo.to_string(),
+            nome: catalogo.nome(id).to_string(),
+            quantidade,
+            preco,
+            etiqueta: None,
+        });
+    }
+
+    let descricao = format!(
+        "orçamento de {} itens, total {}",
+        carrinho.itens().len(),
+        carrinho.total().com_simbolo()
+    );
+    let resultado = auditar(caminho, sessao, descricao)
+        .and_then(|()| Orcamentos::abrir(caminho))
+        .and_then(|mut o| o.criar(&sessao.nome, cliente, &carrinho, dias));
+    match resultado {
+        Ok(orcamento) => {
+            print!("{}", orcamento::texto(&orcamento));
+            println!(
+                "✅ Orçamento {} gravado, válido até {}. Operação concluída em {:.6} segundos.",
+                orcamento.numero,
+                orcamento.validade.format("%d/%m/%Y"),
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Orçamento não gravado: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Orçamento pelo número; se não der, a mensagem já sai e volta o código de saída
+fn buscar(caminho: &str, numero: u64) -> Result<Orcamento, ExitCode> {
+    match orcamento::buscar(caminho, numero) {
+        Ok(Some(orcamento)) => Ok(orcamento),
+        Ok(None) => {
+            println!("Nenhum orçamento com o número {}.", numero);
+            Err(ExitCode::from(1))
+        }
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                orcamento::caminho_dos_orcamentos(caminho),
+                e
+            );
+            Err(ExitCode::from(2))
+        }
+    }
+}
+
+fn mostrar(caminho: &str, args: &[String]) -> ExitCode {
+    let mut numero = None;
+    let mut saida = None;
+    let mut escpos = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--saida" => args.next().map(|v| saida = Some(v.clone())).is_some(),
+            "--escpos" => args.next().map(|v| escpos = Some(v.clone())).is_some(),
+            a if numero.is_none() => a.parse::<u64>().ok().map(|n| numero = Some(n)).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_ORCAMENTO);
+            return ExitCode::from(2);
+        }
+    }
+    let Some(numero) = numero else {
+        println!("{}", USO_ORCAMENTO);
+        return ExitCode::from(2);
+    };
+    let orcamento = match buscar(caminho, numero) {
+        Ok(orcamento) => orcamento,
+        Err(codigo) => return codigo,
+    };
+
+    if let Some(destino) = escpos {
+        let impressora = match Impressora::abrir(ARQUIVO_IMPRESSORA) {
+            Ok(impressora) => impressora.unwrap_or_default(),
+            Err(e) => {
+                println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_IMPRESSORA, e);
+                return ExitCode::from(2);
+            }
+        };
+        let bytes = escpos::gerar(&Documento::do_orcamento(&orcamento), &impressora);
+        if let Err(e) = escpos::enviar(&destino, &bytes) {
+            println!("⚠️ Não foi possível gravar '{}': {}", destino, e);
+            return ExitCode::from(2);
+        }
+        println!("Orçamento {} enviado para '{}'.", numero, destino);
+        return ExitCode::SUCCESS;
+    }
+
+    let texto = orcamento::texto(&orcamento);
+    match saida {
+        None => {
+            print!("{}", texto);
+            println!("Situação: {}", situacao(&orcamento));
+        }
+        Some(destino) => {
+            if let Err(e) = fs::write(&destino, texto) {
+                println!("⚠️ Não foi possível gravar '{}': {}", destino, e);
+                return ExitCode::from(2);
+            }
+            println!("Orçamento {} gravado em '{}'.", numero, destino);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// Situação com o aviso de vencido para os que ainda não viraram venda
+fn situacao(orcamento: &Orcamento) -> String {
+    let vencido = !matches!(orcamento.situacao, Situacao::Vendido(_))
+        && orcamento.vencido(Local::now().date_naive());
+    if vencido {
+        format!("{}, vencido", orcamento.situacao.nome())
+    } else {
+        orcamento.situacao.nome()
+    }
+}
+
+fn listar(caminho: &str) -> ExitCode {
+    let orcamentos = match orcamento::ler(caminho) {
+        Ok(orcamentos) => orcamentos,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                orcamento::caminho_dos_orcamentos(caminho),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    if orcamentos.is_empty() {
+        println!("Nenhum orçamento registrado.");
+        return ExitCode::from(1);
+    }
+
+    for o in &orcamentos {
+        println!(
+            "{:06}  {}  válido até {}  {:>14}  {}  {}",
+            o.numero,
+            o.quando.format("%d/%m/%Y"),
+            o.validade.format("%d/%m/%Y"),
+            o.total.com_simbolo(),
+            o.cliente.as_deref().unwrap_or("-"),
+            situacao(o)
+        );
+    }
+    println!("✅ {} orçamentos.", orcamentos.len());
+    ExitCode::SUCCESS
+}
+
+// A reserva é gravada mesmo sem estoque suficiente, com um aviso: a
+// mercadoria pode estar para chegar
+fn reservar(caminho: &str, sessao: &Sessao, numero: u64) -> ExitCode {
+    let inicio = Instant::now();
+    let orcamento = match buscar(caminho, numero) {
+        Ok(orcamento) => orcamento,
+        Err(codigo) => return codigo,
+    };
+    let faltas = Estoque::abrir(caminho).and_then(|mut estoque| {
+        orcamento::faltas(caminho, &mut estoque, &orcamento).map(|faltas| {
+            faltas
+                .iter()
+                .map(|(item, disponivel)| {
+                    format!(
+                        "[{}] {}: pedido {}, disponível {}",
+                        item.codigo,
+                        item.nome,
+                        estoque::formatar_quantidade(item.quantidade),
+                        estoque::formatar_quantidade(*disponivel)
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    let faltas = match faltas {
+        Ok(faltas) => faltas,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let resultado = auditar(caminho, sessao, format!("reserva do orçamento {}", numero))
+        .and_then(|()| Orcamentos::abrir(caminho))
+        .and_then(|mut o| o.reservar(numero));
+    match resultado {
+        Ok(orcamento) => {
+            for falta in &faltas {
+                println!("⚠️ Estoque insuficiente: {}", falta);
+            }
+            println!(
+                "✅ Itens do orçamento {} reservados até {}. Operação concluída em {:.6} segundos.",
+                numero,
+                orcamento.validade.format("%d/%m/%Y"),
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Reserva não gravada: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn vender(caminho: &str, sessao: &Sessao, args: &[String]) -> ExitCode {
+    let mut numero = None;
+    let mut pago = None;
+    let mut pagamento = Pagamento::Dinheiro;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--pago" => args
+                .next()
+                .and_then(|v| Reais::de_texto(v))
+                .map(|v| pago = Some(v))
+                .is_some(),
+            "--pix" => {
+                pagamento = Pagamento::Pix;
+                true
+            }
+            a if numero.is_none() => a.parse::<u64>().ok().map(|n| numero = Some(n)).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_ORCAMENTO);
+            return ExitCode::from(2);
+        }
+    }
+    let Some(numero) = numero else {
+        println!("{}", USO_ORCAMENTO);
+        return ExitCode::from(2);
+    };
+
+    let inicio = Instant::now();
+    let orcamento = match buscar(caminho, numero) {
+        Ok(orcamento) => orcamento,
+        Err(codigo) => return codigo,
+    };
+    // Sem --pago, o valor exato
+    let pago = pago.unwrap_or(orcamento.total);
+    let descricao = format!(
+        "venda do orçamento {}, total {}",
+        numero,
+        orcamento.total.com_simbolo()
+    );
+    let resultado = auditar(caminho, sessao, descricao).and_then(|()| {
+        let mut orcamentos = Orcamentos::abrir(caminho)?;
+        let mut vendas = Vendas::abrir(caminho)?;
+        let mut estoque = Estoque::abrir(caminho)?;
+        orcamentos.vender(
+            numero,
+            &mut vendas,
+            &mut estoque,
+            &sessao.nome,
+            pago,
+            pagamento,
+        )
+    });
+    match resultado {
+        Ok((_, venda)) => {
+            println!(
+                "✅ Orçamento {} vendido: venda {}, troco {}. Operação concluída em {:.6} segundos.",
+                numero,
+                venda.numero,
+                venda.troco.com_simbolo(),
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Venda não registrada: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
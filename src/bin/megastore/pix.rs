@@ -0,0 +1,75 @@
+use std::fs;
+use std::process::ExitCode;
+
+use projeto_rust::dinheiro::Reais;
+use projeto_rust::pix::{self, ARQUIVO_PIX, Recebedor};
+
+const USO_PIX: &str = "Uso: megastore pix <valor> [--txid identificador] [--saida pix.svg]";
+
+// 🔹 megastore pix: BR Code do PIX estático para o valor, com o QR code na
+// tela e, se pedido, em SVG
+pub fn executar(args: &[String]) -> ExitCode {
+    let mut valor = None;
+    let mut txid = None;
+    let mut saida = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--txid" => args.next().map(|v| txid = Some(v.clone())).is_some(),
+            "--saida" => args.next().map(|v| saida = Some(v.clone())).is_some(),
+            a if valor.is_none() => Reais::de_texto(a).map(|v| valor = Some(v)).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_PIX);
+            return ExitCode::from(2);
+        }
+    }
+    let Some(valor) = valor else {
+        println!("{}", USO_PIX);
+        return ExitCode::from(2);
+    };
+
+    let recebedor = match Recebedor::abrir(ARQUIVO_PIX) {
+        Ok(Some(recebedor)) => recebedor,
+        Ok(None) => {
+            println!(
+                "⚠️ PIX não configurado: crie '{}' com a chave, o nome e a cidade da loja.",
+                ARQUIVO_PIX
+            );
+            return ExitCode::from(2);
+        }
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_PIX, e);
+            return ExitCode::from(2);
+        }
+    };
+    let resultado = pix::br_code(&recebedor, valor, txid.as_deref())
+        .and_then(|codigo| Ok((pix::qr_terminal(&codigo)?, codigo)));
+    let (qr, codigo) = match resultado {
+        Ok(gerado) => gerado,
+        Err(motivo) => {
+            println!("⚠️ {}", motivo);
+            return ExitCode::from(2);
+        }
+    };
+
+    print!("{}", qr);
+    println!("PIX de {} (copia e cola):", valor.com_simbolo());
+    println!("{}", codigo);
+    if let Some(destino) = saida {
+        let gravado = pix::qr_svg(&codigo)
+            .and_then(|svg| fs::write(&destino, svg).map_err(|e| e.to_string()));
+        match gravado {
+            Ok(()) => println!("QR code gravado em '{}'.", destino),
+            Err(motivo) => {
+                println!("⚠️ Não foi possível gravar '{}': {}", destino, motivo);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,149 @@
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::dinheiro::Reais;
+use projeto_rust::operadores::Sessao;
+use projeto_rust::reajuste::{self, Arredondamento, Reajuste, Variacao};
+
+use crate::selecao::Selecao;
+
+const USO_REAJUSTE: &str = "Uso: megastore reajuste [--catalogo produtos.txt]
+        [--busca termo | --prefixo texto | --letra L | --de código --ate código
+        | <código>...] [--categoria nome]
+        --variacao +10%|-5%|+1,50 [--final 90|99] [--sim]";
+
+// Largura do código e nome na prévia
+const LARGURA_PRODUTO: usize = 44;
+
+// 🔹 megastore reajuste: muda o preço dos produtos escolhidos por um percentual
+// ou valor, com final x,90 ou x,99 se pedido. Mostra a prévia e só grava
+// depois da confirmação (ou direto, com --sim). Sai com 1 se não houver o que
+// mudar ou se o reajuste não for confirmado.
+pub fn executar(args: &[String], sessao: &Sessao) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut selecao = Selecao::default();
+    let mut variacao = None;
+    let mut arredondamento = Arredondamento::default();
+    let mut sim = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--variacao" | "--variação" => args
+                .next()
+                .and_then(|v| Variacao::de_texto(v))
+                .map(|v| variacao = Some(v))
+                .is_some(),
+            "--final" => args
+                .next()
+                .and_then(|v| Arredondamento::de_nome(v))
+                .map(|a| arredondamento = a)
+                .is_some(),
+            "--sim" => {
+                sim = true;
+                true
+            }
+            a => selecao.ler(a, &mut args),
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_REAJUSTE);
+            return ExitCode::from(2);
+        }
+    }
+    // Sem seleção não há reajuste; o catálogo inteiro precisa ser pedido com
+    // uma faixa (--de 0, por exemplo)
+    let Some(variacao) = variacao.filter(|_| !selecao.vazia()) else {
+        println!("{}", USO_REAJUSTE);
+        return ExitCode::from(2);
+    };
+    let reajuste = Reajuste {
+        variacao,
+        arredondamento,
+    };
+
+    let catalogo = match Catalogo::abrir(&caminho, None) {
+        Ok(catalogo) => catalogo,
+        Err(e) => {
+            println!("⚠️ Não foi possível abrir '{}': {}", caminho, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let ids = selecao.produtos(&catalogo);
+    let previa = reajuste::previa(&catalogo, &ids, &reajuste);
+    let sem_preco = ids.len() - previa.len();
+    let novos: Vec<_> = previa.into_iter().filter(|a| a.depois != a.antes).collect();
+    if sem_preco > 0 {
+        println!(
+            "⚠️ {} produtos escolhidos sem preço cadastrado ficam de fora.",
+            sem_preco
+        );
+    }
+    if novos.is_empty() {
+        println!("Nenhum preço muda com esse reajuste.");
+        return ExitCode::from(1);
+    }
+
+    println!("Reajuste {}:", variacao.descricao());
+    for a in &novos {
+        let produto: String = format!("[{}] {}", a.codigo, a.nome)
+            .chars()
+            .take(LARGURA_PRODUTO)
+            .collect();
+        println!(
+            "{:<LARGURA_PRODUTO$} {:>10} → {:>10}  {}%",
+            produto,
+            a.antes,
+            a.depois,
+            format!("{:+.1}", a.percentual()).replace('.', ",")
+        );
+    }
+    let zerados = novos.iter().filter(|a| a.depois <= Reais::ZERO).count();
+    if zerados > 0 {
+        println!(
+            "⚠️ O reajuste deixaria {} produtos com preço zero ou negativo; nada foi alterado.",
+            zerados
+        );
+        return ExitCode::from(2);
+    }
+
+    if !sim {
+        println!("Aplicar os {} novos preços? (s/N)", novos.len());
+        let mut entrada = String::new();
+        io::stdin()
+            .read_line(&mut entrada)
+            .expect("Erro ao ler entrada");
+        if !entrada.trim().eq_ignore_ascii_case("s") {
+            println!("Nenhum preço alterado.");
+            return ExitCode::from(1);
+        }
+    }
+
+    // Uma operação só no histórico (o desfazer volta todos os preços); a
+    // auditoria fica com o produto antes e depois de cada um
+    let inicio = Instant::now();
+    let Some(vivo) = crate::editar::abrir(&caminho, sessao) else {
+        return ExitCode::from(2);
+    };
+    let produtos = reajuste::aplicar(&vivo.atual(), &novos);
+    let descricao = format!("reajuste {}", variacao.descricao());
+    match vivo.substituir(&descricao, produtos) {
+        Ok(total) => {
+            println!(
+                "✅ {} preços reajustados. Operação concluída em {:.6} segundos.",
+                total,
+                inicio.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("⚠️ Preços não gravados: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs::File;
+use std::io;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chrono::{Duration, Local};
+use projeto_rust::cadastro::Cadastro;
+use projeto_rust::catalogo::Catalogo;
+use projeto_rust::categorias;
+use projeto_rust::csv;
+use projeto_rust::estoque::{self, Estoque, formatar_quantidade};
+use projeto_rust::reposicao::{self, DIAS_DE_CONSUMO, Sugestao};
+
+const USO_REPOSICAO: &str =
+    "Uso: megastore reposicao [--catalogo produtos.txt] [--dias 30] [--categoria nome]
+        [--saida compras.txt|compras.csv]";
+
+// 🔹 megastore reposicao: produtos abaixo do mínimo com a compra sugerida,
+// agrupados por fornecedor (só os da categoria, se pedido). Sai com 1 se
+// nenhum produto está abaixo do mínimo.
+pub fn executar(args: &[String]) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut dias = DIAS_DE_CONSUMO;
+    let mut categoria = None;
+    let mut saida = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--dias" => args
+                .next()
+                .and_then(|v| v.parse().ok())
+                .filter(|&d| d > 0)
+                .map(|d| dias = d)
+                .is_some(),
+            "--categoria" => args.next().map(|v| categoria = Some(v.clone())).is_some(),
+            "--saida" => args.next().map(|v| saida = Some(v.clone())).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_REPOSICAO);
+            return ExitCode::from(2);
+        }
+    }
+
+    let inicio = Instant::now();
+    let sugestoes = match calcular(&caminho, dias, categoria.as_deref()) {
+        Ok(sugestoes) => sugestoes,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível montar o relatório de '{}': {}",
+                caminho, e
+            );
+            return ExitCode::from(2);
+        }
+    };
+    if sugestoes.is_empty() {
+        println!("Nenhum produto abaixo do mínimo.");
+        return ExitCode::from(1);
+    }
+
+    let gravado = match saida.as_deref() {
+        None => reposicao::escrever_lista(io::stdout().lock(), &sugestoes),
+        Some(destino) if destino.to_lowercase().ends_with(".csv") => {
+            File::create(destino).and_then(|f| escrever_csv(f, &sugestoes))
+        }
+        Some(destino) => {
+            File::create(destino).and_then(|f| reposicao::escrever_lista(f, &sugestoes))
+        }
+    };
+    if let Err(e) = gravado {
+        println!("⚠️ Não foi possível gravar a lista de compras: {}", e);
+        return ExitCode::from(2);
+    }
+
+    if let Some(destino) = &saida {
+        println!("Lista de compras gravada em '{}'.", destino);
+    }
+    println!(
+        "✅ {} produtos abaixo do mínimo (consumo dos últimos {} dias). Operação concluída em {:.6} segundos.",
+        sugestoes.len(),
+        dias,
+        inicio.elapsed().as_secs_f64()
+    );
+    ExitCode::SUCCESS
+}
+
+fn calcular(caminho: &str, dias: u32, categoria: Option<&str>) -> io::Result<Vec<Sugestao>> {
+    let catalogo = Catalogo::abrir(caminho, None)?;
+    let cadastro = Cadastro::abrir(caminho)?;
+    let mut estoque = Estoque::abrir(caminho)?;
+    let desde = Local::now() - Duration::days(dias.into());
+    let consumo = estoque::consumo_desde(caminho, desde)?;
+    let mut sugestoes =
+        reposicao::abaixo_do_minimo(&catalogo, &cadastro, estoque.saldos()?, &consumo);
+    if let Some(categoria) = categoria {
+        sugestoes.retain(|s| {
+            let da_categoria = catalogo
+                .buscar_por_codigo(&s.codigo)
+                .and_then(|id| catalogo.categoria(id));
+            categorias::pertence(da_categoria, categoria)
+        });
+    }
+    Ok(sugestoes)
+}
+
+// Planilha do pedido: uma linha por produto, já na ordem dos fornecedores
+fn escrever_csv(arquivo: File, sugestoes: &[Sugestao]) -> io::Result<()> {
+    let linhas =
+        reposicao::por_fornecedor(sugestoes)
+            .into_iter()
+            .flat_map(|(fornecedor, itens)| {
+                itens.into_iter().map(move |s| {
+                    vec![
+                        fornecedor.unwrap_or_default().to_string(),
+                        s.codigo.clone(),
+                        s.nome.clone(),
+                        formatar_quantidade(s.saldo),
+                        formatar_quantidade(s.minimo),
+                        s.maximo.map(formatar_quantidade).unwrap_or_default(),
+                        formatar_quantidade(s.consumo),
+                        formatar_quantidade(s.comprar),
+                    ]
+                })
+            });
+    csv::escrever_tabela(
+        arquivo,
+        &[
+            "fornecedor",
+            "codigo",
+            "nome",
+            "saldo",
+            "minimo",
+            "maximo",
+            "consumo",
+            "comprar",
+        ],
+        linhas,
+        ';',
+        true,
+    )?;
+    Ok(())
+}
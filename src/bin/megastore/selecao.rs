@@ -0,0 +1,114 @@
+use std::slice::Iter;
+
+use projeto_rust::catalogo::{Catalogo, IdProduto};
+use projeto_rust::categorias;
+
+// 🔹 Produtos escolhidos pela linha de comando, por uma das formas de busca do
+// catálogo: palavra do nome, começo do nome, letra inicial, faixa de códigos
+// ou uma lista de códigos. A categoria é um filtro que vale sozinho ou junto
+// com qualquer uma delas.
+#[derive(Debug, Default)]
+pub struct Selecao {
+    busca: Option<String>,
+    prefixo: Option<String>,
+    letra: Option<String>,
+    de: Option<String>,
+    ate: Option<String>,
+    categoria: Option<String>,
+    codigos: Vec<String>,
+}
+
+impl Selecao {
+    // Lê `arg` (e o valor que vem depois) como opção de seleção ou código;
+    // falso se não for nenhum dos dois, se faltar o valor ou se já houver
+    // outra forma de seleção
+    pub fn ler(&mut self, arg: &str, args: &mut Iter<String>) -> bool {
+        let ok = match arg {
+            "--busca" => args.next().map(|v| self.busca = Some(v.clone())).is_some(),
+            "--prefixo" => args
+                .next()
+                .map(|v| self.prefixo = Some(v.clone()))
+                .is_some(),
+            "--letra" => args
+                .next()
+                .filter(|v| v.chars().count() == 1)
+                .map(|v| self.letra = Some(v.clone()))
+                .is_some(),
+            "--de" => args.next().map(|v| self.de = Some(v.clone())).is_some(),
+            "--ate" | "--até" => args.next().map(|v| self.ate = Some(v.clone())).is_some(),
+            "--categoria" => args
+                .next()
+                .map(|v| self.categoria = Some(v.clone()))
+                .is_some(),
+            a if a.starts_with("--") => false,
+            a => {
+                self.codigos.push(a.to_string());
+                true
+            }
+        };
+        // Uma forma de escolher os produtos só
+        ok && self.formas() <= 1
+    }
+
+    fn formas(&self) -> usize {
+        [
+            self.busca.is_some(),
+            self.prefixo.is_some(),
+            self.letra.is_some(),
+            self.de.is_some() || self.ate.is_some(),
+            !self.codigos.is_empty(),
+        ]
+        .iter()
+        .filter(|&&f| f)
+        .count()
+    }
+
+    // Nenhuma forma escolhida: vale o catálogo inteiro
+    pub fn vazia(&self) -> bool {
+        self.formas() == 0 && self.categoria.is_none()
+    }
+
+    // 🔹 Os produtos escolhidos, por código (a lista de códigos fica na ordem
+    // dada; os que não existem são avisados e ficam de fora)
+    pub fn produtos(&self, catalogo: &Catalogo) -> Vec<IdProduto> {
+        let ids = self.escolhidos(catalogo);
+        match &self.categoria {
+            // A categoria inclui as de baixo dela
+            Some(categoria) => categorias::filtrar(catalogo, &ids, categoria),
+            None => ids,
+        }
+    }
+
+    fn escolhidos(&self, catalogo: &Catalogo) -> Vec<IdProduto> {
+        if !self.codigos.is_empty() {
+            let mut ids = Vec::new();
+            for codigo in &self.codigos {
+                match catalogo.buscar_por_codigo(codigo) {
+                    Some(id) => ids.push(id),
+                    None => println!("⚠️ Produto {} não encontrado.", codigo),
+                }
+            }
+            return ids;
+        }
+
+        let mut ids: Vec<IdProduto> = if let Some(termo) = &self.busca {
+            catalogo.buscar_por_palavra(termo)
+        } else if let Some(prefixo) = self.prefixo.as_ref().or(self.letra.as_ref()) {
+            catalogo.buscar_por_prefixo(prefixo).to_vec()
+        } else {
+            // Faixa de códigos (sem --de ou --ate, o catálogo inteiro)
+            catalogo
+                .ordem_por_codigo()
+                .iter()
+                .copied()
+                .filter(|&id| {
+                    let codigo = catalogo.codigo(id);
+                    self.de.as_deref().is_none_or(|d| codigo >= d)
+                        && self.ate.as_deref().is_none_or(|a| codigo <= a)
+                })
+                .collect()
+        };
+        ids.sort_by(|&a, &b| catalogo.codigo(a).cmp(catalogo.codigo(b)));
+        ids
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::process::ExitCode;
+
+use projeto_rust::escpos::{self, ARQUIVO_IMPRESSORA, Documento, Impressora, PaginaDeCodigo};
+use projeto_rust::venda;
+
+const USO_CUPOM: &str =
+    "Uso: megastore cupom [--catalogo produtos.txt] <número da venda> [--saida cupom.txt]
+        [--escpos /dev/usb/lp0|cupom.bin] [--colunas 48] [--pagina cp860|cp850]";
+
+// 🔹 megastore cupom: reimprime o cupom de uma venda registrada, em texto ou
+// em ESC/POS para a impressora térmica. Sai com 1 se a venda não existe.
+pub fn cupom(args: &[String]) -> ExitCode {
+    let mut caminho = "produtos.txt".to_string();
+    let mut numero = None;
+    let mut saida = None;
+    let mut escpos = None;
+    let mut colunas = None;
+    let mut pagina = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v.clone()).is_some(),
+            "--saida" => args.next().map(|v| saida = Some(v.clone())).is_some(),
+            "--escpos" => args.next().map(|v| escpos = Some(v.clone())).is_some(),
+            "--colunas" => args
+                .next()
+                .and_then(|v| v.parse().ok())
+                .filter(|&c: &usize| c >= 24)
+                .map(|c| colunas = Some(c))
+                .is_some(),
+            "--pagina" | "--página" => args
+                .next()
+                .and_then(|v| PaginaDeCodigo::de_nome(v))
+                .map(|p| pagina = Some(p))
+                .is_some(),
+            a if numero.is_none() => a.parse::<u64>().ok().map(|n| numero = Some(n)).is_some(),
+            _ => false,
+        };
+
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO_CUPOM);
+            return ExitCode::from(2);
+        }
+    }
+    let Some(numero) = numero else {
+        println!("{}", USO_CUPOM);
+        return ExitCode::from(2);
+    };
+
+    let venda = match venda::buscar(&caminho, numero) {
+        Ok(Some(venda)) => venda,
+        Ok(None) => {
+            println!("Nenhuma venda com o número {}.", numero);
+            return ExitCode::from(1);
+        }
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                venda::caminho_das_vendas(&caminho),
+                e
+            );
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(destino) = escpos {
+        // Colunas e página do impressora.json, se houver, e das opções por cima
+        let mut impressora = match Impressora::abrir(ARQUIVO_IMPRESSORA) {
+            Ok(impressora) => impressora.unwrap_or_default(),
+            Err(e) => {
+                println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_IMPRESSORA, e);
+                return ExitCode::from(2);
+            }
+        };
+        impressora.colunas = colunas.unwrap_or(impressora.colunas);
+        impressora.pagina = pagina.unwrap_or(impressora.pagina);
+        let bytes = escpos::gerar(&Documento::da_venda(&venda), &impressora);
+        if let Err(e) = escpos::enviar(&destino, &bytes) {
+            println!("⚠️ Não foi possível gravar '{}': {}", destino, e);
+            return ExitCode::from(2);
+        }
+        println!("Cupom da venda {} enviado para '{}'.", numero, destino);
+        return ExitCode::SUCCESS;
+    }
+
+    let texto = venda::cupom(&venda);
+    match saida {
+        None => print!("{}", texto),
+        Some(destino) => {
+            if let Err(e) = fs::write(&destino, texto) {
+                println!("⚠️ Não foi possível gravar '{}': {}", destino, e);
+                return ExitCode::from(2);
+            }
+            println!("Cupom da venda {} gravado em '{}'.", numero, destino);
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -25,11 +25,13 @@ fn carregar_produtos(caminho: &str) -> Vec<Produto> {
         let reader = io::BufReader::new(file);
 
         for linha in reader.lines() {
-            if let Ok(linha) = linha {
-                let partes: Vec<&str> = linha.splitn(2, ';').collect();
-                if partes.len() == 2 {
-                    produtos.push(Produto::new(partes[0].trim(), partes[1].trim()));
-                }
+            // Linha ilegível (fora do UTF-8) é pulada; as seguintes continuam
+            let Ok(linha) = linha else {
+                continue;
+            };
+            let partes: Vec<&str> = linha.splitn(2, ';').collect();
+            if partes.len() == 2 {
+                produtos.push(Produto::new(partes[0].trim(), partes[1].trim()));
             }
         }
     } else {
@@ -41,7 +43,7 @@ fn carregar_produtos(caminho: &str) -> Vec<Produto> {
 
 fn main() {
     let caminho = "produtos.txt";
-    let mut produtos = carregar_produtos(caminho);
+    let produtos = carregar_produtos(caminho);
 
     if produtos.is_empty() {
         println!("Erro: nenhum produto carregado de '{}'.", caminho);
@@ -145,9 +147,9 @@ fn buscar_por_codigo(produtos: &[Produto]) {
 }
 
 // 🔹 Ordenação por nome
-fn listar_por_nome(produtos: &mut Vec<Produto>) {
+fn listar_por_nome(produtos: &mut [Produto]) {
     let inicio = Instant::now();
-    produtos.sort_by(|a, b| a.nome.to_lowercase().cmp(&b.nome.to_lowercase()));
+    produtos.sort_by_key(|a| a.nome.to_lowercase());
 
     for (i, p) in produtos.iter().enumerate() {
         println!("{}. [{}] {}", i + 1, p.codigo, p.nome);
@@ -161,7 +163,7 @@ fn listar_por_nome(produtos: &mut Vec<Produto>) {
 }
 
 // 🔹 Ordenação por código
-fn listar_por_codigo(produtos: &mut Vec<Produto>) {
+fn listar_por_codigo(produtos: &mut [Produto]) {
     let inicio = Instant::now();
     produtos.sort_by(|a, b| a.codigo.cmp(&b.codigo));
 
@@ -1,131 +1,1449 @@
-use std::collections::BTreeMap;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::time::Instant;
+use std::borrow::Cow;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
-struct Produto {
-    codigo: String,
-    nome: String,
+use chrono::Local;
+use projeto_rust::balanca::{self, ARQUIVO_BALANCA, Balanca, Leitura, Valor};
+use projeto_rust::cadastro::Cadastro;
+use projeto_rust::catalogo::{Catalogo, IdProduto};
+use projeto_rust::categorias::{self, No};
+use projeto_rust::codificacao::Codificacao;
+use projeto_rust::diferencas::Diferencas;
+use projeto_rust::dinheiro::Reais;
+use projeto_rust::escpos::{self, ARQUIVO_IMPRESSORA, Documento, Impressora};
+use projeto_rust::estoque::{self, Estoque, TipoMovimento};
+use projeto_rust::historico;
+use projeto_rust::margem::{self, MARGEM_META, MARKUP_PADRAO};
+use projeto_rust::operadores::{self, Operadores, Permissao, Sessao};
+use projeto_rust::orcamento::{self, DIAS_DE_VALIDADE, Orcamento, Orcamentos, Situacao};
+use projeto_rust::pix::{self, ARQUIVO_PIX, Recebedor};
+use projeto_rust::produto::Produto;
+use projeto_rust::recarga::CatalogoVivo;
+use projeto_rust::reposicao::{self, DIAS_DE_CONSUMO};
+use projeto_rust::venda::{self, Carrinho, Item, Pagamento, Vendas};
+
+const USO: &str =
+    "Uso: megastore_rust_btreemap [--catalogo produtos.txt|produtos.json|produtos.jsonl|produtos.db]
+                              [--encoding utf-8|windows-1252|latin-1|utf-16le|utf-16be]";
+
+// Catálogos procurados, em ordem, quando "--catalogo" não é informado
+const CATALOGOS_PADRAO: [&str; 4] = [
+    "produtos.txt",
+    "produtos.json",
+    "produtos.jsonl",
+    "produtos.db",
+];
+
+// Quantos produtos de cada tipo aparecem no resumo de uma recarga
+const LIMITE_RESUMO: usize = 5;
+
+// QR code do último PIX cobrado no caixa
+const PIX_SVG: &str = "pix.svg";
+
+// Produtos listados para o caixa escolher numa busca por nome
+const LIMITE_ESCOLHA: usize = 10;
+
+// Tentativas de senha antes de o programa fechar
+const TENTATIVAS_LOGIN: usize = 3;
+
+// 🔹 Opções do menu e a permissão que cada uma exige; as que o perfil do
+// operador não pode usar nem aparecem
+const OPCOES: &[(&str, &str, Permissao)] = &[
+    (
+        "1",
+        "Buscar produto por nome (parcial)",
+        Permissao::Consultar,
+    ),
+    ("2", "Buscar produto por código", Permissao::Consultar),
+    (
+        "3",
+        "Listar produtos por ordem alfabética",
+        Permissao::Consultar,
+    ),
+    (
+        "4",
+        "Listar produtos por letra inicial",
+        Permissao::Consultar,
+    ),
+    ("5", "Listar produtos por código", Permissao::Consultar),
+    ("6", "Incluir ou renomear produto", Permissao::Cadastrar),
+    ("7", "Remover produto", Permissao::Remover),
+    ("8", "Desfazer última alteração", Permissao::Desfazer),
+    ("9", "Refazer alteração desfeita", Permissao::Desfazer),
+    (
+        "10",
+        "Histórico de nomes de um produto",
+        Permissao::Consultar,
+    ),
+    (
+        "11",
+        "Lançar movimento de estoque",
+        Permissao::MovimentarEstoque,
+    ),
+    ("12", "Ficha de estoque de um produto", Permissao::Consultar),
+    ("13", "Produtos abaixo do mínimo", Permissao::Consultar),
+    (
+        "14",
+        "Estoque mínimo e máximo de um produto",
+        Permissao::Cadastrar,
+    ),
+    ("15", "Ponto de venda (caixa)", Permissao::Vender),
+    ("16", "Montar orçamento", Permissao::Vender),
+    ("17", "Relatórios de margem", Permissao::AlterarPreco),
+    ("18", "Navegar por categorias", Permissao::Consultar),
+    ("19", "Trocar de operador", Permissao::Consultar),
+    ("20", "Sair", Permissao::Consultar),
+];
+
+// 🔹 Estado da tela compartilhado com a thread que vigia o produtos.txt
+struct Tela {
+    // O menu está na tela esperando uma opção
+    no_menu: bool,
+    // Resumos de recarga que chegaram durante uma operação
+    avisos: Vec<String>,
+    sessao: Sessao,
+    // Categoria escolhida na navegação, que filtra as buscas e listagens
+    categoria: Option<String>,
 }
 
-impl Produto {
-    fn new(codigo: &str, nome: &str) -> Self {
-        Produto {
-            codigo: codigo.to_string(),
-            nome: nome.to_string(),
+fn main() {
+    // Sem "--catalogo", vale o primeiro que existir: produtos.txt, .json ou .jsonl.
+    // A codificação é detectada; "--encoding windows-1252" força uma.
+    let mut caminho = CATALOGOS_PADRAO
+        .iter()
+        .find(|c| Path::new(c).exists())
+        .unwrap_or(&CATALOGOS_PADRAO[0])
+        .to_string();
+    let mut codificacao = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--catalogo" => args.next().map(|v| caminho = v).is_some(),
+            "--encoding" => args
+                .next()
+                .and_then(|v| Codificacao::de_nome(&v))
+                .map(|c| codificacao = Some(c))
+                .is_some(),
+            _ => false,
+        };
+        if !ok {
+            println!("⚠️ Argumento inválido: '{}'", arg);
+            println!("{}", USO);
+            return;
         }
     }
-}
+    let caminho = caminho.as_str();
+
+    // O catálogo é lido para a memória (o arquivo continua livre para edição) e
+    // os índices vêm do "produtos.txt.idx" quando ele ainda corresponde ao catálogo
+    let vivo = match CatalogoVivo::abrir(caminho, codificacao) {
+        Ok(vivo) => Arc::new(vivo),
+        Err(_) => {
+            println!("⚠️ Não foi possível abrir '{}'", caminho);
+            return;
+        }
+    };
+    vivo.atual().conversao().relatar(caminho);
+    vivo.recuperacao().relatar(caminho);
+
+    if vivo.atual().is_empty() {
+        println!("Erro: nenhum produto carregado de '{}'.", caminho);
+        return;
+    }
+
+    let operadores = match Operadores::abrir(caminho) {
+        Ok(operadores) => operadores,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível ler '{}': {}",
+                operadores::caminho_dos_operadores(caminho),
+                e
+            );
+            return;
+        }
+    };
+    let Some(sessao) = entrar(&operadores) else {
+        return;
+    };
+    vivo.definir_operador(&sessao.nome);
+
+    let mut estoque = match Estoque::abrir(caminho) {
+        Ok(estoque) => estoque,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível abrir '{}': {}",
+                estoque::caminho_do_estoque(caminho),
+                e
+            );
+            return;
+        }
+    };
 
-// 🔹 Carrega produtos do arquivo produtos.txt (formato: "codigo;nome")
-fn carregar_produtos(caminho: &str) -> Vec<Produto> {
-    let mut produtos = Vec::new();
+    let mut vendas = match Vendas::abrir(caminho) {
+        Ok(vendas) => vendas,
+        Err(e) => {
+            println!(
+                "⚠️ Não foi possível abrir '{}': {}",
+                venda::caminho_das_vendas(caminho),
+                e
+            );
+            return;
+        }
+    };
 
-    if let Ok(file) = File::open(caminho) {
-        let reader = io::BufReader::new(file);
+    // Edições no produtos.txt entram sem precisar sair e abrir de novo
+    let tela = Arc::new(Mutex::new(Tela {
+        no_menu: false,
+        avisos: Vec::new(),
+        sessao,
+        categoria: None,
+    }));
+    let tela_vigia = Arc::clone(&tela);
+    let vigiado = caminho.to_string();
+    vivo.vigiar(Duration::from_secs(1), move |novo, diferencas| {
+        let aviso = resumo_da_recarga(&vigiado, diferencas);
+        let mut tela = tela_vigia.lock().unwrap();
+        if tela.no_menu {
+            println!("\n{}", aviso);
+            exibir_menu(novo, &tela.sessao, tela.categoria.as_deref());
+        } else {
+            tela.avisos.push(aviso);
+        }
+    });
 
-        for linha in reader.lines() {
-            if let Ok(linha) = linha {
-                let partes: Vec<&str> = linha.splitn(2, ';').collect();
-                if partes.len() == 2 {
-                    produtos.push(Produto::new(partes[0].trim(), partes[1].trim()));
+    // Menu
+    loop {
+        {
+            let mut tela = tela.lock().unwrap();
+            for aviso in tela.avisos.drain(..) {
+                println!("\n{}", aviso);
+            }
+            exibir_menu(&vivo.atual(), &tela.sessao, tela.categoria.as_deref());
+            tela.no_menu = true;
+        }
+
+        let mut escolha = String::new();
+        io::stdin()
+            .read_line(&mut escolha)
+            .expect("Erro ao ler entrada");
+        let escolha = escolha.trim();
+
+        let (sessao, categoria) = {
+            let mut tela = tela.lock().unwrap();
+            tela.no_menu = false;
+            (tela.sessao.clone(), tela.categoria.clone())
+        };
+        let categoria = categoria.as_deref();
+        let opcao = OPCOES.iter().find(|(numero, _, _)| *numero == escolha);
+        if let Some((_, _, permissao)) = opcao
+            && let Err(recusa) = sessao.exigir(*permissao)
+        {
+            println!("⚠️ {}", recusa);
+            continue;
+        }
+
+        // Cada operação usa a versão do catálogo vigente quando ela começou;
+        // uma recarga no meio de uma listagem só vale para a próxima operação
+        let catalogo = vivo.atual();
+
+        match escolha {
+            "1" => buscar_por_nome_prefixo(&catalogo, categoria),
+            "2" => buscar_por_codigo(&vivo, &catalogo, &sessao),
+            "3" => listar(
+                &catalogo,
+                catalogo.ordem_por_nome(),
+                "alfabética",
+                categoria,
+            ),
+            "4" => listar_por_letra_inicial(&catalogo, categoria),
+            "5" => listar(&catalogo, catalogo.ordem_por_codigo(), "código", categoria),
+            "6" => gravar_produto(&vivo),
+            "7" => remover_produto(&vivo),
+            "8" => desfazer(&vivo),
+            "9" => refazer(&vivo),
+            "10" => historico_do_produto(&vivo),
+            "11" => lancar_movimento(&vivo, &catalogo, &mut estoque),
+            "12" => ficha_de_estoque(&vivo, &catalogo),
+            "13" => abaixo_do_minimo(&vivo, &catalogo, &mut estoque),
+            "14" => definir_niveis(&vivo, &catalogo),
+            "15" => ponto_de_venda(&vivo, &catalogo, &mut estoque, &mut vendas),
+            "16" => montar_orcamento(&vivo, &catalogo, &mut estoque),
+            "17" => relatorios_de_margem(&catalogo, vivo.caminho()),
+            "18" => {
+                let escolhida = navegar_por_categorias(&catalogo, categoria);
+                tela.lock().unwrap().categoria = escolhida;
+            }
+            // Senha errada ao trocar encerra, como na entrada
+            "19" => match entrar(&operadores) {
+                Some(nova) => {
+                    vivo.definir_operador(&nova.nome);
+                    tela.lock().unwrap().sessao = nova;
                 }
+                None => break,
+            },
+            "20" => {
+                println!("Saindo...");
+                break;
+            }
+            _ => println!("⚠️ Opção inválida. Escolha entre 1 e {}.", OPCOES.len()),
+        }
+    }
+}
+
+fn exibir_menu(catalogo: &Catalogo, sessao: &Sessao, categoria: Option<&str>) {
+    println!(
+        "\n=== Menu MegaStore ({} produtos) - {} ({}) ===",
+        catalogo.len(),
+        sessao.nome,
+        sessao.papel.nome()
+    );
+    if let Some(categoria) = categoria {
+        println!("Buscas e listagens só na categoria '{}'.", categoria);
+    }
+    for (numero, descricao, permissao) in OPCOES {
+        if sessao.pode(*permissao) {
+            println!("{}. {}", numero, descricao);
+        }
+    }
+}
+
+// 🔹 Pede operador e senha. Sem contas cadastradas, segue com o usuário do
+// sistema; depois de algumas senhas erradas, desiste (None)
+fn entrar(operadores: &Operadores) -> Option<Sessao> {
+    if operadores.is_empty() {
+        println!(
+            "Nenhum operador cadastrado: acesso livre. Cadastre com 'megastore operador adicionar'."
+        );
+        return Some(Sessao::sem_cadastro());
+    }
+
+    for _ in 0..TENTATIVAS_LOGIN {
+        println!("Operador:");
+        let mut entrada = String::new();
+        io::stdin()
+            .read_line(&mut entrada)
+            .expect("Erro ao ler entrada");
+        let nome = entrada.trim();
+        let senha = rpassword::prompt_password("Senha: ").unwrap_or_default();
+
+        match operadores.autenticar(nome, &senha) {
+            Some(sessao) => {
+                println!("✅ Bem-vindo, {} ({}).", sessao.nome, sessao.papel.nome());
+                return Some(sessao);
+            }
+            None => println!("⚠️ Operador ou senha incorretos."),
+        }
+    }
+
+    println!("⚠️ Muitas tentativas. Saindo...");
+    None
+}
+
+// 🔹 Inclui um produto novo ou troca o nome de um existente (vai para o diário)
+fn gravar_produto(vivo: &CatalogoVivo) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim().to_string();
+
+    let catalogo = vivo.atual();
+    match catalogo.buscar_por_codigo(&codigo) {
+        Some(id) => println!("Nome atual: {}. Digite o novo nome:", catalogo.nome(id)),
+        None => println!("Produto novo. Digite o nome:"),
+    }
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let nome = entrada.trim();
+
+    let inicio = Instant::now();
+    match vivo.gravar(Produto::new(&codigo, nome)) {
+        Ok(()) => println!(
+            "✅ Produto [{}] {} gravado. Operação concluída em {:.6} segundos.",
+            codigo,
+            nome,
+            inicio.elapsed().as_secs_f64()
+        ),
+        Err(e) => println!("⚠️ Produto não gravado: {}", e),
+    }
+}
+
+// 🔹 Remove um produto pelo código (vai para o diário)
+fn remover_produto(vivo: &CatalogoVivo) {
+    println!("Digite o código do produto a remover:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim();
+
+    let inicio = Instant::now();
+    match vivo.remover(codigo) {
+        Ok(true) => println!(
+            "✅ Produto [{}] removido. Operação concluída em {:.6} segundos.",
+            codigo,
+            inicio.elapsed().as_secs_f64()
+        ),
+        Ok(false) => println!("Nenhum produto encontrado com este código."),
+        Err(e) => println!("⚠️ Produto não removido: {}", e),
+    }
+}
+
+// 🔹 Desfaz a última inclusão, renomeação ou remoção
+fn desfazer(vivo: &CatalogoVivo) {
+    match vivo.desfazer() {
+        Ok(Some(operacao)) => println!("✅ Desfeito: {}", operacao.descricao),
+        Ok(None) => println!("Nada a desfazer."),
+        Err(e) => println!("⚠️ Não foi possível desfazer: {}", e),
+    }
+}
+
+fn refazer(vivo: &CatalogoVivo) {
+    match vivo.refazer() {
+        Ok(Some(operacao)) => println!("✅ Refeito: {}", operacao.descricao),
+        Ok(None) => println!("Nada a refazer."),
+        Err(e) => println!("⚠️ Não foi possível refazer: {}", e),
+    }
+}
+
+// 🔹 Todos os nomes que um código já teve, com data e hora
+fn historico_do_produto(vivo: &CatalogoVivo) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim();
+
+    match historico::nomes_do_produto(vivo.caminho(), codigo) {
+        Ok(nomes) if nomes.is_empty() => {
+            println!("Nenhuma alteração registrada para o código '{}'.", codigo)
+        }
+        Ok(nomes) => {
+            println!("Histórico do produto [{}]:", codigo);
+            for n in nomes {
+                println!(
+                    "{}  {}  ({})",
+                    n.quando.format("%d/%m/%Y %H:%M:%S"),
+                    n.nome.as_deref().unwrap_or("(removido)"),
+                    n.motivo
+                );
             }
         }
+        Err(e) => println!("⚠️ Não foi possível ler o histórico: {}", e),
+    }
+}
+
+// 🔹 Entrada, saída, ajuste (contagem) ou devolução de um produto do catálogo;
+// o movimento também vai para a trilha de auditoria
+fn lancar_movimento(vivo: &CatalogoVivo, catalogo: &Catalogo, estoque: &mut Estoque) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim().to_string();
+    let Some(id) = catalogo.buscar_por_codigo(&codigo) else {
+        println!("Nenhum produto encontrado com este código.");
+        return;
+    };
+    match estoque.saldo(&codigo) {
+        Ok(saldo) => println!(
+            "[{}] {} - saldo atual: {}",
+            codigo,
+            catalogo.nome(id),
+            estoque::formatar_quantidade(saldo)
+        ),
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return;
+        }
+    }
+
+    println!("Tipo (entrada, saída, ajuste, devolução):");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let Some(tipo) = TipoMovimento::de_nome(entrada.trim()) else {
+        println!("⚠️ Tipo inválido. Use entrada, saída, ajuste ou devolução.");
+        return;
+    };
+
+    if tipo == TipoMovimento::Ajuste {
+        println!("Quantidade contada (o saldo passa a ser esta):");
     } else {
-        println!("⚠️ Não foi possível abrir '{}'", caminho);
+        println!("Quantidade:");
     }
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let Some(quantidade) = estoque::ler_quantidade(&entrada) else {
+        println!("⚠️ Quantidade inválida.");
+        return;
+    };
+
+    println!("Motivo (ex: NF 1234, avaria, inventário):");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let motivo = entrada.trim();
 
-    produtos
+    let inicio = Instant::now();
+    let descricao = format!(
+        "[{}] {} de {} ({})",
+        codigo,
+        tipo.nome(),
+        estoque::formatar_quantidade(quantidade),
+        motivo
+    );
+    let resultado = vivo
+        .registrar_evento("estoque", descricao)
+        .and_then(|()| estoque.lancar(&vivo.operador(), &codigo, tipo, quantidade, motivo));
+    match resultado {
+        Ok(saldo) => {
+            if saldo < 0.0 {
+                println!("⚠️ O saldo ficou negativo: confira o estoque do produto.");
+            }
+            println!(
+                "✅ Movimento de {} lançado. Novo saldo: {}. Operação concluída em {:.6} segundos.",
+                tipo.nome(),
+                estoque::formatar_quantidade(saldo),
+                inicio.elapsed().as_secs_f64()
+            );
+        }
+        Err(e) => println!("⚠️ Movimento não lançado: {}", e),
+    }
 }
 
-fn main() {
-    let caminho = "produtos.txt";
-    let produtos = carregar_produtos(caminho);
+// 🔹 Todos os movimentos do produto, com o saldo depois de cada um
+fn ficha_de_estoque(vivo: &CatalogoVivo, catalogo: &Catalogo) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim();
 
-    if produtos.is_empty() {
-        println!("Erro: nenhum produto carregado de '{}'.", caminho);
+    let ficha = match estoque::ficha(vivo.caminho(), codigo) {
+        Ok(ficha) => ficha,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler o estoque: {}", e);
+            return;
+        }
+    };
+    let nome = catalogo
+        .buscar_por_codigo(codigo)
+        .map_or("(fora do catálogo)", |id| catalogo.nome(id));
+    if ficha.is_empty() {
+        println!("[{}] {} - nenhum movimento de estoque.", codigo, nome);
         return;
     }
 
-    // Índice de busca por código usando BTreeMap
-    let mut indice_por_codigo: BTreeMap<String, Produto> = BTreeMap::new();
-    for p in &produtos {
-        indice_por_codigo.insert(p.codigo.clone(), p.clone());
+    println!("Ficha de estoque [{}] {}:", codigo, nome);
+    for linha in &ficha {
+        let m = &linha.movimento;
+        println!(
+            "{}  {:<10} {:>10}  saldo {:>10}  {}  ({})",
+            m.quando.format("%d/%m/%Y %H:%M:%S"),
+            m.tipo.nome(),
+            estoque::formatar_quantidade(m.quantidade),
+            estoque::formatar_quantidade(linha.saldo),
+            m.operador,
+            m.motivo
+        );
     }
+    let saldo = ficha.last().map_or(0.0, |l| l.saldo);
+    println!("Saldo atual: {}", estoque::formatar_quantidade(saldo));
+}
+
+// 🔹 Relatório "Produtos abaixo do mínimo", com a compra sugerida pelo consumo
+// dos últimos dias, agrupado por fornecedor
+fn abaixo_do_minimo(vivo: &CatalogoVivo, catalogo: &Catalogo, estoque: &mut Estoque) {
+    let inicio = Instant::now();
+    let desde = Local::now() - chrono::Duration::days(DIAS_DE_CONSUMO.into());
+    let dados = Cadastro::abrir(vivo.caminho()).and_then(|cadastro| {
+        let consumo = estoque::consumo_desde(vivo.caminho(), desde)?;
+        let saldos = estoque.saldos()?;
+        Ok(reposicao::abaixo_do_minimo(
+            catalogo, &cadastro, saldos, &consumo,
+        ))
+    });
+    let sugestoes = match dados {
+        Ok(sugestoes) => sugestoes,
+        Err(e) => {
+            println!("⚠️ Não foi possível montar o relatório: {}", e);
+            return;
+        }
+    };
 
-    // Índice de busca por nome (prefixos) usando BTreeMap
-    let mut indice_por_nome: BTreeMap<String, Produto> = BTreeMap::new();
-    for p in &produtos {
-        indice_por_nome.insert(p.nome.to_lowercase(), p.clone());
+    if sugestoes.is_empty() {
+        println!("Nenhum produto abaixo do mínimo.");
+    } else {
+        println!("Produtos abaixo do mínimo:");
+        if let Err(e) = reposicao::escrever_lista(io::stdout().lock(), &sugestoes) {
+            println!("⚠️ {}", e);
+        }
     }
+    println!(
+        "✅ {} produtos abaixo do mínimo (consumo dos últimos {} dias). Operação concluída em {:.6} segundos.",
+        sugestoes.len(),
+        DIAS_DE_CONSUMO,
+        inicio.elapsed().as_secs_f64()
+    );
+}
 
-    // Vetores ordenados
-    let mut ordenado_por_nome = produtos.clone();
-    ordenado_por_nome.sort_by(|a, b| a.nome.to_lowercase().cmp(&b.nome.to_lowercase()));
+// Resposta vazia mantém o valor, "-" apaga; None se não for número
+fn ler_nivel(atual: Option<f64>) -> Option<Option<f64>> {
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    match entrada.trim() {
+        "" => Some(atual),
+        "-" => Some(None),
+        texto => estoque::ler_quantidade(texto).map(Some),
+    }
+}
 
-    let mut ordenado_por_codigo = produtos.clone();
-    ordenado_por_codigo.sort_by(|a, b| a.codigo.cmp(&b.codigo));
+// 🔹 Estoque mínimo e máximo próprios do produto (os da categoria valem quando
+// o produto não tem)
+fn definir_niveis(vivo: &CatalogoVivo, catalogo: &Catalogo) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let codigo = entrada.trim();
+    let Some(id) = catalogo.buscar_por_codigo(codigo) else {
+        println!("Nenhum produto encontrado com este código.");
+        return;
+    };
+    let mut produto = catalogo.produto(id);
+    let mut niveis = produto.complemento.niveis;
+    let formatar = |n: Option<f64>| n.map_or("-".to_string(), estoque::formatar_quantidade);
 
-    // Menu
+    println!("[{}] {}", codigo, catalogo.nome(id));
+    println!(
+        "Estoque mínimo (atual: {}; Enter mantém, '-' apaga):",
+        formatar(niveis.minimo)
+    );
+    let Some(minimo) = ler_nivel(niveis.minimo) else {
+        println!("⚠️ Quantidade inválida.");
+        return;
+    };
+    println!(
+        "Estoque máximo (atual: {}; Enter mantém, '-' apaga):",
+        formatar(niveis.maximo)
+    );
+    let Some(maximo) = ler_nivel(niveis.maximo) else {
+        println!("⚠️ Quantidade inválida.");
+        return;
+    };
+    niveis.minimo = minimo;
+    niveis.maximo = maximo;
+    if let Err(motivo) = niveis.validar() {
+        println!("⚠️ {}", motivo);
+        return;
+    }
+
+    produto.complemento.niveis = niveis;
+    let descricao = format!(
+        "[{}] mínimo {}, máximo {}",
+        codigo,
+        formatar(niveis.minimo),
+        formatar(niveis.maximo)
+    );
+    match vivo.substituir(&descricao, vec![produto]) {
+        Ok(0) => println!("Nada mudou."),
+        Ok(_) => println!("✅ {}", descricao),
+        Err(e) => println!("⚠️ Cadastro não gravado: {}", e),
+    }
+}
+
+// Produto pelo código, pelo código de barras (com o peso ou o preço, se for
+// etiqueta de balança) ou, se não for nenhum dos dois, pelas palavras do nome
+// (com mais de um resultado, o caixa escolhe na lista)
+fn escolher_produto(catalogo: &Catalogo, balanca: &Balanca, termo: &str) -> Option<Leitura> {
+    match balanca::localizar(catalogo, balanca, termo) {
+        Ok(Some(leitura)) => return Some(leitura),
+        Ok(None) => {}
+        Err(motivo) => {
+            println!("⚠️ {}; passe o leitor de novo.", motivo);
+            return None;
+        }
+    }
+    let mut palavras = termo.split_whitespace();
+    let mut ids = palavras
+        .next()
+        .map(|p| catalogo.buscar_por_palavra(p))
+        .unwrap_or_default();
+    for palavra in palavras {
+        let outros = catalogo.buscar_por_palavra(palavra);
+        ids.retain(|id| outros.binary_search(id).is_ok());
+    }
+
+    match ids.as_slice() {
+        [] => {
+            println!("Nenhum produto encontrado com '{}'.", termo);
+            None
+        }
+        [id] => Some(Leitura {
+            id: *id,
+            valor: None,
+        }),
+        _ => {
+            ids.truncate(LIMITE_ESCOLHA);
+            for (i, &id) in ids.iter().enumerate() {
+                println!("{}. [{}] {}", i + 1, catalogo.codigo(id), catalogo.nome(id));
+            }
+            println!("Escolha o número (Enter cancela):");
+            let mut entrada = String::new();
+            io::stdin()
+                .read_line(&mut entrada)
+                .expect("Erro ao ler entrada");
+            let numero: usize = entrada.trim().parse().ok()?;
+            let id = *ids.get(numero.checked_sub(1)?)?;
+            Some(Leitura { id, valor: None })
+        }
+    }
+}
+
+// Preço cadastrado ou, sem ele, o que o caixa digitar para esta venda
+fn preco_da_venda(cadastrado: Option<Reais>) -> Option<Reais> {
+    if cadastrado.is_some() {
+        return cadastrado;
+    }
+    println!("Produto sem preço cadastrado. Preço unitário nesta venda:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let preco = Reais::de_texto(&entrada).filter(|&p| p > Reais::ZERO);
+    if preco.is_none() {
+        println!("⚠️ Preço inválido; item não incluído.");
+    }
+    preco
+}
+
+fn exibir_carrinho(carrinho: &Carrinho) {
+    for (i, item) in carrinho.itens().iter().enumerate() {
+        println!(
+            "  {}. [{}] {}  {} x {} = {}",
+            i + 1,
+            item.codigo,
+            item.nome,
+            estoque::formatar_quantidade(item.quantidade),
+            item.preco,
+            item.total()
+        );
+    }
+    println!("  Subtotal: {}", carrinho.total().com_simbolo());
+}
+
+// Como terminou a montagem do carrinho
+enum FimDoCarrinho {
+    Fechar,
+    Cancelar,
+    // "O n" com o carrinho vazio: vender o orçamento n
+    Orcamento(u64),
+}
+
+// 🔹 Monta o carrinho pelo código, pelo leitor ou pelo nome, até o caixa
+// fechar ('F', com algum item), cancelar ('C') ou pedir um orçamento ('O n')
+fn montar_carrinho(
+    catalogo: &Catalogo,
+    balanca: &Balanca,
+    carrinho: &mut Carrinho,
+) -> FimDoCarrinho {
     loop {
-        println!("\n=== Menu MegaStore ({} produtos) ===", produtos.len());
-        println!("1. Buscar produto por nome (parcial)");
-        println!("2. Buscar produto por código");
-        println!("3. Listar produtos por ordem alfabética");
-        println!("4. Listar produtos por letra inicial");
-        println!("5. Listar produtos por código");
-        println!("6. Sair");
+        let mut entrada = String::new();
+        io::stdin()
+            .read_line(&mut entrada)
+            .expect("Erro ao ler entrada");
+        let entrada = entrada.trim();
 
-        let mut escolha = String::new();
-        io::stdin().read_line(&mut escolha).expect("Erro ao ler entrada");
-        let escolha = escolha.trim();
+        match entrada.to_uppercase().as_str() {
+            "" => continue,
+            "C" => return FimDoCarrinho::Cancelar,
+            "F" if carrinho.is_empty() => {
+                println!("⚠️ Nenhum item no carrinho.");
+                continue;
+            }
+            "F" => return FimDoCarrinho::Fechar,
+            comando if comando.starts_with("R ") => {
+                match comando[2..]
+                    .trim()
+                    .parse()
+                    .ok()
+                    .and_then(|n| carrinho.remover(n))
+                {
+                    Some(item) => println!("Item [{}] {} retirado.", item.codigo, item.nome),
+                    None => println!("⚠️ Não há item com esse número."),
+                }
+                exibir_carrinho(carrinho);
+                continue;
+            }
+            comando if comando.starts_with("O ") => {
+                match comando[2..].trim().parse() {
+                    Ok(numero) if carrinho.is_empty() => return FimDoCarrinho::Orcamento(numero),
+                    Ok(_) => println!("⚠️ Há itens no carrinho: cancele ('C') antes."),
+                    Err(_) => println!("⚠️ Número de orçamento inválido."),
+                }
+                continue;
+            }
+            _ => {}
+        }
 
-        match escolha {
-            "1" => buscar_por_nome_prefixo(&indice_por_nome),
-            "2" => buscar_por_codigo(&indice_por_codigo),
-            "3" => listar(&ordenado_por_nome, "alfabética"),
-            "4" => listar_por_letra_inicial(&ordenado_por_nome),
-            "5" => listar(&ordenado_por_codigo, "código"),
-            "6" => {
-                println!("Saindo...");
-                break;
+        let (quantidade, termo) = match entrada.split_once('*') {
+            Some((quantidade, termo)) => (estoque::ler_quantidade(quantidade), termo.trim()),
+            None => (Some(1.0), entrada),
+        };
+        let Some(quantidade) = quantidade.filter(|&q| q > 0.0) else {
+            println!("⚠️ Quantidade inválida.");
+            continue;
+        };
+        let Some(leitura) = escolher_produto(catalogo, balanca, termo) else {
+            continue;
+        };
+
+        let codigo = catalogo.codigo(leitura.id);
+        let cadastrado = catalogo.preco(leitura.id).filter(|&p| p > Reais::ZERO);
+        let (quantidade, preco, etiqueta) = match leitura.valor {
+            // Preço total na etiqueta: a quantidade vem do preço por quilo (ou
+            // por metro), se houver; senão a linha é uma unidade pelo total
+            Some(Valor::Preco(total)) => {
+                let medida = cadastrado.map_or(1.0, |p| {
+                    let medida = total.centavos() as f64 / p.centavos() as f64;
+                    (medida * 1000.0).round() / 1000.0
+                });
+                (
+                    medida * quantidade,
+                    cadastrado.unwrap_or(total),
+                    Some(total.vezes(quantidade)),
+                )
             }
-            _ => println!("⚠️ Opção inválida. Escolha entre 1 e 6."),
+            Some(Valor::Peso(quilos)) => match preco_da_venda(cadastrado) {
+                Some(preco) => (quilos * quantidade, preco, None),
+                None => continue,
+            },
+            None => match preco_da_venda(cadastrado) {
+                Some(preco) => (quantidade, preco, None),
+                None => continue,
+            },
+        };
+
+        carrinho.adicionar(Item {
+            codigo: codigo.to_string(),
+            nome: catalogo.nome(leitura.id).to_string(),
+            quantidade,
+            preco,
+            etiqueta,
+        });
+        exibir_carrinho(carrinho);
+    }
+}
+
+fn abrir_balanca() -> Option<Balanca> {
+    match Balanca::abrir(ARQUIVO_BALANCA) {
+        Ok(balanca) => Some(balanca),
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_BALANCA, e);
+            None
         }
     }
 }
 
-// 🔹 Busca otimizada por nome (prefixo parcial) usando BTreeMap
-fn buscar_por_nome_prefixo(indice: &BTreeMap<String, Produto>) {
-    println!("Digite parte do nome do produto (prefixo):");
+// 🔹 Ponto de venda: monta o carrinho (ou pega um orçamento), fecha a venda
+// com o troco, dá baixa no estoque e imprime o cupom
+fn ponto_de_venda(
+    vivo: &CatalogoVivo,
+    catalogo: &Catalogo,
+    estoque: &mut Estoque,
+    vendas: &mut Vendas,
+) {
+    let Some(balanca) = abrir_balanca() else {
+        return;
+    };
+    let mut carrinho = Carrinho::default();
+
+    println!("=== Ponto de venda ({}) ===", vivo.operador());
+    println!("Digite o código, passe o leitor ou digite parte do nome; '3*0472' para 3 unidades.");
+    println!("'F' fecha a venda, 'R n' tira o item n, 'O n' vende o orçamento n, 'C' cancela.");
+    loop {
+        let vendido = match montar_carrinho(catalogo, &balanca, &mut carrinho) {
+            FimDoCarrinho::Cancelar => {
+                println!("Venda cancelada.");
+                return;
+            }
+            FimDoCarrinho::Fechar => fechar_venda(vivo, estoque, vendas, &carrinho, None),
+            FimDoCarrinho::Orcamento(numero) => vender_orcamento(vivo, estoque, vendas, numero),
+        };
+        if vendido {
+            return;
+        }
+    }
+}
+
+// Mostra o orçamento e cobra pelos preços dele; falso se não foi vendido
+fn vender_orcamento(
+    vivo: &CatalogoVivo,
+    estoque: &mut Estoque,
+    vendas: &mut Vendas,
+    numero: u64,
+) -> bool {
+    let orcamento = match orcamento::buscar(vivo.caminho(), numero) {
+        Ok(Some(orcamento)) => orcamento,
+        Ok(None) => {
+            println!("Nenhum orçamento com o número {}.", numero);
+            return false;
+        }
+        Err(e) => {
+            println!("⚠️ Não foi possível ler os orçamentos: {}", e);
+            return false;
+        }
+    };
+    if let Situacao::Vendido(venda) = orcamento.situacao {
+        println!("⚠️ O orçamento {} já virou a venda {}.", numero, venda);
+        return false;
+    }
+    if orcamento.vencido(Local::now().date_naive()) {
+        println!(
+            "⚠️ O orçamento {} venceu em {}; monte a venda de novo, com os preços de hoje.",
+            numero,
+            orcamento.validade.format("%d/%m/%Y")
+        );
+        return false;
+    }
+    print!("\n{}", orcamento::texto(&orcamento));
+    fechar_venda(vivo, estoque, vendas, &orcamento.carrinho(), Some(numero))
+}
+
+// 🔹 Orçamento: monta a lista como no caixa, grava com a validade e, se o
+// cliente quiser, reserva os itens no estoque
+fn montar_orcamento(vivo: &CatalogoVivo, catalogo: &Catalogo, estoque: &mut Estoque) {
+    let Some(balanca) = abrir_balanca() else {
+        return;
+    };
+    let mut carrinho = Carrinho::default();
+
+    println!("=== Orçamento ({}) ===", vivo.operador());
+    println!("Digite o código, passe o leitor ou digite parte do nome; '3*0472' para 3 unidades.");
+    println!("'F' termina, 'R n' tira o item n, 'C' cancela.");
+    loop {
+        match montar_carrinho(catalogo, &balanca, &mut carrinho) {
+            FimDoCarrinho::Fechar => break,
+            FimDoCarrinho::Cancelar => {
+                println!("Orçamento cancelado.");
+                return;
+            }
+            FimDoCarrinho::Orcamento(_) => {
+                println!("⚠️ Orçamentos são vendidos no ponto de venda.")
+            }
+        }
+    }
+
+    println!("Nome do cliente (Enter = sem nome):");
+    let mut cliente = String::new();
+    io::stdin()
+        .read_line(&mut cliente)
+        .expect("Erro ao ler entrada");
+    let cliente = Some(cliente.trim().to_string()).filter(|c| !c.is_empty());
+    println!("Válido por quantos dias? (Enter = {})", DIAS_DE_VALIDADE);
     let mut entrada = String::new();
-    io::stdin().read_line(&mut entrada).expect("Erro ao ler entrada");
-    let chave = entrada.trim().to_lowercase();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let dias = match entrada.trim() {
+        "" => DIAS_DE_VALIDADE,
+        texto => match texto.parse() {
+            Ok(dias) => dias,
+            Err(_) => {
+                println!("⚠️ Número de dias inválido; usando {}.", DIAS_DE_VALIDADE);
+                DIAS_DE_VALIDADE
+            }
+        },
+    };
 
-    if chave.is_empty() {
-        println!("⚠️ Nenhum termo digitado.");
+    let inicio = Instant::now();
+    let descricao = format!(
+        "orçamento de {} itens, total {}",
+        carrinho.itens().len(),
+        carrinho.total().com_simbolo()
+    );
+    let resultado = vivo
+        .registrar_evento("orcamento", descricao)
+        .and_then(|()| Orcamentos::abrir(vivo.caminho()))
+        .and_then(|mut o| o.criar(&vivo.operador(), cliente, &carrinho, dias));
+    let orcamento = match resultado {
+        Ok(orcamento) => orcamento,
+        Err(e) => {
+            println!("⚠️ Orçamento não gravado: {}", e);
+            return;
+        }
+    };
+    let texto = orcamento::texto(&orcamento);
+    println!("\n{}", texto);
+    println!(
+        "✅ Orçamento {} gravado, válido até {}. Operação concluída em {:.6} segundos.",
+        orcamento.numero,
+        orcamento.validade.format("%d/%m/%Y"),
+        inicio.elapsed().as_secs_f64()
+    );
+
+    println!("Reservar os itens no estoque até a validade? (s/N)");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    if entrada.trim().eq_ignore_ascii_case("s") {
+        reservar_orcamento(vivo, estoque, &orcamento);
+    }
+
+    imprimir(&Documento::do_orcamento(&orcamento));
+    println!("Gravar o orçamento em arquivo? (s/N)");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    if entrada.trim().eq_ignore_ascii_case("s") {
+        let arquivo = format!("orcamento_{:06}.txt", orcamento.numero);
+        match std::fs::write(&arquivo, &texto) {
+            Ok(()) => println!("Orçamento gravado em '{}'.", arquivo),
+            Err(e) => println!("⚠️ Não foi possível gravar '{}': {}", arquivo, e),
+        }
+    }
+}
+
+// Reserva mesmo faltando estoque, avisando do que falta
+fn reservar_orcamento(vivo: &CatalogoVivo, estoque: &mut Estoque, orcamento: &Orcamento) {
+    match orcamento::faltas(vivo.caminho(), estoque, orcamento) {
+        Ok(faltas) => {
+            for (item, disponivel) in faltas {
+                println!(
+                    "⚠️ Estoque insuficiente: [{}] {}: pedido {}, disponível {}",
+                    item.codigo,
+                    item.nome,
+                    estoque::formatar_quantidade(item.quantidade),
+                    estoque::formatar_quantidade(disponivel)
+                );
+            }
+        }
+        Err(e) => println!("⚠️ Não foi possível ler o estoque: {}", e),
+    }
+    let resultado = vivo
+        .registrar_evento(
+            "orcamento",
+            format!("reserva do orçamento {}", orcamento.numero),
+        )
+        .and_then(|()| Orcamentos::abrir(vivo.caminho()))
+        .and_then(|mut o| o.reservar(orcamento.numero));
+    match resultado {
+        Ok(_) => println!(
+            "✅ Itens reservados até {}.",
+            orcamento.validade.format("%d/%m/%Y")
+        ),
+        Err(e) => println!("⚠️ Reserva não gravada: {}", e),
+    }
+}
+
+// Pede o valor pago e registra a venda; falso se o caixa precisa continuar
+// (valor insuficiente ou erro ao gravar)
+fn fechar_venda(
+    vivo: &CatalogoVivo,
+    estoque: &mut Estoque,
+    vendas: &mut Vendas,
+    carrinho: &Carrinho,
+    orcamento: Option<u64>,
+) -> bool {
+    let total = carrinho.total();
+    let recebedor = match Recebedor::abrir(ARQUIVO_PIX) {
+        Ok(recebedor) => recebedor,
+        Err(e) => {
+            println!("⚠️ PIX indisponível ('{}'): {}", ARQUIVO_PIX, e);
+            None
+        }
+    };
+    if recebedor.is_some() {
+        println!(
+            "Total: {}. Valor pago (Enter = valor exato, 'P' = PIX):",
+            total.com_simbolo()
+        );
+    } else {
+        println!(
+            "Total: {}. Valor pago (Enter = valor exato):",
+            total.com_simbolo()
+        );
+    }
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let (pago, pagamento) = match (entrada.trim(), &recebedor) {
+        ("", _) => (Some(total), Pagamento::Dinheiro),
+        ("P" | "p", Some(recebedor)) => {
+            if !cobrar_pix(recebedor, total) {
+                return false;
+            }
+            (Some(total), Pagamento::Pix)
+        }
+        (texto, _) => (Reais::de_texto(texto), Pagamento::Dinheiro),
+    };
+    let Some(pago) = pago.filter(|&p| p >= total) else {
+        println!("⚠️ Valor pago inválido ou menor que o total.");
+        return false;
+    };
+
+    let inicio = Instant::now();
+    let mut descricao = format!(
+        "venda de {} itens, total {}",
+        carrinho.itens().len(),
+        total.com_simbolo()
+    );
+    if let Some(numero) = orcamento {
+        descricao.push_str(&format!(" (orçamento {})", numero));
+    }
+    // Vinda de um orçamento, a venda usa os itens e preços gravados nele
+    let resultado = vivo.registrar_evento("venda", descricao).and_then(|()| {
+        let operador = vivo.operador();
+        match orcamento {
+            Some(numero) => Orcamentos::abrir(vivo.caminho())
+                .and_then(|mut o| o.vender(numero, vendas, estoque, &operador, pago, pagamento))
+                .map(|(_, venda)| venda),
+            None => vendas.registrar(estoque, &operador, carrinho, pago, pagamento),
+        }
+    });
+    let venda = match resultado {
+        Ok(venda) => venda,
+        Err(e) => {
+            println!("⚠️ Venda não registrada: {}", e);
+            return false;
+        }
+    };
+
+    let cupom = venda::cupom(&venda);
+    println!("\n{}", cupom);
+    println!(
+        "✅ Venda {} registrada. Troco: {}. Operação concluída em {:.6} segundos.",
+        venda.numero,
+        venda.troco.com_simbolo(),
+        inicio.elapsed().as_secs_f64()
+    );
+
+    imprimir(&Documento::da_venda(&venda));
+    println!("Gravar o cupom em arquivo? (s/N)");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    if entrada.trim().eq_ignore_ascii_case("s") {
+        let arquivo = format!("cupom_{:06}.txt", venda.numero);
+        match std::fs::write(&arquivo, &cupom) {
+            Ok(()) => println!("Cupom gravado em '{}'.", arquivo),
+            Err(e) => println!("⚠️ Não foi possível gravar '{}': {}", arquivo, e),
+        }
+    }
+    true
+}
+
+// Mostra o QR code do PIX com o total (e grava em PIX_SVG, para uma tela
+// voltada ao cliente); verdadeiro se o caixa confirmar que o pagamento entrou
+fn cobrar_pix(recebedor: &Recebedor, total: Reais) -> bool {
+    let codigo = match pix::br_code(recebedor, total, None) {
+        Ok(codigo) => codigo,
+        Err(motivo) => {
+            println!("⚠️ {}", motivo);
+            return false;
+        }
+    };
+    match pix::qr_terminal(&codigo) {
+        Ok(qr) => print!("{}", qr),
+        Err(motivo) => println!("⚠️ {}", motivo),
+    }
+    println!("PIX copia e cola:\n{}", codigo);
+    match pix::qr_svg(&codigo).map(|svg| std::fs::write(PIX_SVG, svg)) {
+        Ok(Ok(())) => println!("QR code gravado em '{}'.", PIX_SVG),
+        Ok(Err(e)) => println!("⚠️ Não foi possível gravar '{}': {}", PIX_SVG, e),
+        Err(motivo) => println!("⚠️ {}", motivo),
+    }
+
+    println!(
+        "Pagamento de {} recebido no PIX? (s/N)",
+        total.com_simbolo()
+    );
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    entrada.trim().eq_ignore_ascii_case("s")
+}
+
+// Com a impressora térmica configurada, o documento sai nela (se o caixa quiser)
+fn imprimir(documento: &Documento) {
+    let impressora = match Impressora::abrir(ARQUIVO_IMPRESSORA) {
+        Ok(Some(impressora)) => impressora,
+        Ok(None) => return,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_IMPRESSORA, e);
+            return;
+        }
+    };
+    let Some(caminho) = impressora.caminho.as_deref() else {
         return;
+    };
+    println!("Imprimir {} em '{}'? (S/n)", documento.titulo, caminho);
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    if entrada.trim().eq_ignore_ascii_case("n") {
+        return;
+    }
+    let bytes = escpos::gerar(documento, &impressora);
+    if let Err(e) = escpos::enviar(caminho, &bytes) {
+        println!("⚠️ Não foi possível imprimir em '{}': {}", caminho, e);
     }
+}
+
+// 🔹 Relatórios de margem: por produto, por categoria, abaixo da meta e o
+// preço sugerido de um produto pelo markup
+fn relatorios_de_margem(catalogo: &Catalogo, caminho: &str) {
+    let linhas = margem::por_produto(catalogo);
+
+    println!("=== Relatórios de margem ===");
+    println!("1. Margem e markup por produto");
+    println!("2. Margem por categoria");
+    println!("3. Produtos e vendas abaixo da meta");
+    println!("4. Preço sugerido de um produto");
+    let mut escolha = String::new();
+    io::stdin()
+        .read_line(&mut escolha)
+        .expect("Erro ao ler entrada");
 
     let inicio = Instant::now();
+    let saida = io::stdout();
+    let escrito = match escolha.trim() {
+        "1" => margem::escrever_produtos(saida.lock(), &linhas, MARGEM_META, MARKUP_PADRAO),
+        "2" => margem::escrever_categorias(saida.lock(), &linhas, MARGEM_META),
+        "3" => {
+            let abaixo: Vec<_> = linhas
+                .iter()
+                .filter(|l| l.margem < MARGEM_META)
+                .cloned()
+                .collect();
+            margem::escrever_produtos(saida.lock(), &abaixo, MARGEM_META, MARKUP_PADRAO)
+                .and_then(|()| venda::ler(caminho))
+                .and_then(|vendas| {
+                    let vendidos = margem::vendidos_abaixo(&vendas, catalogo, MARGEM_META);
+                    margem::escrever_vendidos(saida.lock(), &vendidos)
+                })
+        }
+        "4" => {
+            preco_sugerido(catalogo);
+            return;
+        }
+        _ => {
+            println!("⚠️ Opção inválida.");
+            return;
+        }
+    };
+    match escrito {
+        Ok(()) => println!(
+            "✅ {} produtos com custo e preço. Operação concluída em {:.6} segundos.",
+            linhas.len(),
+            inicio.elapsed().as_secs_f64()
+        ),
+        Err(e) => println!("⚠️ Não foi possível montar o relatório: {}", e),
+    }
+}
+
+// Custo do produto mais o markup digitado (Enter = MARKUP_PADRAO)
+fn preco_sugerido(catalogo: &Catalogo) {
+    println!("Digite o código do produto:");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let Some(id) = catalogo.buscar_por_codigo(entrada.trim()) else {
+        println!("Nenhum produto encontrado com este código.");
+        return;
+    };
+    let codigo = catalogo.codigo(id);
+    let Some(custo) = catalogo.complemento(id).custo else {
+        println!("⚠️ O produto [{}] não tem custo cadastrado.", codigo);
+        return;
+    };
+    println!(
+        "Markup em % (Enter = {}):",
+        margem::formatar_percentual(MARKUP_PADRAO)
+    );
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let markup = match entrada.trim().trim_end_matches('%') {
+        "" => MARKUP_PADRAO,
+        texto => match texto.replace(',', ".").parse::<f64>() {
+            Ok(markup) if markup > -100.0 => markup,
+            _ => {
+                println!("⚠️ Markup inválido.");
+                return;
+            }
+        },
+    };
+    println!(
+        "[{}] {}  custo {}",
+        codigo,
+        catalogo.nome(id),
+        margem::resumo_do_custo(custo, catalogo.preco(id), markup)
+    );
+}
+
+// 🔹 Navega pela árvore de categorias com a contagem de cada uma. Devolve a
+// categoria que passa a filtrar as buscas e listagens (a mesma de antes, se
+// nada for escolhido)
+fn navegar_por_categorias(catalogo: &Catalogo, categoria: Option<&str>) -> Option<String> {
+    let arvore = No::montar(catalogo);
+    // Começa na categoria do filtro, se ela ainda existir
+    let mut atual = categoria.and_then(|c| arvore.buscar(c)).unwrap_or(&arvore);
+
+    loop {
+        if atual.raiz() {
+            println!("=== Categorias ({} produtos) ===", atual.total);
+        } else {
+            println!("=== {} ({} produtos) ===", atual.caminho, atual.total);
+        }
+        for (i, filho) in atual.filhos.iter().enumerate() {
+            println!("{}. {} ({})", i + 1, filho.nome, filho.total);
+        }
+        if !atual.produtos.is_empty() {
+            println!(
+                "   {} produtos {}",
+                atual.produtos.len(),
+                if atual.raiz() {
+                    "sem categoria"
+                } else {
+                    "sem subcategoria"
+                }
+            );
+        }
+        println!(
+            "Número para abrir, P para listar os produtos, F para filtrar as buscas por esta categoria, V para voltar, T para tirar o filtro ou Enter para sair:"
+        );
+        let mut entrada = String::new();
+        io::stdin()
+            .read_line(&mut entrada)
+            .expect("Erro ao ler entrada");
+
+        match entrada.trim().to_uppercase().as_str() {
+            "" => return categoria.map(str::to_string),
+            "V" => {
+                let acima = atual.caminho.rfind('>').map_or("", |i| &atual.caminho[..i]);
+                atual = arvore.buscar(acima).unwrap_or(&arvore);
+            }
+            "P" => {
+                let inicio = Instant::now();
+                let ids = atual.todos_os_produtos(catalogo);
+                for (i, &id) in ids.iter().enumerate() {
+                    println!("{}. [{}] {}", i + 1, catalogo.codigo(id), catalogo.nome(id));
+                }
+                println!(
+                    "✅ {} itens listados. Operação concluída em {:.6} segundos.",
+                    ids.len(),
+                    inicio.elapsed().as_secs_f64()
+                );
+            }
+            "F" if atual.raiz() => println!("⚠️ Abra uma categoria antes de filtrar."),
+            "F" => {
+                println!("✅ Buscas e listagens filtradas por '{}'.", atual.caminho);
+                return Some(atual.caminho.clone());
+            }
+            "T" => {
+                println!("✅ Buscas e listagens sem filtro de categoria.");
+                return None;
+            }
+            numero => match numero.parse::<usize>() {
+                Ok(n) if (1..=atual.filhos.len()).contains(&n) => atual = &atual.filhos[n - 1],
+                _ => println!("⚠️ Opção inválida."),
+            },
+        }
+    }
+}
 
-    let prefixo_fim = format!("{}~", chave);
-    let mut encontrados = Vec::new();
+// 🔹 Resumo curto do que mudou no catálogo desde a última leitura
+fn resumo_da_recarga(caminho: &str, diferencas: &Diferencas) -> String {
+    let mut linhas = vec![format!(
+        "🔄 '{}' foi atualizado: {} adicionados, {} removidos, {} renomeados, {} com novo código.",
+        caminho,
+        diferencas.adicionados.len(),
+        diferencas.removidos.len(),
+        diferencas.renomeados.len(),
+        diferencas.recodificados.len()
+    )];
 
-    for (_, produto) in indice.range(chave.clone()..prefixo_fim) {
-        encontrados.push(produto);
+    for p in diferencas.adicionados.iter().take(LIMITE_RESUMO) {
+        linhas.push(format!("  + [{}] {}", p.codigo, p.nome));
+    }
+    for p in diferencas.removidos.iter().take(LIMITE_RESUMO) {
+        linhas.push(format!("  - [{}] {}", p.codigo, p.nome));
+    }
+    for (antes, depois) in diferencas.renomeados.iter().take(LIMITE_RESUMO) {
+        linhas.push(format!(
+            "  ~ [{}] {} -> {}",
+            antes.codigo, antes.nome, depois.nome
+        ));
     }
+    for (antes, depois) in diferencas.recodificados.iter().take(LIMITE_RESUMO) {
+        linhas.push(format!(
+            "  # {} [{}] -> [{}]",
+            antes.nome, antes.codigo, depois.codigo
+        ));
+    }
+
+    let total = diferencas.total();
+    let exibidos = linhas.len() - 1;
+    if total > exibidos {
+        linhas.push(format!("  ... e mais {} alterações.", total - exibidos));
+    }
+
+    linhas.join("\n")
+}
 
+// 🔹 Busca otimizada por nome (prefixo parcial) com busca binária na ordem
+// alfabética; com uma categoria escolhida, só os produtos dela
+fn buscar_por_nome_prefixo(catalogo: &Catalogo, categoria: Option<&str>) {
+    println!("Digite parte do nome do produto (prefixo):");
+    let mut entrada = String::new();
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
+    let chave = entrada.trim().to_lowercase();
+
+    if chave.is_empty() {
+        println!("⚠️ Nenhum termo digitado.");
+        return;
+    }
+
+    let inicio = Instant::now();
+    let encontrados = catalogo.buscar_por_prefixo(&chave);
+    let encontrados = na_categoria(catalogo, encontrados, categoria);
     let duracao = inicio.elapsed().as_secs_f64();
 
     if encontrados.is_empty() {
         println!("Nenhum produto encontrado contendo '{}'", chave);
     } else {
         println!("Produtos encontrados contendo '{}':", chave);
-        for p in &encontrados {
-            println!("[{}] {}", p.codigo, p.nome);
+        for &id in encontrados.iter() {
+            println!("[{}] {}", catalogo.codigo(id), catalogo.nome(id));
         }
     }
 
@@ -136,81 +1454,126 @@ fn buscar_por_nome_prefixo(indice: &BTreeMap<String, Produto>) {
     );
 }
 
-// 🔹 Busca exata por código usando BTreeMap
-fn buscar_por_codigo(indice: &BTreeMap<String, Produto>) {
-    println!("Digite o código (ex: 0472):");
+// 🔹 Busca exata por código (busca binária no índice por código) ou, se a
+// entrada vier do leitor, pelo código de barras; fica na trilha de auditoria
+fn buscar_por_codigo(vivo: &CatalogoVivo, catalogo: &Catalogo, sessao: &Sessao) {
+    let balanca = match Balanca::abrir(ARQUIVO_BALANCA) {
+        Ok(balanca) => balanca,
+        Err(e) => {
+            println!("⚠️ Não foi possível ler '{}': {}", ARQUIVO_BALANCA, e);
+            return;
+        }
+    };
+    println!("Digite o código (ex: 0472) ou passe o leitor no código de barras:");
     let mut entrada = String::new();
-    io::stdin().read_line(&mut entrada).expect("Erro ao ler entrada");
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
     let chave = entrada.trim();
 
     let inicio = Instant::now();
-    let mut encontrados = Vec::new();
+    let encontrado = balanca::localizar(catalogo, &balanca, chave);
+    let duracao = inicio.elapsed().as_secs_f64();
+    let encontrado = match encontrado {
+        Ok(encontrado) => encontrado,
+        Err(motivo) => {
+            println!("⚠️ {}", motivo);
+            return;
+        }
+    };
 
-    if let Some(produto) = indice.get(chave) {
-        encontrados.push(produto);
+    let descricao = format!("busca pelo código '{}'", chave);
+    if let Err(e) = vivo.registrar_evento("consultar", descricao) {
+        println!("⚠️ Consulta não registrada na auditoria: {}", e);
     }
 
-    let duracao = inicio.elapsed().as_secs_f64();
-
-    if encontrados.is_empty() {
-        println!("Nenhum produto encontrado com este código.");
-    } else {
-        for p in &encontrados {
-            println!("Encontrado: [{}] {}", p.codigo, p.nome);
+    match &encontrado {
+        Some(leitura) => {
+            let codigo = catalogo.codigo(leitura.id);
+            println!("Encontrado: [{}] {}", codigo, catalogo.nome(leitura.id));
+            let preco = catalogo.preco(leitura.id);
+            if let Some(preco) = preco {
+                println!("Preço: {}", preco.com_simbolo());
+            }
+            if let Some(categoria) = catalogo.categoria(leitura.id) {
+                println!("Categoria: {}", categoria);
+            }
+            // Custo e margem só para quem pode mexer no preço
+            let custo = catalogo.complemento(leitura.id).custo;
+            if let Some(custo) = custo.filter(|_| sessao.pode(Permissao::AlterarPreco)) {
+                println!(
+                    "Custo: {}",
+                    margem::resumo_do_custo(custo, preco, MARKUP_PADRAO)
+                );
+            }
+        }
+        None => println!("Nenhum produto encontrado com este código."),
+    }
+    match encontrado.as_ref().and_then(|l| l.valor) {
+        Some(Valor::Peso(quilos)) => println!(
+            "Etiqueta de balança: {} kg",
+            estoque::formatar_quantidade(quilos)
+        ),
+        Some(Valor::Preco(total)) => {
+            println!("Etiqueta de balança: {}", total.com_simbolo())
         }
+        None => {}
     }
 
     println!(
         "✅ {} itens encontrados. Operação concluída em {:.6} segundos.",
-        encontrados.len(),
+        encontrado.iter().count(),
         duracao
     );
 }
 
-// 🔹 Listagem completa por nome ou código
-fn listar(lista: &[Produto], modo: &str) {
+// 🔹 Listagem completa por nome ou código (ou só a da categoria escolhida)
+fn listar(catalogo: &Catalogo, ordem: &[IdProduto], modo: &str, categoria: Option<&str>) {
     let inicio = Instant::now();
-    println!("Produtos em ordem {}:", modo);
+    let ordem = na_categoria(catalogo, ordem, categoria);
+    match categoria {
+        Some(categoria) => println!("Produtos de '{}' em ordem {}:", categoria, modo),
+        None => println!("Produtos em ordem {}:", modo),
+    }
 
-    for (i, p) in lista.iter().enumerate() {
-        println!("{}. [{}] {}", i + 1, p.codigo, p.nome);
+    for (i, &id) in ordem.iter().enumerate() {
+        println!("{}. [{}] {}", i + 1, catalogo.codigo(id), catalogo.nome(id));
     }
 
     let duracao = inicio.elapsed().as_secs_f64();
 
     println!(
         "✅ {} itens listados. Operação concluída em {:.6} segundos.",
-        lista.len(),
+        ordem.len(),
         duracao
     );
 }
 
-// 🔹 Listar produtos por letra inicial
-fn listar_por_letra_inicial(lista: &[Produto]) {
+// 🔹 Listar produtos por letra inicial (só os da categoria escolhida, se houver)
+fn listar_por_letra_inicial(catalogo: &Catalogo, categoria: Option<&str>) {
     println!("Digite a letra inicial:");
     let mut entrada = String::new();
-    io::stdin().read_line(&mut entrada).expect("Erro ao ler entrada");
+    io::stdin()
+        .read_line(&mut entrada)
+        .expect("Erro ao ler entrada");
     let letra = entrada.trim().to_lowercase();
 
-    if letra.len() != 1 {
+    if letra.chars().count() != 1 {
         println!("⚠️ Digite apenas uma letra.");
         return;
     }
 
     let inicio = Instant::now();
-    let encontrados: Vec<&Produto> = lista
-        .iter()
-        .filter(|p| p.nome.to_lowercase().starts_with(&letra))
-        .collect();
-
+    let encontrados = catalogo.buscar_por_prefixo(&letra);
+    let encontrados = na_categoria(catalogo, encontrados, categoria);
     let duracao = inicio.elapsed().as_secs_f64();
 
     if encontrados.is_empty() {
         println!("Nenhum produto encontrado com '{}'", letra);
     } else {
         println!("Produtos que começam com '{}':", letra);
-        for (i, p) in encontrados.iter().enumerate() {
-            println!("{}. [{}] {}", i + 1, p.codigo, p.nome);
+        for (i, &id) in encontrados.iter().enumerate() {
+            println!("{}. [{}] {}", i + 1, catalogo.codigo(id), catalogo.nome(id));
         }
     }
 
@@ -220,3 +1583,16 @@ fn listar_por_letra_inicial(lista: &[Produto]) {
         duracao
     );
 }
+
+// Os produtos de `ids` que estão na categoria escolhida (todos, sem
+// categoria)
+fn na_categoria<'a>(
+    catalogo: &Catalogo,
+    ids: &'a [IdProduto],
+    categoria: Option<&str>,
+) -> Cow<'a, [IdProduto]> {
+    match categoria {
+        Some(categoria) => Cow::Owned(categorias::filtrar(catalogo, ids, categoria)),
+        None => Cow::Borrowed(ids),
+    }
+}
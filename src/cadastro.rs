@@ -0,0 +1,218 @@
+// 🔹 Dados complementares dos produtos: preço, custo, código de barras,
+// categoria, fornecedor e níveis de estoque. Eles andam com o `Produto` (e
+// passam pelo diário, pelo histórico e pela auditoria como o nome); nos
+// catálogos JSON vão no próprio registro do produto e no SQLite, em colunas
+// da tabela de produtos. O "codigo;nome" não tem
+// onde guardá-los, então ficam em "<catalogo>.cadastro.json", pelo código,
+// regravado junto com o catálogo. O mesmo arquivo guarda os níveis de estoque
+// de cada categoria.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::categorias;
+use crate::dinheiro::Reais;
+use crate::produto::Produto;
+
+// 🔹 Estoque mínimo e máximo (None = não definido)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Niveis {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimo: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximo: Option<f64>,
+}
+
+impl Niveis {
+    pub fn validar(&self) -> Result<(), String> {
+        if self.minimo.is_some_and(|m| m < 0.0) || self.maximo.is_some_and(|m| m < 0.0) {
+            return Err("níveis de estoque não podem ser negativos".to_string());
+        }
+        if let (Some(minimo), Some(maximo)) = (self.minimo, self.maximo)
+            && maximo < minimo
+        {
+            return Err("o estoque máximo não pode ser menor que o mínimo".to_string());
+        }
+        Ok(())
+    }
+}
+
+// 🔹 O que o cadastro guarda de um produto
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Complemento {
+    // Preço de venda
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preco: Option<Reais>,
+    // Custo de compra (para margem e markup)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custo: Option<Reais>,
+    // Código de barras (EAN-8, EAN-13 ou GTIN-14), já conferido por
+    // `gtin::validar` e sem repetir o de outro produto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gtin: Option<String>,
+    // Caminho na árvore de categorias ("Elétrica > Iluminação > Lâmpadas"),
+    // já normalizado por `categorias::normalizar`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categoria: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fornecedor: Option<String>,
+    #[serde(flatten)]
+    pub niveis: Niveis,
+}
+
+impl Complemento {
+    // Nomes dos campos no JSON (os dos níveis vêm achatados)
+    pub const CAMPOS: &[&str] = &[
+        "preco",
+        "custo",
+        "gtin",
+        "categoria",
+        "fornecedor",
+        "minimo",
+        "maximo",
+    ];
+
+    pub fn is_empty(&self) -> bool {
+        *self == Complemento::default()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Arquivo {
+    // Complemento dos produtos de um catálogo "codigo;nome"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    produtos: BTreeMap<String, Complemento>,
+    // Níveis que valem para os produtos da categoria sem níveis próprios
+    #[serde(default)]
+    categorias: BTreeMap<String, Niveis>,
+}
+
+pub fn caminho_do_cadastro(caminho: &str) -> String {
+    format!("{}.cadastro.json", caminho)
+}
+
+// Arquivo inexistente = cadastro vazio
+fn ler_arquivo(caminho: &str) -> io::Result<Option<Arquivo>> {
+    match fs::read_to_string(caminho_do_cadastro(caminho)) {
+        Ok(texto) => serde_json::from_str(&texto)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// Grava num temporário e troca, como o catálogo
+fn gravar_arquivo(caminho: &str, arquivo: &Arquivo) -> io::Result<()> {
+    let texto = serde_json::to_string_pretty(arquivo).map_err(io::Error::other)?;
+    let destino = caminho_do_cadastro(caminho);
+    let temporario = format!("{}.tmp", destino);
+    fs::write(&temporario, texto)?;
+    fs::rename(&temporario, &destino)
+}
+
+// 🔹 Complementos guardados no arquivo do cadastro, pelo código
+pub fn ler_complementos(caminho: &str) -> io::Result<BTreeMap<String, Complemento>> {
+    Ok(ler_arquivo(caminho)?
+        .map(|arquivo| arquivo.produtos)
+        .unwrap_or_default())
+}
+
+// 🔹 Completa os produtos lidos do catálogo com o que está no arquivo do
+// cadastro. Só os produtos sem nenhum dado complementar recebem: num catálogo
+// JSON ou SQLite, o que está no registro vale mais (o arquivo ainda pode ter
+// sobras de quando os dados ficavam só nele, até a próxima gravação).
+pub fn completar(caminho: &str, produtos: &mut [Produto]) -> io::Result<()> {
+    let mut guardados = ler_complementos(caminho)?;
+    if guardados.is_empty() {
+        return Ok(());
+    }
+    for produto in produtos.iter_mut().filter(|p| p.complemento.is_empty()) {
+        if let Some(complemento) = guardados.remove(&produto.codigo) {
+            produto.complemento = complemento;
+        }
+    }
+    Ok(())
+}
+
+// 🔹 Regrava os complementos guardados no arquivo do cadastro: os dos
+// `produtos` (de um catálogo "codigo;nome") ou nenhum, para um catálogo que
+// guarda os dados no próprio registro (JSON ou SQLite). Produto removido do catálogo sai
+// daqui também.
+pub fn gravar_complementos(caminho: &str, produtos: &[Produto]) -> io::Result<()> {
+    let complementos: BTreeMap<String, Complemento> = produtos
+        .iter()
+        .filter(|p| !p.complemento.is_empty())
+        .map(|p| (p.codigo.clone(), p.complemento.clone()))
+        .collect();
+    let arquivo = match ler_arquivo(caminho)? {
+        Some(arquivo) => Arquivo {
+            produtos: complementos,
+            ..arquivo
+        },
+        None if complementos.is_empty() => return Ok(()),
+        None => Arquivo {
+            produtos: complementos,
+            ..Arquivo::default()
+        },
+    };
+    gravar_arquivo(caminho, &arquivo)
+}
+
+// 🔹 Níveis de estoque das categorias de um catálogo
+pub struct Cadastro {
+    caminho: String,
+    categorias: BTreeMap<String, Niveis>,
+}
+
+impl Cadastro {
+    pub fn abrir(caminho: &str) -> io::Result<Cadastro> {
+        let arquivo = ler_arquivo(caminho)?.unwrap_or_default();
+        Ok(Cadastro {
+            caminho: caminho.to_string(),
+            categorias: arquivo.categorias,
+        })
+    }
+
+    pub fn niveis_da_categoria(&self, categoria: &str) -> Niveis {
+        self.categorias.get(categoria).copied().unwrap_or_default()
+    }
+
+    pub fn definir_niveis_da_categoria(&mut self, categoria: &str, niveis: Niveis) {
+        if niveis == Niveis::default() {
+            self.categorias.remove(categoria);
+        } else {
+            self.categorias.insert(categoria.to_string(), niveis);
+        }
+    }
+
+    // 🔹 Níveis que valem para o produto: os dele e, no que faltar, os da
+    // categoria e depois os das categorias acima dela (até o departamento)
+    pub fn niveis(&self, complemento: &Complemento) -> Niveis {
+        let categorias = complemento
+            .categoria
+            .as_deref()
+            .into_iter()
+            .flat_map(categorias::ancestrais);
+        let mut niveis = complemento.niveis;
+        for categoria in categorias {
+            let da_categoria = self.niveis_da_categoria(categoria);
+            niveis.minimo = niveis.minimo.or(da_categoria.minimo);
+            niveis.maximo = niveis.maximo.or(da_categoria.maximo);
+        }
+        niveis
+    }
+
+    // Relê o arquivo para não desfazer os complementos gravados com o
+    // catálogo desde a abertura
+    pub fn salvar(&self) -> io::Result<()> {
+        let arquivo = Arquivo {
+            categorias: self.categorias.clone(),
+            ..ler_arquivo(&self.caminho)?.unwrap_or_default()
+        };
+        gravar_arquivo(&self.caminho, &arquivo)
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Deref;
+
+use memmap2::Mmap;
+
+use crate::cadastro::{self, Complemento, Niveis};
+use crate::codificacao::{self, Codificacao, Conversao};
+use crate::diario::{self, Alteracao};
+use crate::dinheiro::Reais;
+use crate::formato::Formato;
+use crate::indice::{self, Assinatura, Indice};
+use crate::json;
+use crate::produto::Produto;
+use crate::sqlite;
+use crate::texto::{comeca_com_sem_caixa, comparar_sem_caixa};
+
+// Posição do produto no catálogo (ordem das linhas no arquivo)
+pub type IdProduto = u32;
+
+// 🔹 Texto bruto do catálogo: o arquivo mapeado em memória ou um buffer próprio
+enum Arena {
+    Mapeado(Mmap),
+    Memoria(Vec<u8>),
+}
+
+impl Deref for Arena {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Arena::Mapeado(m) => m,
+            Arena::Memoria(v) => v,
+        }
+    }
+}
+
+// Produto sem dados complementares
+static SEM_COMPLEMENTO: Complemento = Complemento {
+    preco: None,
+    custo: None,
+    gtin: None,
+    categoria: None,
+    fornecedor: None,
+    niveis: Niveis {
+        minimo: None,
+        maximo: None,
+    },
+};
+
+// Dados complementares pelo código, só dos produtos que têm algum
+#[derive(Debug, Default)]
+struct Complementos {
+    por_codigo: HashMap<String, Complemento>,
+    // Código de barras -> código do produto
+    por_gtin: HashMap<String, String>,
+}
+
+impl Complementos {
+    fn de_produtos(produtos: &[Produto]) -> Complementos {
+        let mut complementos = Complementos::default();
+        for p in produtos {
+            complementos.incluir(&p.codigo, &p.complemento);
+        }
+        complementos
+    }
+
+    // O primeiro produto com o código fica com o complemento
+    fn incluir(&mut self, codigo: &str, complemento: &Complemento) {
+        if complemento.is_empty() || self.por_codigo.contains_key(codigo) {
+            return;
+        }
+        if let Some(gtin) = &complemento.gtin {
+            self.por_gtin.insert(gtin.clone(), codigo.to_string());
+        }
+        self.por_codigo
+            .insert(codigo.to_string(), complemento.clone());
+    }
+
+    // Os do próprio catálogo mais os guardados no arquivo do cadastro (todos,
+    // num "codigo;nome"; num JSON, só os de produtos sem nada no registro)
+    fn com_cadastro(mut self, caminho: &str) -> io::Result<Complementos> {
+        for (codigo, complemento) in cadastro::ler_complementos(caminho)? {
+            self.incluir(&codigo, &complemento);
+        }
+        Ok(self)
+    }
+}
+
+// 🔹 Catálogo somente leitura: código e nome ficam como faixas de um único arena
+// (sem uma String por produto) e os índices guardam apenas ids de produto. Os
+// dados complementares (preço, código de barras...) ficam à parte, pelo código.
+pub struct Catalogo {
+    arena: Arena,
+    indice: Indice,
+    conversao: Conversao,
+    complementos: Complementos,
+}
+
+impl Catalogo {
+    // 🔹 Mapeia o arquivo ("codigo;nome", JSON, JSON Lines ou SQLite) e monta os índices
+    // em memória. Sem `codificacao`, ela é detectada; um texto que não é UTF-8 é
+    // convertido. Alterações ainda no diário ("<caminho>.log") são reaplicadas.
+    pub fn abrir(caminho: &str, codificacao: Option<Codificacao>) -> io::Result<Catalogo> {
+        let (bruto, _) = mapear(caminho)?;
+        Catalogo::construir(caminho, bruto, codificacao)?.com_diario(caminho)
+    }
+
+    // 🔹 Como `abrir`, mas só com o que está no arquivo: o diário não é
+    // reaplicado (para comparar arquivos como estão em disco)
+    pub fn abrir_sem_diario(
+        caminho: &str,
+        codificacao: Option<Codificacao>,
+    ) -> io::Result<Catalogo> {
+        let (bruto, _) = mapear(caminho)?;
+        Catalogo::construir(caminho, bruto, codificacao)
+    }
+
+    // Usa o texto do arquivo como está ou, se preciso, o que sai da conversão
+    // para UTF-8 e da leitura do JSON
+    fn construir(
+        caminho: &str,
+        bruto: Arena,
+        codificacao: Option<Codificacao>,
+    ) -> io::Result<Catalogo> {
+        let formato = Formato::detectar(caminho, &bruto);
+        let escolhida = codificacao.unwrap_or_else(|| codificacao::detectar(&bruto));
+
+        if formato == Formato::Texto && codificacao::pode_usar_direto(&bruto, escolhida) {
+            return Ok(Catalogo {
+                indice: Indice::construir(&bruto, formato, None),
+                conversao: conversao_direta(&bruto),
+                arena: bruto,
+                complementos: Complementos::default().com_cadastro(caminho)?,
+            });
+        }
+
+        let (arena, conversao, complementos) = converter(caminho, &bruto, formato, escolhida)?;
+        Ok(Catalogo {
+            indice: Indice::construir(&arena, formato, Some(escolhida)),
+            arena,
+            conversao,
+            complementos: complementos.com_cadastro(caminho)?,
+        })
+    }
+
+    // 🔹 Como `abrir`, mas reaproveita o índice gravado em "<caminho>.idx".
+    // Se o catálogo mudou (ou o índice não existe), reconstrói e grava de novo.
+    pub fn abrir_com_indice(
+        caminho: &str,
+        codificacao: Option<Codificacao>,
+    ) -> io::Result<Catalogo> {
+        let (bruto, assinatura) = mapear(caminho)?;
+        Catalogo::indexar(caminho, bruto, assinatura, codificacao)?.com_diario(caminho)
+    }
+
+    // 🔹 Como `abrir_com_indice`, mas copia o texto para a memória em vez de
+    // mapear: o arquivo fica livre para ser editado (e até truncado) por outros
+    // programas enquanto este catálogo está em uso
+    pub fn abrir_sem_bloquear(
+        caminho: &str,
+        codificacao: Option<Codificacao>,
+    ) -> io::Result<(Catalogo, Assinatura)> {
+        let mut file = File::open(caminho)?;
+        let assinatura = Assinatura::de_metadados(&file.metadata()?);
+        let mut texto = Vec::new();
+        file.read_to_end(&mut texto)?;
+        if texto.len() > u32::MAX as usize {
+            return Err(muito_grande());
+        }
+
+        let catalogo = Catalogo::indexar(caminho, Arena::Memoria(texto), assinatura, codificacao)?
+            .com_diario(caminho)?;
+        Ok((catalogo, assinatura))
+    }
+
+    fn indexar(
+        caminho: &str,
+        bruto: Arena,
+        mut assinatura: Assinatura,
+        codificacao: Option<Codificacao>,
+    ) -> io::Result<Catalogo> {
+        let caminho_indice = format!("{}.idx", caminho);
+
+        // O índice só serve se foi feito com a codificação pedida
+        let salvo = Indice::carregar(&caminho_indice, &assinatura, &bruto).filter(|indice| {
+            codificacao.is_none_or(|c| indice.convertido_de().unwrap_or(Codificacao::Utf8) == c)
+        });
+        if let Some(indice) = salvo {
+            return match indice.convertido_de() {
+                None => Ok(Catalogo {
+                    conversao: conversao_direta(&bruto),
+                    arena: bruto,
+                    indice,
+                    complementos: Complementos::default().com_cadastro(caminho)?,
+                }),
+                Some(c) => {
+                    let (arena, conversao, complementos) =
+                        converter(caminho, &bruto, indice.formato(), c)?;
+                    Ok(Catalogo {
+                        arena,
+                        indice,
+                        conversao,
+                        complementos: complementos.com_cadastro(caminho)?,
+                    })
+                }
+            };
+        }
+
+        assinatura.checksum = indice::checksum(&bruto);
+        let catalogo = Catalogo::construir(caminho, bruto, codificacao)?;
+        // Sem permissão de escrita o catálogo funciona do mesmo jeito, só sem o cache
+        let _ = catalogo.indice.gravar(&caminho_indice, &assinatura);
+
+        Ok(catalogo)
+    }
+
+    // O índice gravado é sempre o do arquivo; com alterações no diário, o
+    // catálogo é remontado com elas aplicadas
+    fn com_diario(self, caminho: &str) -> io::Result<Catalogo> {
+        let recuperacao = diario::ler(caminho)?;
+        if recuperacao.alteracoes.is_empty() {
+            return Ok(self);
+        }
+        Ok(self.com_alteracoes(&recuperacao.alteracoes))
+    }
+
+    // 🔹 Cópia do catálogo com as alterações aplicadas (os índices são remontados)
+    pub fn com_alteracoes<'a>(
+        &self,
+        alteracoes: impl IntoIterator<Item = &'a Alteracao>,
+    ) -> Catalogo {
+        let mut produtos = self.produtos();
+        for alteracao in alteracoes {
+            alteracao.aplicar(&mut produtos);
+        }
+        Catalogo {
+            conversao: self.conversao.clone(),
+            ..Catalogo::de_produtos(&produtos)
+        }
+    }
+
+    // 🔹 Monta um catálogo a partir de produtos já carregados
+    pub fn de_produtos(produtos: &[Produto]) -> Catalogo {
+        let texto = texto_de_produtos(produtos);
+        let indice = Indice::construir(&texto, Formato::Texto, None);
+        Catalogo {
+            arena: Arena::Memoria(texto),
+            indice,
+            conversao: Conversao::new(Codificacao::Utf8),
+            complementos: Complementos::de_produtos(produtos),
+        }
+    }
+
+    // Codificação em que o arquivo foi lido e perdas na conversão
+    pub fn conversao(&self) -> &Conversao {
+        &self.conversao
+    }
+
+    pub fn len(&self) -> usize {
+        self.indice.produtos()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn texto(&self, (inicio, fim): (usize, usize)) -> &str {
+        let bytes = self.arena.get(inicio..fim).unwrap_or_default();
+        std::str::from_utf8(bytes).unwrap_or_default()
+    }
+
+    pub fn codigo(&self, id: IdProduto) -> &str {
+        self.texto(self.indice.faixa_codigo(id))
+    }
+
+    pub fn nome(&self, id: IdProduto) -> &str {
+        self.texto(self.indice.faixa_nome(id))
+    }
+
+    // Cópia independente do produto (para quem precisa de um `Produto`)
+    pub fn produto(&self, id: IdProduto) -> Produto {
+        Produto {
+            complemento: self.complemento(id).clone(),
+            ..Produto::new(self.codigo(id), self.nome(id))
+        }
+    }
+
+    // 🔹 Preço, custo, código de barras etc. (tudo None se o produto não tem)
+    pub fn complemento(&self, id: IdProduto) -> &Complemento {
+        self.complementos
+            .por_codigo
+            .get(self.codigo(id))
+            .unwrap_or(&SEM_COMPLEMENTO)
+    }
+
+    pub fn preco(&self, id: IdProduto) -> Option<Reais> {
+        self.complemento(id).preco
+    }
+
+    pub fn categoria(&self, id: IdProduto) -> Option<&str> {
+        self.complemento(id).categoria.as_deref()
+    }
+
+    pub fn produtos(&self) -> Vec<Produto> {
+        (0..self.len() as IdProduto)
+            .map(|id| self.produto(id))
+            .collect()
+    }
+
+    pub fn ordem_por_codigo(&self) -> &[IdProduto] {
+        self.indice.ordem_por_codigo()
+    }
+
+    pub fn ordem_por_nome(&self) -> &[IdProduto] {
+        self.indice.ordem_por_nome()
+    }
+
+    // 🔹 Busca exata por código (busca binária na ordem por código)
+    pub fn buscar_por_codigo(&self, codigo: &str) -> Option<IdProduto> {
+        let ordem = self.ordem_por_codigo();
+        let pos = ordem.partition_point(|&id| self.codigo(id) < codigo);
+        ordem
+            .get(pos)
+            .copied()
+            .filter(|&id| self.codigo(id) == codigo)
+    }
+
+    // 🔹 Produto do código de barras (GTIN)
+    pub fn buscar_por_gtin(&self, gtin: &str) -> Option<IdProduto> {
+        let codigo = self.complementos.por_gtin.get(gtin)?;
+        self.buscar_por_codigo(codigo)
+    }
+
+    // 🔹 Produtos cujo nome começa com `prefixo` (em ordem alfabética)
+    pub fn buscar_por_prefixo(&self, prefixo: &str) -> &[IdProduto] {
+        let prefixo = prefixo.to_lowercase();
+        let ordem = self.ordem_por_nome();
+        let inicio =
+            ordem.partition_point(|&id| comparar_sem_caixa(self.nome(id), &prefixo).is_lt());
+        let fim = inicio
+            + ordem[inicio..].partition_point(|&id| comeca_com_sem_caixa(self.nome(id), &prefixo));
+        &ordem[inicio..fim]
+    }
+
+    // 🔹 Produtos com alguma palavra do nome começando com `termo` (índice invertido)
+    pub fn buscar_por_palavra(&self, termo: &str) -> Vec<IdProduto> {
+        let termo = termo.to_lowercase();
+        let total = self.indice.quantidade_palavras();
+
+        // Palavras ordenadas: busca binária pela primeira >= termo
+        let (mut baixo, mut alto) = (0, total);
+        while baixo < alto {
+            let meio = (baixo + alto) / 2;
+            if self.indice.palavra(meio) < termo.as_str() {
+                baixo = meio + 1;
+            } else {
+                alto = meio;
+            }
+        }
+
+        let mut encontrados: Vec<IdProduto> = (baixo..total)
+            .take_while(|&i| self.indice.palavra(i).starts_with(&termo))
+            .flat_map(|i| self.indice.ocorrencias(i).iter().copied())
+            .collect();
+        encontrados.sort_unstable();
+        encontrados.dedup();
+        encontrados
+    }
+}
+
+// 🔹 Mapeia o catálogo em memória e lê a assinatura (tamanho e data) do arquivo
+fn mapear(caminho: &str) -> io::Result<(Arena, Assinatura)> {
+    let file = File::open(caminho)?;
+    let meta = file.metadata()?;
+    let assinatura = Assinatura::de_metadados(&meta);
+
+    // Arquivos vazios não podem ser mapeados em todas as plataformas
+    if meta.len() == 0 {
+        return Ok((Arena::Memoria(Vec::new()), assinatura));
+    }
+
+    // O mapeamento é só leitura; o catálogo não deve ser truncado por
+    // outro processo enquanto o programa está aberto
+    let mapa = unsafe { Mmap::map(&file)? };
+    if mapa.len() > u32::MAX as usize {
+        return Err(muito_grande());
+    }
+
+    Ok((Arena::Mapeado(mapa), assinatura))
+}
+
+// Texto do arquivo usado direto: só o BOM (pulado pelo índice) é informado
+fn conversao_direta(bruto: &[u8]) -> Conversao {
+    let mut conversao = Conversao::new(Codificacao::Utf8);
+    conversao.bom = bruto.starts_with(b"\xEF\xBB\xBF");
+    conversao
+}
+
+// Converte o texto do arquivo para UTF-8 num buffer próprio; um catálogo em
+// JSON vira o texto "codigo;nome" dos registros válidos (e os complementos
+// deles) e um banco SQLite, o dos produtos gravados nele
+fn converter(
+    caminho: &str,
+    bruto: &[u8],
+    formato: Formato,
+    codificacao: Codificacao,
+) -> io::Result<(Arena, Conversao, Complementos)> {
+    let (produtos, conversao) = match formato {
+        Formato::Sqlite => (
+            sqlite::ler_produtos(caminho)?,
+            Conversao::new(Codificacao::Utf8),
+        ),
+        Formato::Texto => {
+            let (texto, conversao) = codificacao::decodificar(bruto, Some(codificacao));
+            let texto = texto.into_bytes();
+            if texto.len() > u32::MAX as usize {
+                return Err(muito_grande());
+            }
+            return Ok((Arena::Memoria(texto), conversao, Complementos::default()));
+        }
+        Formato::Json | Formato::JsonLinhas => {
+            let (texto, conversao) = codificacao::decodificar(bruto, Some(codificacao));
+            let leitura = if formato == Formato::Json {
+                json::ler_json(&texto)
+                    .map_err(|motivo| io::Error::new(io::ErrorKind::InvalidData, motivo))?
+            } else {
+                json::ler_jsonl(&texto)
+            };
+            (leitura.produtos, conversao)
+        }
+    };
+
+    let texto = texto_de_produtos(&produtos);
+    if texto.len() > u32::MAX as usize {
+        return Err(muito_grande());
+    }
+    Ok((
+        Arena::Memoria(texto),
+        conversao,
+        Complementos::de_produtos(&produtos),
+    ))
+}
+
+fn texto_de_produtos(produtos: &[Produto]) -> Vec<u8> {
+    let mut texto = Vec::new();
+    for p in produtos {
+        texto.extend_from_slice(p.codigo.as_bytes());
+        texto.push(b';');
+        texto.extend_from_slice(p.nome.as_bytes());
+        texto.push(b'\n');
+    }
+    texto
+}
+
+// As faixas de texto do índice usam u32
+fn muito_grande() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "catálogo maior que 4 GiB")
+}
@@ -0,0 +1,212 @@
+// 🔹 Árvore de categorias dos produtos: departamento → categoria →
+// subcategoria. O produto guarda o caminho inteiro na categoria
+// ("Elétrica > Iluminação > Lâmpadas"); um produto só com o departamento
+// ("Elétrica") também vale. Os filtros pegam a subárvore: "Elétrica" inclui
+// tudo o que está abaixo dela. Nomes são comparados sem caixa e sem acentos.
+
+use std::io::{self, Write};
+
+use crate::catalogo::{Catalogo, IdProduto};
+use crate::texto::{comparar_sem_caixa, remover_acentos};
+
+// Entre os níveis do caminho, como ele é gravado e mostrado
+pub const SEPARADOR: &str = " > ";
+
+pub const NIVEIS: [&str; 3] = ["departamento", "categoria", "subcategoria"];
+
+// 🔹 Caminho digitado no formato do cadastro ("elétrica>iluminação" vira
+// "elétrica > iluminação"); recusa nível vazio e mais de três níveis
+pub fn normalizar(texto: &str) -> Result<String, String> {
+    let niveis: Vec<&str> = texto.split('>').map(str::trim).collect();
+    if niveis.iter().any(|n| n.is_empty()) {
+        return Err(format!("categoria inválida: '{}'", texto.trim()));
+    }
+    if niveis.len() > NIVEIS.len() {
+        return Err(format!(
+            "a categoria tem no máximo {} níveis ({})",
+            NIVEIS.len(),
+            NIVEIS.join(" → ")
+        ));
+    }
+    Ok(niveis.join(SEPARADOR))
+}
+
+// Os nomes de cada nível, do departamento para baixo
+pub fn niveis(categoria: &str) -> impl Iterator<Item = &str> {
+    categoria
+        .split('>')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+}
+
+fn mesmo_nome(a: &str, b: &str) -> bool {
+    remover_acentos(a).to_lowercase() == remover_acentos(b).to_lowercase()
+}
+
+// 🔹 A categoria do produto é `filtro` ou fica abaixo dele
+pub fn pertence(categoria: Option<&str>, filtro: &str) -> bool {
+    let Some(categoria) = categoria else {
+        return false;
+    };
+    let mut do_produto = niveis(categoria);
+    niveis(filtro).all(|f| do_produto.next().is_some_and(|n| mesmo_nome(n, f)))
+}
+
+// 🔹 A categoria e as de cima, da mais específica ao departamento:
+// "A > B > C", "A > B", "A"
+pub fn ancestrais(categoria: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(categoria.trim()), |c| {
+        c.rfind('>').map(|i| c[..i].trim_end())
+    })
+}
+
+// 🔹 Só os produtos de `ids` que estão na categoria (ou abaixo dela), na
+// mesma ordem
+pub fn filtrar(catalogo: &Catalogo, ids: &[IdProduto], filtro: &str) -> Vec<IdProduto> {
+    ids.iter()
+        .copied()
+        .filter(|&id| pertence(catalogo.categoria(id), filtro))
+        .collect()
+}
+
+// 🔹 Um nó da árvore; a raiz tem nome e caminho vazios e guarda os produtos
+// sem categoria
+#[derive(Debug, Clone, Default)]
+pub struct No {
+    pub nome: String,
+    // Desde o departamento, com o SEPARADOR
+    pub caminho: String,
+    // Produtos do nó e de todos abaixo dele
+    pub total: usize,
+    // Produtos classificados exatamente aqui, por código
+    pub produtos: Vec<IdProduto>,
+    // Em ordem alfabética
+    pub filhos: Vec<No>,
+}
+
+impl No {
+    // 🔹 A árvore de todo o catálogo
+    pub fn montar(catalogo: &Catalogo) -> No {
+        let mut raiz = No::default();
+        for &id in catalogo.ordem_por_codigo() {
+            let categoria = catalogo.categoria(id);
+            let mut no = &mut raiz;
+            no.total += 1;
+            for nivel in categoria.into_iter().flat_map(niveis) {
+                // A primeira grafia encontrada dá o nome ao nó
+                let i = match no.filhos.iter().position(|f| mesmo_nome(&f.nome, nivel)) {
+                    Some(i) => i,
+                    None => {
+                        let caminho = if no.caminho.is_empty() {
+                            nivel.to_string()
+                        } else {
+                            format!("{}{}{}", no.caminho, SEPARADOR, nivel)
+                        };
+                        no.filhos.push(No {
+                            nome: nivel.to_string(),
+                            caminho,
+                            ..No::default()
+                        });
+                        no.filhos.len() - 1
+                    }
+                };
+                no = &mut no.filhos[i];
+                no.total += 1;
+            }
+            no.produtos.push(id);
+        }
+        raiz.ordenar();
+        raiz
+    }
+
+    fn ordenar(&mut self) {
+        self.filhos
+            .sort_by(|a, b| comparar_sem_caixa(&a.nome, &b.nome));
+        for filho in &mut self.filhos {
+            filho.ordenar();
+        }
+    }
+
+    pub fn raiz(&self) -> bool {
+        self.caminho.is_empty()
+    }
+
+    // 🔹 O nó do caminho ("" = a própria raiz)
+    pub fn buscar(&self, caminho: &str) -> Option<&No> {
+        niveis(caminho).try_fold(self, |no, nivel| {
+            no.filhos.iter().find(|f| mesmo_nome(&f.nome, nivel))
+        })
+    }
+
+    // 🔹 Produtos do nó e de todos abaixo dele, por código
+    pub fn todos_os_produtos(&self, catalogo: &Catalogo) -> Vec<IdProduto> {
+        let mut ids = Vec::with_capacity(self.total);
+        let mut pendentes = vec![self];
+        while let Some(no) = pendentes.pop() {
+            ids.extend_from_slice(&no.produtos);
+            pendentes.extend(&no.filhos);
+        }
+        ids.sort_by(|&a, &b| catalogo.codigo(a).cmp(catalogo.codigo(b)));
+        ids
+    }
+}
+
+// 🔹 A árvore a partir do nó, um nível por recuo, com a contagem de cada
+// categoria e, se pedido, os produtos de cada uma
+pub fn escrever_arvore<W: Write>(
+    mut saida: W,
+    no: &No,
+    catalogo: &Catalogo,
+    com_produtos: bool,
+) -> io::Result<()> {
+    if no.raiz() {
+        writeln!(saida, "Categorias ({} produtos):", no.total)?;
+        for filho in &no.filhos {
+            escrever_no(&mut saida, filho, catalogo, com_produtos, 1)?;
+        }
+        if !no.produtos.is_empty() {
+            writeln!(saida, "  (sem categoria) ({})", no.produtos.len())?;
+            if com_produtos {
+                escrever_produtos(&mut saida, &no.produtos, catalogo, 2)?;
+            }
+        }
+        Ok(())
+    } else {
+        escrever_no(&mut saida, no, catalogo, com_produtos, 0)
+    }
+}
+
+fn escrever_no<W: Write>(
+    saida: &mut W,
+    no: &No,
+    catalogo: &Catalogo,
+    com_produtos: bool,
+    recuo: usize,
+) -> io::Result<()> {
+    writeln!(saida, "{}{} ({})", "  ".repeat(recuo), no.nome, no.total)?;
+    if com_produtos {
+        escrever_produtos(saida, &no.produtos, catalogo, recuo + 1)?;
+    }
+    for filho in &no.filhos {
+        escrever_no(saida, filho, catalogo, com_produtos, recuo + 1)?;
+    }
+    Ok(())
+}
+
+fn escrever_produtos<W: Write>(
+    saida: &mut W,
+    ids: &[IdProduto],
+    catalogo: &Catalogo,
+    recuo: usize,
+) -> io::Result<()> {
+    for &id in ids {
+        writeln!(
+            saida,
+            "{}[{}] {}",
+            "  ".repeat(recuo),
+            catalogo.codigo(id),
+            catalogo.nome(id)
+        )?;
+    }
+    Ok(())
+}
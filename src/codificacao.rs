@@ -0,0 +1,274 @@
+// 🔹 Detecção e conversão da codificação do catálogo. Planilhas exportadas pelo
+// Excel no Windows costumam sair em Windows-1252, com CRLF ou com BOM no início.
+
+const BOM_UTF8: &[u8] = b"\xEF\xBB\xBF";
+const BOM_UTF16_LE: &[u8] = b"\xFF\xFE";
+const BOM_UTF16_BE: &[u8] = b"\xFE\xFF";
+
+// Quantas perdas ficam guardadas com a linha e o motivo (as demais só são contadas)
+const LIMITE_PERDAS: usize = 100;
+
+// Caracteres de 0x80 a 0x9F no Windows-1252 (0 = byte sem caractere definido);
+// de 0xA0 em diante a tabela coincide com o Latin-1
+const WINDOWS_1252: [char; 32] = [
+    '€', '\0', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\0', 'Ž', '\0', '\0', '‘',
+    '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\0', 'ž', 'Ÿ',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codificacao {
+    #[default]
+    Utf8,
+    Windows1252,
+    Latin1,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Codificacao {
+    // Nomes aceitos em `--encoding` (sem diferenciar maiúsculas)
+    pub fn de_nome(nome: &str) -> Option<Codificacao> {
+        match nome.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(Codificacao::Utf8),
+            "windows-1252" | "cp1252" | "ansi" => Some(Codificacao::Windows1252),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(Codificacao::Latin1),
+            "utf-16le" | "utf-16" => Some(Codificacao::Utf16Le),
+            "utf-16be" => Some(Codificacao::Utf16Be),
+            _ => None,
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            Codificacao::Utf8 => "UTF-8",
+            Codificacao::Windows1252 => "Windows-1252",
+            Codificacao::Latin1 => "ISO-8859-1",
+            Codificacao::Utf16Le => "UTF-16LE",
+            Codificacao::Utf16Be => "UTF-16BE",
+        }
+    }
+}
+
+// Trecho que não pôde ser convertido fielmente
+#[derive(Debug, Clone)]
+pub struct Perda {
+    pub linha: usize,
+    pub motivo: String,
+}
+
+// 🔹 Como o texto foi lido: codificação usada, BOM removido e perdas na conversão
+#[derive(Debug, Clone, Default)]
+pub struct Conversao {
+    pub codificacao: Codificacao,
+    pub bom: bool,
+    pub perdas: Vec<Perda>,
+    pub total_perdas: usize,
+}
+
+impl Conversao {
+    pub fn new(codificacao: Codificacao) -> Conversao {
+        Conversao {
+            codificacao,
+            bom: false,
+            perdas: Vec::new(),
+            total_perdas: 0,
+        }
+    }
+
+    // 🔹 Informa a conversão feita e as primeiras perdas (nada, se o arquivo já era UTF-8)
+    pub fn relatar(&self, caminho: &str) {
+        if self.codificacao != Codificacao::Utf8 {
+            println!(
+                "'{}' está em {} e foi convertido para UTF-8.",
+                caminho,
+                self.codificacao.nome()
+            );
+        }
+        if self.total_perdas == 0 {
+            return;
+        }
+
+        println!(
+            "⚠️ {} trechos de '{}' não puderam ser convertidos sem perda:",
+            self.total_perdas, caminho
+        );
+        for perda in self.perdas.iter().take(5) {
+            println!("  linha {}: {}", perda.linha, perda.motivo);
+        }
+        if self.total_perdas > 5 {
+            println!("  ... e mais {}.", self.total_perdas - 5);
+        }
+    }
+
+    fn perder(&mut self, linha: usize, motivo: String) {
+        self.total_perdas += 1;
+        if self.perdas.len() < LIMITE_PERDAS {
+            self.perdas.push(Perda { linha, motivo });
+        }
+    }
+}
+
+// 🔹 BOM decide; sem BOM, um texto que é UTF-8 válido é UTF-8 e o resto é
+// tratado como Windows-1252 (que inclui todos os caracteres do Latin-1)
+pub fn detectar(bytes: &[u8]) -> Codificacao {
+    if bytes.starts_with(BOM_UTF8) {
+        Codificacao::Utf8
+    } else if bytes.starts_with(BOM_UTF16_LE) {
+        Codificacao::Utf16Le
+    } else if bytes.starts_with(BOM_UTF16_BE) {
+        Codificacao::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        Codificacao::Utf8
+    } else {
+        Codificacao::Windows1252
+    }
+}
+
+// 🔹 Verdadeiro se o texto pode ser usado como está (UTF-8 válido, sem CR
+// isolado); BOM e CRLF o índice já sabe ignorar
+pub fn pode_usar_direto(bytes: &[u8], codificacao: Codificacao) -> bool {
+    codificacao == Codificacao::Utf8
+        && std::str::from_utf8(bytes).is_ok()
+        && !bytes
+            .iter()
+            .enumerate()
+            .any(|(i, &b)| b == b'\r' && bytes.get(i + 1) != Some(&b'\n'))
+}
+
+// 🔹 Converte para UTF-8 (detectando a codificação se `codificacao` for None),
+// remove o BOM e troca CRLF e CR isolado por '\n'
+pub fn decodificar(bytes: &[u8], codificacao: Option<Codificacao>) -> (String, Conversao) {
+    let codificacao = codificacao.unwrap_or_else(|| detectar(bytes));
+    let mut conversao = Conversao::new(codificacao);
+
+    let bom = match codificacao {
+        Codificacao::Utf8 => BOM_UTF8,
+        Codificacao::Utf16Le => BOM_UTF16_LE,
+        Codificacao::Utf16Be => BOM_UTF16_BE,
+        _ => b"",
+    };
+    let bytes = match bytes.strip_prefix(bom) {
+        Some(resto) if !bom.is_empty() => {
+            conversao.bom = true;
+            resto
+        }
+        _ => bytes,
+    };
+
+    let texto = match codificacao {
+        Codificacao::Utf8 => de_utf8(bytes, &mut conversao),
+        Codificacao::Windows1252 | Codificacao::Latin1 => de_byte_unico(bytes, &mut conversao),
+        Codificacao::Utf16Le => de_utf16(bytes, u16::from_le_bytes, &mut conversao),
+        Codificacao::Utf16Be => de_utf16(bytes, u16::from_be_bytes, &mut conversao),
+    };
+
+    (normalizar_quebras(texto), conversao)
+}
+
+fn de_utf8(bytes: &[u8], conversao: &mut Conversao) -> String {
+    let mut texto = String::with_capacity(bytes.len());
+    let mut linha = 1;
+
+    for trecho in bytes.utf8_chunks() {
+        texto.push_str(trecho.valid());
+        linha += trecho.valid().matches('\n').count();
+        if !trecho.invalid().is_empty() {
+            texto.push(char::REPLACEMENT_CHARACTER);
+            conversao.perder(
+                linha,
+                format!(
+                    "sequência UTF-8 inválida {:02X?} trocada por '�'",
+                    trecho.invalid()
+                ),
+            );
+        }
+    }
+    texto
+}
+
+fn de_byte_unico(bytes: &[u8], conversao: &mut Conversao) -> String {
+    let windows = conversao.codificacao == Codificacao::Windows1252;
+    let mut texto = String::with_capacity(bytes.len() + bytes.len() / 8);
+    let mut linha = 1;
+
+    for &b in bytes {
+        let c = match b {
+            0x80..=0x9F if windows => match WINDOWS_1252[(b - 0x80) as usize] {
+                '\0' => {
+                    conversao.perder(
+                        linha,
+                        format!(
+                            "byte 0x{:02X} não existe em Windows-1252, trocado por '�'",
+                            b
+                        ),
+                    );
+                    char::REPLACEMENT_CHARACTER
+                }
+                c => c,
+            },
+            0x80..=0x9F => {
+                conversao.perder(
+                    linha,
+                    format!(
+                        "caractere de controle 0x{:02X} (o arquivo parece ser Windows-1252)",
+                        b
+                    ),
+                );
+                b as char
+            }
+            _ => b as char,
+        };
+        if b == b'\n' {
+            linha += 1;
+        }
+        texto.push(c);
+    }
+    texto
+}
+
+// 🔹 O byte do caractere em Windows-1252, se ele existir lá (para quem grava
+// nessa codificação, como o texto das etiquetas em PDF)
+pub fn para_windows_1252(c: char) -> Option<u8> {
+    match c as u32 {
+        0..=0x7F | 0xA0..=0xFF => Some(c as u8),
+        _ => WINDOWS_1252
+            .iter()
+            .position(|&w| w == c && w != '\0')
+            .map(|i| 0x80 + i as u8),
+    }
+}
+
+fn de_utf16(bytes: &[u8], ler: fn([u8; 2]) -> u16, conversao: &mut Conversao) -> String {
+    let unidades = bytes.chunks_exact(2).map(|par| ler([par[0], par[1]]));
+    let mut texto = String::with_capacity(bytes.len() / 2);
+    let mut linha = 1;
+
+    for resultado in char::decode_utf16(unidades) {
+        let c = resultado.unwrap_or_else(|e| {
+            conversao.perder(
+                linha,
+                format!(
+                    "unidade UTF-16 0x{:04X} sem par, trocada por '�'",
+                    e.unpaired_surrogate()
+                ),
+            );
+            char::REPLACEMENT_CHARACTER
+        });
+        if c == '\n' {
+            linha += 1;
+        }
+        texto.push(c);
+    }
+    if bytes.len() % 2 == 1 {
+        conversao.perder(linha, "byte final sem par em UTF-16 ignorado".to_string());
+    }
+    texto
+}
+
+// CRLF (Windows) e CR isolado (Mac antigo) viram '\n'
+fn normalizar_quebras(texto: String) -> String {
+    if !texto.contains('\r') {
+        return texto;
+    }
+    texto.replace("\r\n", "\n").replace('\r', "\n")
+}
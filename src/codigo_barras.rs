@@ -0,0 +1,89 @@
+// 🔹 Desenho dos códigos de barras EAN-13 e Code 128 (subconjunto B) como uma
+// sequência de módulos (true = barra, false = espaço), para quem precisa
+// desenhá-los por conta própria, como as etiquetas em SVG. As impressoras
+// térmicas recebem só o número e desenham sozinhas.
+
+use crate::gtin;
+
+// Dígitos do lado esquerdo com paridade ímpar (L); o lado direito (R) é o
+// complemento e a paridade par (G) é o R de trás para a frente
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+
+// Paridade dos 6 dígitos da esquerda pelo primeiro dígito (que não é desenhado)
+const EAN_PARIDADE: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL",
+    "LGGLGL",
+];
+
+// Larguras barra/espaço de cada símbolo do Code 128, pelo valor (0 a 106)
+const CODE128: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE128_INICIO_B: usize = 104;
+const CODE128_FIM: usize = 106;
+
+fn modulos(padrao: &str) -> impl Iterator<Item = bool> + '_ {
+    padrao.chars().map(|c| c == '1')
+}
+
+// 🔹 Os 95 módulos de um EAN-13 (None se não for um EAN-13 válido)
+pub fn ean13(codigo: &str) -> Option<Vec<bool>> {
+    let codigo = gtin::validar(codigo).ok().filter(|c| c.len() == 13)?;
+    let digitos: Vec<usize> = codigo.bytes().map(|b| (b - b'0') as usize).collect();
+
+    let mut barras = Vec::with_capacity(95);
+    barras.extend(modulos("101"));
+    for (&d, paridade) in digitos[1..7].iter().zip(EAN_PARIDADE[digitos[0]].chars()) {
+        let l = modulos(EAN_L[d]);
+        if paridade == 'L' {
+            barras.extend(l);
+        } else {
+            barras.extend(l.map(|m| !m).collect::<Vec<_>>().into_iter().rev());
+        }
+    }
+    barras.extend(modulos("01010"));
+    for &d in &digitos[7..] {
+        barras.extend(modulos(EAN_L[d]).map(|m| !m));
+    }
+    barras.extend(modulos("101"));
+    Some(barras)
+}
+
+// 🔹 Módulos do Code 128 B do texto (letras, números e pontuação ASCII), com o
+// dígito verificador; None se tiver algum caractere fora disso
+pub fn code128(texto: &str) -> Option<Vec<bool>> {
+    let mut valores = vec![CODE128_INICIO_B];
+    for c in texto.chars() {
+        if !(' '..='~').contains(&c) {
+            return None;
+        }
+        valores.push(c as usize - 32);
+    }
+    let soma: usize = valores.iter().enumerate().map(|(i, &v)| v * i.max(1)).sum();
+    valores.push(soma % 103);
+    valores.push(CODE128_FIM);
+
+    let mut barras = Vec::new();
+    for v in valores {
+        for (i, largura) in CODE128[v].chars().enumerate() {
+            let largura = largura.to_digit(10).unwrap_or(1) as usize;
+            barras.extend(std::iter::repeat_n(i % 2 == 0, largura));
+        }
+    }
+    Some(barras)
+}
@@ -0,0 +1,287 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+
+use crate::codificacao::{self, Codificacao};
+use crate::produto::{Leitura, LinhaInvalida, Produto, validar};
+use crate::texto::remover_acentos;
+
+// 🔹 Colunas da planilha que viram código e nome (pelo título no cabeçalho)
+#[derive(Debug, Clone)]
+pub struct Colunas {
+    pub codigo: String,
+    pub nome: String,
+}
+
+impl Default for Colunas {
+    fn default() -> Self {
+        Colunas {
+            codigo: "codigo".to_string(),
+            nome: "nome".to_string(),
+        }
+    }
+}
+
+// Registro da planilha: linha do arquivo onde começa, texto original e campos
+struct Registro<'a> {
+    linha: usize,
+    conteudo: &'a str,
+    campos: Result<Vec<String>, String>,
+}
+
+// 🔹 Delimitador mais frequente fora de aspas na primeira linha (';' no Excel
+// em português, ',' no padrão RFC 4180); em empate fica o ';'
+pub fn detectar_delimitador(texto: &str) -> char {
+    let mut entre_aspas = false;
+    let (mut pontos_e_virgulas, mut virgulas) = (0, 0);
+
+    for c in texto.chars() {
+        match c {
+            '"' => entre_aspas = !entre_aspas,
+            '\n' if !entre_aspas => break,
+            ';' if !entre_aspas => pontos_e_virgulas += 1,
+            ',' if !entre_aspas => virgulas += 1,
+            _ => {}
+        }
+    }
+
+    if virgulas > pontos_e_virgulas {
+        ','
+    } else {
+        ';'
+    }
+}
+
+// 🔹 Separa o texto em registros segundo o RFC 4180: campos entre aspas podem
+// conter o delimitador e quebras de linha, e "" dentro das aspas vale uma aspa
+fn separar_registros(texto: &str, delimitador: char) -> Vec<Registro<'_>> {
+    let mut registros = Vec::new();
+    let mut campos = Vec::new();
+    let mut campo = String::new();
+    let mut entre_aspas = false;
+    // O campo atual já teve as aspas fechadas: só pode vir delimitador ou fim de linha
+    let mut citado = false;
+    let mut erro = None;
+    let (mut linha, mut inicio_linha, mut inicio) = (1, 1, 0);
+    let mut chars = texto.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        if entre_aspas {
+            match c {
+                '"' if chars.peek().is_some_and(|&(_, p)| p == '"') => {
+                    chars.next();
+                    campo.push('"');
+                }
+                '"' => {
+                    entre_aspas = false;
+                    citado = true;
+                }
+                '\n' => {
+                    linha += 1;
+                    campo.push(c);
+                }
+                _ => campo.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if campo.is_empty() && !citado => entre_aspas = true,
+            '"' => {
+                erro.get_or_insert_with(|| format!("aspas no meio do campo '{}'", campo));
+                campo.push(c);
+            }
+            '\n' => {
+                campos.push(std::mem::take(&mut campo));
+                registros.push(Registro {
+                    linha: inicio_linha,
+                    conteudo: &texto[inicio..pos],
+                    campos: match erro.take() {
+                        Some(e) => Err(e),
+                        None => Ok(std::mem::take(&mut campos)),
+                    },
+                });
+                linha += 1;
+                inicio_linha = linha;
+                inicio = pos + 1;
+                citado = false;
+            }
+            c if c == delimitador => {
+                campos.push(std::mem::take(&mut campo));
+                citado = false;
+            }
+            _ => {
+                if citado {
+                    erro.get_or_insert_with(|| {
+                        format!("texto depois das aspas que fecham o campo '{}'", campo)
+                    });
+                }
+                campo.push(c);
+            }
+        }
+    }
+
+    if entre_aspas {
+        erro = Some("aspas abertas e não fechadas até o fim do arquivo".to_string());
+    }
+    if inicio < texto.len() {
+        campos.push(campo);
+        registros.push(Registro {
+            linha: inicio_linha,
+            conteudo: &texto[inicio..],
+            campos: match erro {
+                Some(e) => Err(e),
+                None => Ok(campos),
+            },
+        });
+    }
+
+    registros
+}
+
+// Títulos são comparados sem acentos, sem maiúsculas e sem espaços nas pontas
+fn mesmo_titulo(a: &str, b: &str) -> bool {
+    remover_acentos(a.trim()).to_lowercase() == remover_acentos(b.trim()).to_lowercase()
+}
+
+// 🔹 Converte o texto da planilha em produtos. A primeira linha é o cabeçalho;
+// cada linha passa pelas mesmas regras de `produto::validar`.
+pub fn importar(
+    texto: &str,
+    delimitador: Option<char>,
+    colunas: &Colunas,
+) -> Result<Leitura, String> {
+    let delimitador = delimitador.unwrap_or_else(|| detectar_delimitador(texto));
+    let mut registros = separar_registros(texto, delimitador).into_iter();
+
+    let cabecalho = match registros.next().map(|r| r.campos) {
+        Some(Ok(cabecalho)) => cabecalho,
+        Some(Err(motivo)) => return Err(format!("cabeçalho inválido: {}", motivo)),
+        None => return Err("planilha vazia".to_string()),
+    };
+    let posicao = |titulo: &str| {
+        cabecalho
+            .iter()
+            .position(|c| mesmo_titulo(c, titulo))
+            .ok_or_else(|| {
+                format!(
+                    "coluna '{}' não encontrada (colunas: {})",
+                    titulo,
+                    cabecalho.join(", ")
+                )
+            })
+    };
+    let col_codigo = posicao(&colunas.codigo)?;
+    let col_nome = posicao(&colunas.nome)?;
+
+    let mut leitura = Leitura::default();
+    for registro in registros {
+        let resultado = registro.campos.and_then(|campos| {
+            if campos.iter().all(|c| c.trim().is_empty()) {
+                return Ok(None);
+            }
+            let (Some(codigo), Some(nome)) = (campos.get(col_codigo), campos.get(col_nome)) else {
+                return Err(format!(
+                    "linha com {} colunas, o cabeçalho tem {}",
+                    campos.len(),
+                    cabecalho.len()
+                ));
+            };
+            let (codigo, nome) = (codigo.trim(), nome.trim());
+            validar(codigo, nome)?;
+            Ok(Some(Produto::new(codigo, nome)))
+        });
+
+        match resultado {
+            Ok(Some(produto)) => leitura.produtos.push(produto),
+            Ok(None) => {}
+            Err(motivo) => leitura.invalidas.push(LinhaInvalida {
+                numero: registro.linha,
+                conteudo: registro.conteudo.to_string(),
+                motivo,
+            }),
+        }
+    }
+
+    Ok(leitura)
+}
+
+// 🔹 Lê uma planilha CSV do disco (detectando codificação e delimitador)
+pub fn ler_csv(
+    caminho: &str,
+    codificacao: Option<Codificacao>,
+    delimitador: Option<char>,
+    colunas: &Colunas,
+) -> io::Result<Leitura> {
+    let (texto, conversao) = codificacao::decodificar(&fs::read(caminho)?, codificacao);
+    let mut leitura = importar(&texto, delimitador, colunas)
+        .map_err(|motivo| io::Error::new(io::ErrorKind::InvalidData, motivo))?;
+    leitura.conversao = conversao;
+    Ok(leitura)
+}
+
+// Campo entre aspas quando contém delimitador, aspas ou quebra de linha
+fn campo(valor: &str, delimitador: char) -> Cow<'_, str> {
+    if valor.contains([delimitador, '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", valor.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(valor)
+    }
+}
+
+// 🔹 Grava pares (código, nome) como CSV com cabeçalho "codigo;nome" e quebras
+// CRLF. Com `bom`, o arquivo começa com o BOM do UTF-8 para o Excel reconhecer
+// os acentos.
+pub fn escrever_csv<'a, W: Write>(
+    saida: W,
+    produtos: impl IntoIterator<Item = (&'a str, &'a str)>,
+    delimitador: char,
+    bom: bool,
+) -> io::Result<usize> {
+    let mut saida = BufWriter::new(saida);
+    let mut total = 0;
+
+    if bom {
+        saida.write_all(b"\xEF\xBB\xBF")?;
+    }
+    write!(saida, "codigo{}nome\r\n", delimitador)?;
+    for (codigo, nome) in produtos {
+        write!(
+            saida,
+            "{}{}{}\r\n",
+            campo(codigo, delimitador),
+            delimitador,
+            campo(nome, delimitador)
+        )?;
+        total += 1;
+    }
+
+    saida.flush()?;
+    Ok(total)
+}
+
+// 🔹 Grava uma tabela qualquer (relatórios) com as mesmas regras de `escrever_csv`
+pub fn escrever_tabela<W: Write>(
+    saida: W,
+    cabecalho: &[&str],
+    linhas: impl IntoIterator<Item = Vec<String>>,
+    delimitador: char,
+    bom: bool,
+) -> io::Result<usize> {
+    let mut saida = BufWriter::new(saida);
+    let mut total = 0;
+    let separador = delimitador.to_string();
+
+    if bom {
+        saida.write_all(b"\xEF\xBB\xBF")?;
+    }
+    write!(saida, "{}\r\n", cabecalho.join(&separador))?;
+    for linha in linhas {
+        let campos: Vec<_> = linha.iter().map(|v| campo(v, delimitador)).collect();
+        write!(saida, "{}\r\n", campos.join(&separador))?;
+        total += 1;
+    }
+
+    saida.flush()?;
+    Ok(total)
+}
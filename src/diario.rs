@@ -0,0 +1,269 @@
+// 🔹 Diário de alterações do catálogo ("<catalogo>.log"). Cada edição é
+// acrescentada ao fim do diário em vez de regravar o catálogo inteiro; ao abrir,
+// o diário é reaplicado sobre o arquivo do catálogo (a última versão compactada).
+//
+// Cada registro é uma linha "<crc32 em hexadecimal> <alteração em JSON>". Uma
+// linha sem '\n' no fim ou com CRC errado é o que sobrou de uma gravação
+// interrompida: ela e o que vier depois são descartados.
+//
+// Outro programa (o `megastore gravar` de outro terminal) pode gravar no
+// mesmo diário: o arquivo fica travado durante cada gravação, durante o corte
+// de um fim incompleto e da leitura até a limpeza na compactação.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::produto::Produto;
+
+// Alterações acumuladas antes de o diário ser compactado no catálogo
+pub const LIMITE_COMPACTACAO: usize = 1000;
+
+// 🔹 Uma edição do catálogo. Aplicar a mesma alteração duas vezes dá o mesmo
+// resultado, então um diário reaplicado sobre um catálogo já compactado (queda
+// entre gravar o catálogo e limpar o diário) não muda nada.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Alteracao {
+    // Inclui o produto ou substitui o que tem o mesmo código, com preço,
+    // custo e os demais dados complementares (campos extras do JSON que o
+    // novo não traz continuam os do produto substituído)
+    Gravar { produto: Box<Produto> },
+    // Tira todos os produtos com o código
+    Remover { codigo: String },
+}
+
+impl Alteracao {
+    pub fn aplicar(&self, produtos: &mut Vec<Produto>) {
+        match self {
+            Alteracao::Gravar { produto } => {
+                let mut novo = Produto::clone(produto);
+                match produtos.iter_mut().find(|p| p.codigo == produto.codigo) {
+                    Some(atual) => {
+                        for (campo, valor) in std::mem::take(&mut atual.extras) {
+                            novo.extras.entry(campo).or_insert(valor);
+                        }
+                        *atual = novo;
+                    }
+                    None => produtos.push(novo),
+                }
+            }
+            Alteracao::Remover { codigo } => produtos.retain(|p| &p.codigo != codigo),
+        }
+    }
+}
+
+// 🔹 O que foi encontrado no diário ao abrir o catálogo
+#[derive(Debug, Default)]
+pub struct Recuperacao {
+    pub alteracoes: Vec<Alteracao>,
+    // Bytes do fim do diário que não formavam registros válidos
+    pub descartados: u64,
+}
+
+impl Recuperacao {
+    // 🔹 Informa as alterações reaplicadas e o trecho descartado (nada, se o diário estava vazio)
+    pub fn relatar(&self, caminho: &str) {
+        if !self.alteracoes.is_empty() {
+            println!(
+                "{} alterações ainda não compactadas foram reaplicadas sobre '{}'.",
+                self.alteracoes.len(),
+                caminho
+            );
+        }
+        if self.descartados > 0 {
+            println!(
+                "⚠️ {} bytes do fim de '{}' estavam incompletos ou corrompidos e foram descartados.",
+                self.descartados,
+                caminho_do_diario(caminho)
+            );
+        }
+    }
+}
+
+pub fn caminho_do_diario(caminho: &str) -> String {
+    format!("{}.log", caminho)
+}
+
+// 🔹 Lê o diário do catálogo sem alterá-lo (diário inexistente = vazio)
+pub fn ler(caminho: &str) -> io::Result<Recuperacao> {
+    match fs::read(caminho_do_diario(caminho)) {
+        Ok(bytes) => Ok(separar_registros(&bytes).0),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Recuperacao::default()),
+        Err(e) => Err(e),
+    }
+}
+
+// Registros válidos e quantos bytes do início eles ocupam
+fn separar_registros(bytes: &[u8]) -> (Recuperacao, u64) {
+    let mut recuperacao = Recuperacao::default();
+    let mut validos = 0;
+
+    for linha in bytes.split_inclusive(|&b| b == b'\n') {
+        match ler_registro(linha) {
+            Some(alteracao) => {
+                recuperacao.alteracoes.push(alteracao);
+                validos += linha.len();
+            }
+            None => break,
+        }
+    }
+
+    recuperacao.descartados = (bytes.len() - validos) as u64;
+    (recuperacao, validos as u64)
+}
+
+fn ler_registro(linha: &[u8]) -> Option<Alteracao> {
+    let linha = linha.strip_suffix(b"\n")?;
+    let (crc, conteudo) = linha.split_at_checked(8)?;
+    let conteudo = conteudo.strip_prefix(b" ")?;
+
+    let crc = u32::from_str_radix(std::str::from_utf8(crc).ok()?, 16).ok()?;
+    if crc != crc32(conteudo) {
+        return None;
+    }
+    serde_json::from_slice(conteudo).ok()
+}
+
+// 🔹 Diário aberto para gravação
+pub struct Diario {
+    caminho: String,
+    arquivo: File,
+    registros: usize,
+    // Tamanho do arquivo depois da nossa última gravação; se mudou, outro
+    // programa gravou ou compactou e os registros são contados de novo
+    tamanho: u64,
+}
+
+impl Diario {
+    // 🔹 Abre (ou cria) o diário do catálogo. Um fim incompleto ou corrompido é
+    // cortado, para que os próximos registros não fiquem depois de lixo.
+    pub fn abrir(caminho: &str) -> io::Result<(Diario, Recuperacao)> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_do_diario(caminho))?;
+        let mut diario = Diario {
+            caminho: caminho.to_string(),
+            arquivo,
+            registros: 0,
+            tamanho: u64::MAX,
+        };
+        // Travado: um fim incompleto pode ser a gravação de outro programa
+        // ainda em andamento
+        diario.arquivo.lock()?;
+        let resultado = diario.recuperar();
+        let _ = diario.arquivo.unlock();
+        Ok((diario, resultado?))
+    }
+
+    // Relê o diário, corta o fim que não forma registros e conta os que ficam
+    // (com o arquivo travado)
+    fn recuperar(&mut self) -> io::Result<Recuperacao> {
+        let bytes = fs::read(caminho_do_diario(&self.caminho))?;
+        let (recuperacao, validos) = separar_registros(&bytes);
+        if recuperacao.descartados > 0 {
+            self.arquivo.set_len(validos)?;
+            self.arquivo.sync_all()?;
+        }
+        self.registros = recuperacao.alteracoes.len();
+        self.tamanho = validos;
+        Ok(recuperacao)
+    }
+
+    // Alterações gravadas desde a última compactação
+    pub fn len(&self) -> usize {
+        self.registros
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registros == 0
+    }
+
+    // 🔹 Acrescenta a alteração e só retorna depois que ela está no disco
+    pub fn registrar(&mut self, alteracao: &Alteracao) -> io::Result<()> {
+        self.arquivo.lock()?;
+        let resultado = self.registrar_travado(alteracao);
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    // Se outro programa gravou desde a nossa última gravação, o diário é
+    // relido (e um fim deixado pela metade por ele, cortado) antes de acrescentar
+    fn registrar_travado(&mut self, alteracao: &Alteracao) -> io::Result<()> {
+        if self.arquivo.metadata()?.len() != self.tamanho {
+            self.recuperar()?;
+        }
+
+        let conteudo = serde_json::to_vec(alteracao).map_err(io::Error::other)?;
+        let mut linha = format!("{:08x} ", crc32(&conteudo)).into_bytes();
+        linha.extend_from_slice(&conteudo);
+        linha.push(b'\n');
+
+        self.arquivo.write_all(&linha)?;
+        self.arquivo.sync_data()?;
+        self.registros += 1;
+        self.tamanho += linha.len() as u64;
+        Ok(())
+    }
+
+    // 🔹 Compactação: passa as alterações do diário para `gravar` (que regrava
+    // o catálogo com elas) e esvazia o diário. O arquivo fica travado do começo
+    // ao fim, para que nada gravado por outro programa no meio se perca.
+    // Devolve quantas alterações foram compactadas.
+    pub fn compactar(
+        &mut self,
+        gravar: impl FnOnce(&[Alteracao]) -> io::Result<()>,
+    ) -> io::Result<usize> {
+        self.arquivo.lock()?;
+        let resultado = self.compactar_travado(gravar);
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    fn compactar_travado(
+        &mut self,
+        gravar: impl FnOnce(&[Alteracao]) -> io::Result<()>,
+    ) -> io::Result<usize> {
+        let alteracoes = self.recuperar()?.alteracoes;
+        if alteracoes.is_empty() {
+            return Ok(0);
+        }
+        gravar(&alteracoes)?;
+
+        self.arquivo.set_len(0)?;
+        self.arquivo.sync_all()?;
+        self.registros = 0;
+        self.tamanho = 0;
+        Ok(alteracoes.len())
+    }
+}
+
+// CRC-32 (IEEE, o mesmo do zip e do PNG), com a tabela montada na compilação
+const TABELA_CRC: [u32; 256] = {
+    let mut tabela = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        tabela[i] = c;
+        i += 1;
+    }
+    tabela
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |c, &b| {
+        TABELA_CRC[((c ^ b as u32) & 0xFF) as usize] ^ (c >> 8)
+    })
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use crate::catalogo::Catalogo;
+use crate::produto::Produto;
+
+// 🔹 Diferenças entre duas versões do catálogo
+#[derive(Debug, Default)]
+pub struct Diferencas {
+    pub adicionados: Vec<Produto>,
+    pub removidos: Vec<Produto>,
+    // Mesmo código com outro nome: (antes, depois)
+    pub renomeados: Vec<(Produto, Produto)>,
+    // Mesmo nome com outro código: (antes, depois)
+    pub recodificados: Vec<(Produto, Produto)>,
+}
+
+impl Diferencas {
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    pub fn total(&self) -> usize {
+        self.adicionados.len()
+            + self.removidos.len()
+            + self.renomeados.len()
+            + self.recodificados.len()
+    }
+}
+
+// 🔹 Compara dois catálogos percorrendo ambos em ordem de código
+pub fn comparar(antigo: &Catalogo, novo: &Catalogo) -> Diferencas {
+    let antigos = pares_ordenados(antigo);
+    let novos = pares_ordenados(novo);
+
+    let mut removidos: Vec<(&str, &str)> = Vec::new();
+    let mut adicionados: Vec<(&str, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < antigos.len() || j < novos.len() {
+        match (antigos.get(i), novos.get(j)) {
+            (Some(a), Some(n)) if a == n => {
+                i += 1;
+                j += 1;
+            }
+            (Some(a), Some(n)) if a < n => {
+                removidos.push(*a);
+                i += 1;
+            }
+            (Some(_), Some(n)) | (None, Some(n)) => {
+                adicionados.push(*n);
+                j += 1;
+            }
+            (Some(a), None) => {
+                removidos.push(*a);
+                i += 1;
+            }
+            (None, None) => break,
+        }
+    }
+
+    // Cada removido pode formar par com um adicionado: primeiro pelo código
+    // (renomeação), depois pelo nome sem diferenciar maiúsculas (recodificação)
+    let mut usado = vec![false; removidos.len()];
+    let mut por_codigo: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    let mut por_nome: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, (codigo, nome)) in removidos.iter().enumerate().rev() {
+        por_codigo.entry(codigo).or_default().push(i);
+        por_nome.entry(nome.to_lowercase()).or_default().push(i);
+    }
+
+    let mut diferencas = Diferencas::default();
+    let mut sem_par = Vec::new();
+
+    for (codigo, nome) in adicionados {
+        match por_codigo
+            .get_mut(codigo)
+            .and_then(|lista| tirar_livre(lista, &mut usado))
+        {
+            Some(i) => diferencas.renomeados.push((
+                Produto::new(removidos[i].0, removidos[i].1),
+                Produto::new(codigo, nome),
+            )),
+            None => sem_par.push((codigo, nome)),
+        }
+    }
+
+    for (codigo, nome) in sem_par {
+        match por_nome
+            .get_mut(&nome.to_lowercase())
+            .and_then(|lista| tirar_livre(lista, &mut usado))
+        {
+            Some(i) => diferencas.recodificados.push((
+                Produto::new(removidos[i].0, removidos[i].1),
+                Produto::new(codigo, nome),
+            )),
+            None => diferencas.adicionados.push(Produto::new(codigo, nome)),
+        }
+    }
+
+    for (i, (codigo, nome)) in removidos.iter().enumerate() {
+        if !usado[i] {
+            diferencas.removidos.push(Produto::new(codigo, nome));
+        }
+    }
+
+    diferencas
+}
+
+// Próximo removido ainda sem par da lista (e o marca como usado)
+fn tirar_livre(lista: &mut Vec<usize>, usado: &mut [bool]) -> Option<usize> {
+    while let Some(i) = lista.pop() {
+        if !usado[i] {
+            usado[i] = true;
+            return Some(i);
+        }
+    }
+    None
+}
+
+// Pares (código, nome) em ordem de código e, para códigos repetidos, de nome
+fn pares_ordenados(catalogo: &Catalogo) -> Vec<(&str, &str)> {
+    let mut pares: Vec<(&str, &str)> = catalogo
+        .ordem_por_codigo()
+        .iter()
+        .map(|&id| (catalogo.codigo(id), catalogo.nome(id)))
+        .collect();
+    pares.sort_unstable();
+    pares
+}
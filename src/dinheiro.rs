@@ -0,0 +1,125 @@
+// 🔹 Valores em reais guardados em centavos inteiros, para as somas de uma
+// venda não acumularem erro de arredondamento. No JSON aparecem como número
+// com casas decimais (12.9), que é como as pessoas e outros sistemas escrevem.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reais(i64);
+
+impl Reais {
+    pub const ZERO: Reais = Reais(0);
+
+    pub fn de_centavos(centavos: i64) -> Reais {
+        Reais(centavos)
+    }
+
+    pub fn centavos(&self) -> i64 {
+        self.0
+    }
+
+    // 🔹 Lê "12,90", "12.90", "1.234,56" ou "R$ 5". Com vírgula, os pontos são
+    // separadores de milhar; sem vírgula, um ponto é a vírgula decimal.
+    pub fn de_texto(texto: &str) -> Option<Reais> {
+        let texto = texto.trim();
+        let texto = texto.strip_prefix("R$").unwrap_or(texto).trim();
+        let (negativo, texto) = match texto.strip_prefix('-') {
+            Some(resto) => (true, resto),
+            None => (false, texto),
+        };
+        let normalizado = if texto.contains(',') {
+            texto.replace('.', "").replace(',', ".")
+        } else {
+            texto.to_string()
+        };
+
+        let (inteiro, fracao) = normalizado
+            .split_once('.')
+            .unwrap_or((normalizado.as_str(), ""));
+        let digitos = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+        if inteiro.is_empty() && fracao.is_empty()
+            || !digitos(inteiro)
+            || !digitos(fracao)
+            || fracao.len() > 2
+        {
+            return None;
+        }
+
+        let reais: i64 = if inteiro.is_empty() {
+            0
+        } else {
+            inteiro.parse().ok()?
+        };
+        let centavos: i64 = format!("{:0<2}", fracao).parse().ok()?;
+        let valor = reais.checked_mul(100)?.checked_add(centavos)?;
+        Some(Reais(if negativo { -valor } else { valor }))
+    }
+
+    // 🔹 Preço vezes quantidade (fracionada para peso e metro), arredondado ao centavo
+    pub fn vezes(&self, quantidade: f64) -> Reais {
+        Reais((self.0 as f64 * quantidade).round() as i64)
+    }
+
+    // Com "R$ " na frente
+    pub fn com_simbolo(&self) -> String {
+        format!("R$ {}", self)
+    }
+}
+
+// "1.234,56"
+impl fmt::Display for Reais {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sinal = if self.0 < 0 { "-" } else { "" };
+        let absoluto = self.0.unsigned_abs();
+        let inteiro = (absoluto / 100).to_string();
+
+        let mut agrupado = String::new();
+        for (i, c) in inteiro.chars().enumerate() {
+            if i > 0 && (inteiro.len() - i).is_multiple_of(3) {
+                agrupado.push('.');
+            }
+            agrupado.push(c);
+        }
+        let texto = format!("{}{},{:02}", sinal, agrupado, absoluto % 100);
+        f.pad(&texto)
+    }
+}
+
+impl Add for Reais {
+    type Output = Reais;
+
+    fn add(self, outro: Reais) -> Reais {
+        Reais(self.0 + outro.0)
+    }
+}
+
+impl Sub for Reais {
+    type Output = Reais;
+
+    fn sub(self, outro: Reais) -> Reais {
+        Reais(self.0 - outro.0)
+    }
+}
+
+impl Sum for Reais {
+    fn sum<I: Iterator<Item = Reais>>(iter: I) -> Reais {
+        iter.fold(Reais::ZERO, |a, b| a + b)
+    }
+}
+
+impl Serialize for Reais {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(self.0 as f64 / 100.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Reais {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Reais, D::Error> {
+        let valor = f64::deserialize(d)?;
+        Ok(Reais((valor * 100.0).round() as i64))
+    }
+}
@@ -0,0 +1,368 @@
+// 🔹 Cupons para impressora térmica em ESC/POS: os bytes que vão direto para a
+// impressora (pelo dispositivo, como /dev/usb/lp0, ou por um arquivo), com a
+// página de código certa para os acentos, colunas de código, descrição,
+// quantidade e preço e o código de barras do número do documento.
+
+use std::fs;
+use std::io;
+
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+
+use crate::dinheiro::Reais;
+use crate::estoque::formatar_quantidade;
+use crate::orcamento::Orcamento;
+use crate::texto::remover_acentos;
+use crate::venda::{Item, LOJA, Venda};
+
+pub const ARQUIVO_IMPRESSORA: &str = "impressora.json";
+
+// Fonte A numa bobina de 80 mm (58 mm: 32)
+pub const COLUNAS_PADRAO: usize = 48;
+
+// Comandos usados
+const INICIAR: &[u8] = &[0x1B, b'@'];
+const NEGRITO_LIGA: &[u8] = &[0x1B, b'E', 1];
+const NEGRITO_DESLIGA: &[u8] = &[0x1B, b'E', 0];
+const ALTURA_DUPLA: &[u8] = &[0x1D, b'!', 0x01];
+const TAMANHO_NORMAL: &[u8] = &[0x1D, b'!', 0x00];
+const AVANCAR_E_CORTAR: &[u8] = &[0x1B, b'd', 4, 0x1D, b'V', 66, 0];
+
+// 🔹 Tabela de caracteres da impressora para os acentos: a CP860 (português)
+// ou a CP850 (multilíngue), que quase toda impressora tem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaginaDeCodigo {
+    Cp860,
+    Cp850,
+}
+
+impl PaginaDeCodigo {
+    pub fn de_nome(nome: &str) -> Option<PaginaDeCodigo> {
+        match nome.to_lowercase().replace('-', "").as_str() {
+            "cp860" | "860" => Some(PaginaDeCodigo::Cp860),
+            "cp850" | "850" => Some(PaginaDeCodigo::Cp850),
+            _ => None,
+        }
+    }
+
+    // Número da tabela no comando ESC t
+    fn numero(&self) -> u8 {
+        match self {
+            PaginaDeCodigo::Cp850 => 2,
+            PaginaDeCodigo::Cp860 => 3,
+        }
+    }
+
+    // Byte do caractere na tabela, para os que o português usa
+    fn byte(&self, c: char) -> Option<u8> {
+        let comum = match c {
+            'Ç' => 0x80,
+            'ü' => 0x81,
+            'é' => 0x82,
+            'â' => 0x83,
+            'à' => 0x85,
+            'ç' => 0x87,
+            'ê' => 0x88,
+            'É' => 0x90,
+            'ô' => 0x93,
+            'Ü' => 0x9A,
+            'á' => 0xA0,
+            'í' => 0xA1,
+            'ó' => 0xA2,
+            'ú' => 0xA3,
+            'ª' => 0xA6,
+            'º' => 0xA7,
+            _ => 0,
+        };
+        if comum != 0 {
+            return Some(comum);
+        }
+        let byte = match self {
+            PaginaDeCodigo::Cp860 => match c {
+                'ã' => 0x84,
+                'Á' => 0x86,
+                'Ê' => 0x89,
+                'Í' => 0x8B,
+                'Ô' => 0x8C,
+                'Ã' => 0x8E,
+                'Â' => 0x8F,
+                'À' => 0x91,
+                'õ' => 0x94,
+                'Ú' => 0x96,
+                'Õ' => 0x99,
+                'Ó' => 0x9F,
+                _ => return None,
+            },
+            PaginaDeCodigo::Cp850 => match c {
+                'Á' => 0xB5,
+                'Â' => 0xB6,
+                'À' => 0xB7,
+                'ã' => 0xC6,
+                'Ã' => 0xC7,
+                'Ê' => 0xD2,
+                'Í' => 0xD6,
+                'Ó' => 0xE0,
+                'Ô' => 0xE2,
+                'õ' => 0xE4,
+                'Õ' => 0xE5,
+                'Ú' => 0xE9,
+                _ => return None,
+            },
+        };
+        Some(byte)
+    }
+
+    // 🔹 Texto nos bytes da tabela; o que ela não tem sai sem acento ou como '?'
+    pub fn codificar(&self, texto: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(texto.len());
+        for c in texto.chars() {
+            if c.is_ascii() {
+                bytes.push(c as u8);
+            } else if let Some(b) = self.byte(c) {
+                bytes.push(b);
+            } else {
+                let sem_acento = remover_acentos(&c.to_string());
+                match sem_acento.chars().next() {
+                    Some(s) if s.is_ascii() => bytes.push(s as u8),
+                    _ => bytes.push(b'?'),
+                }
+            }
+        }
+        bytes
+    }
+}
+
+// 🔹 Como a impressora está configurada ("impressora.json"; sem ele, os padrões)
+#[derive(Debug, Clone, Deserialize)]
+pub struct Impressora {
+    // Dispositivo ou arquivo onde o cupom é gravado
+    #[serde(default)]
+    pub caminho: Option<String>,
+    #[serde(default = "colunas_padrao")]
+    pub colunas: usize,
+    #[serde(default = "pagina_padrao")]
+    pub pagina: PaginaDeCodigo,
+}
+
+fn colunas_padrao() -> usize {
+    COLUNAS_PADRAO
+}
+
+fn pagina_padrao() -> PaginaDeCodigo {
+    PaginaDeCodigo::Cp860
+}
+
+impl Default for Impressora {
+    fn default() -> Impressora {
+        Impressora {
+            caminho: None,
+            colunas: COLUNAS_PADRAO,
+            pagina: pagina_padrao(),
+        }
+    }
+}
+
+impl Impressora {
+    // Arquivo inexistente = sem impressora configurada (None)
+    pub fn abrir(caminho: &str) -> io::Result<Option<Impressora>> {
+        match fs::read_to_string(caminho) {
+            Ok(texto) => serde_json::from_str(&texto)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// 🔹 O que vai impresso: serve para a venda e para outros documentos com itens
+pub struct Documento<'a> {
+    // "Venda 000123"
+    pub titulo: String,
+    pub quando: DateTime<Local>,
+    pub operador: &'a str,
+    pub itens: &'a [Item],
+    // TOTAL primeiro (em destaque), depois pago, troco...
+    pub totais: Vec<(String, Reais)>,
+    pub rodape: Vec<String>,
+    // Conteúdo do código de barras no fim (Code 128)
+    pub codigo_de_barras: String,
+}
+
+impl<'a> Documento<'a> {
+    pub fn da_venda(venda: &'a Venda) -> Documento<'a> {
+        Documento {
+            titulo: format!("Venda {:06}", venda.numero),
+            quando: venda.quando,
+            operador: &venda.operador,
+            itens: &venda.itens,
+            totais: vec![
+                (format!("TOTAL ({} itens)", venda.itens.len()), venda.total),
+                (format!("Pago ({})", venda.pagamento.nome()), venda.pago),
+                ("Troco".to_string(), venda.troco),
+            ],
+            rodape: vec!["Obrigado pela preferência!".to_string()],
+            codigo_de_barras: format!("{:06}", venda.numero),
+        }
+    }
+
+    pub fn do_orcamento(orcamento: &'a Orcamento) -> Documento<'a> {
+        let mut rodape = Vec::new();
+        if let Some(cliente) = &orcamento.cliente {
+            rodape.push(format!("Cliente: {}", cliente));
+        }
+        rodape.push(format!(
+            "Válido até {}",
+            orcamento.validade.format("%d/%m/%Y")
+        ));
+        rodape.push("Não é documento fiscal".to_string());
+        Documento {
+            titulo: format!("Orçamento {:06}", orcamento.numero),
+            quando: orcamento.quando,
+            operador: &orcamento.operador,
+            itens: &orcamento.itens,
+            totais: vec![(
+                format!("TOTAL ({} itens)", orcamento.itens.len()),
+                orcamento.total,
+            )],
+            rodape,
+            // "O" na frente para não confundir com o número de uma venda
+            codigo_de_barras: format!("O{:06}", orcamento.numero),
+        }
+    }
+}
+
+// Colunas da linha de valores do item: quantidade, preço e total
+const COLUNA_QTD: usize = 9;
+const COLUNA_PRECO: usize = 11;
+const COLUNA_TOTAL: usize = 12;
+
+fn cortar(texto: &str, largura: usize) -> String {
+    texto.chars().take(largura).collect()
+}
+
+// Texto à esquerda e valor à direita na largura do papel
+fn com_valor(texto: &str, valor: &str, colunas: usize) -> String {
+    let espaco = colunas.saturating_sub(valor.chars().count());
+    format!("{:<espaco$}{}", cortar(texto, espaco), valor)
+}
+
+// Descrição quebrada em linhas da largura do papel
+fn quebrar(texto: &str, colunas: usize) -> Vec<String> {
+    let chars: Vec<char> = texto.chars().collect();
+    chars
+        .chunks(colunas.max(1))
+        .map(|linha| linha.iter().collect())
+        .collect()
+}
+
+// 🔹 Bytes ESC/POS do documento
+pub fn gerar(documento: &Documento, impressora: &Impressora) -> Vec<u8> {
+    let colunas = impressora.colunas;
+    let pagina = impressora.pagina;
+    let traco = "-".repeat(colunas);
+    let mut b = Vec::new();
+    let linha = |b: &mut Vec<u8>, texto: &str| {
+        b.extend(pagina.codificar(texto));
+        b.push(b'\n');
+    };
+
+    b.extend(INICIAR);
+    b.extend([0x1B, b't', pagina.numero()]);
+
+    // Cabeçalho centralizado
+    b.extend(alinhar(1));
+    b.extend(NEGRITO_LIGA);
+    b.extend(ALTURA_DUPLA);
+    linha(&mut b, LOJA);
+    b.extend(TAMANHO_NORMAL);
+    b.extend(NEGRITO_DESLIGA);
+    linha(&mut b, &documento.titulo);
+    linha(
+        &mut b,
+        &documento.quando.format("%d/%m/%Y %H:%M:%S").to_string(),
+    );
+    linha(&mut b, &format!("Operador: {}", documento.operador));
+    b.extend(alinhar(0));
+
+    // Itens: código e descrição numa linha, quantidade, preço e total em colunas na outra
+    linha(&mut b, &traco);
+    linha(&mut b, "Código Descrição");
+    let valores = |qtd: &str, preco: &str, total: &str| {
+        let recuo = colunas.saturating_sub(COLUNA_QTD + COLUNA_PRECO + COLUNA_TOTAL);
+        format!(
+            "{:recuo$}{:>COLUNA_QTD$}{:>COLUNA_PRECO$}{:>COLUNA_TOTAL$}",
+            "", qtd, preco, total
+        )
+    };
+    linha(&mut b, &valores("Qtd", "Preço", "Total"));
+    linha(&mut b, &traco);
+    for item in documento.itens {
+        for parte in quebrar(&format!("{} {}", item.codigo, item.nome), colunas) {
+            linha(&mut b, &parte);
+        }
+        linha(
+            &mut b,
+            &valores(
+                &formatar_quantidade(item.quantidade),
+                &item.preco.to_string(),
+                &item.total().to_string(),
+            ),
+        );
+    }
+    linha(&mut b, &traco);
+
+    for (i, (rotulo, valor)) in documento.totais.iter().enumerate() {
+        if i == 0 {
+            b.extend(NEGRITO_LIGA);
+        }
+        linha(&mut b, &com_valor(rotulo, &valor.com_simbolo(), colunas));
+        if i == 0 {
+            b.extend(NEGRITO_DESLIGA);
+        }
+    }
+    linha(&mut b, &traco);
+
+    b.extend(alinhar(1));
+    for texto in &documento.rodape {
+        linha(&mut b, texto);
+    }
+    b.extend(codigo_de_barras(&documento.codigo_de_barras));
+    b.extend(alinhar(0));
+    b.extend(AVANCAR_E_CORTAR);
+    b
+}
+
+// ESC a: 0 esquerda, 1 centro, 2 direita
+fn alinhar(posicao: u8) -> [u8; 3] {
+    [0x1B, b'a', posicao]
+}
+
+// Code 128 (subconjunto B) com o texto legível embaixo
+fn codigo_de_barras(conteudo: &str) -> Vec<u8> {
+    let dados: Vec<u8> = conteudo
+        .bytes()
+        .filter(|b| (b' '..=b'~').contains(b))
+        .take(200)
+        .collect();
+    if dados.is_empty() {
+        return Vec::new();
+    }
+    let mut b = vec![
+        0x1D, b'h', 60, // altura em pontos
+        0x1D, b'w', 2, // largura do módulo
+        0x1D, b'H', 2, // texto embaixo
+        0x1D, b'k', 73,
+    ];
+    b.push((dados.len() + 2) as u8);
+    b.extend(b"{B");
+    b.extend(dados);
+    b.push(b'\n');
+    b
+}
+
+// 🔹 Grava os bytes no dispositivo da impressora ou num arquivo
+pub fn enviar(caminho: &str, bytes: &[u8]) -> io::Result<()> {
+    fs::write(caminho, bytes)
+}
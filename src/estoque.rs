@@ -0,0 +1,273 @@
+// 🔹 Estoque dos produtos ("<catalogo>.estoque.jsonl"), pelo código. Cada linha
+// é um movimento em JSON (entrada, saída, ajuste ou devolução) com motivo,
+// operador, data e hora. O saldo não é guardado: é a soma dos movimentos.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TipoMovimento {
+    // Compra ou transferência recebida
+    Entrada,
+    // Venda, perda ou consumo
+    Saida,
+    // Contagem do inventário: a quantidade passa a ser o saldo
+    Ajuste,
+    // Mercadoria que o cliente devolveu
+    Devolucao,
+}
+
+impl TipoMovimento {
+    pub fn de_nome(nome: &str) -> Option<TipoMovimento> {
+        match nome.to_lowercase().as_str() {
+            "entrada" => Some(TipoMovimento::Entrada),
+            "saida" | "saída" => Some(TipoMovimento::Saida),
+            "ajuste" => Some(TipoMovimento::Ajuste),
+            "devolucao" | "devolução" => Some(TipoMovimento::Devolucao),
+            _ => None,
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            TipoMovimento::Entrada => "entrada",
+            TipoMovimento::Saida => "saída",
+            TipoMovimento::Ajuste => "ajuste",
+            TipoMovimento::Devolucao => "devolução",
+        }
+    }
+}
+
+// 🔹 Um lançamento no estoque. A quantidade é sempre positiva (fracionada para
+// produtos vendidos por peso ou metro); o tipo diz o que ela faz com o saldo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Movimento {
+    pub quando: DateTime<Local>,
+    pub operador: String,
+    pub codigo: String,
+    pub tipo: TipoMovimento,
+    pub quantidade: f64,
+    pub motivo: String,
+}
+
+impl Movimento {
+    pub fn aplicar(&self, saldo: f64) -> f64 {
+        match self.tipo {
+            TipoMovimento::Entrada | TipoMovimento::Devolucao => saldo + self.quantidade,
+            TipoMovimento::Saida => saldo - self.quantidade,
+            TipoMovimento::Ajuste => self.quantidade,
+        }
+    }
+}
+
+// 🔹 Uma linha da ficha de estoque: o movimento e o saldo depois dele
+#[derive(Debug, Clone)]
+pub struct LinhaDaFicha {
+    pub movimento: Movimento,
+    pub saldo: f64,
+}
+
+pub fn caminho_do_estoque(caminho: &str) -> String {
+    format!("{}.estoque.jsonl", caminho)
+}
+
+// 🔹 Quantidade como o balconista escreve: "12", "1,5", "0,375"
+pub fn formatar_quantidade(quantidade: f64) -> String {
+    let texto = format!("{:.3}", quantidade);
+    let texto = texto.trim_end_matches('0').trim_end_matches('.');
+    match texto {
+        "-0" => "0".to_string(),
+        t => t.replace('.', ","),
+    }
+}
+
+// Aceita vírgula ou ponto decimal
+pub fn ler_quantidade(texto: &str) -> Option<f64> {
+    texto
+        .trim()
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|q| q.is_finite())
+}
+
+// Movimentos gravados e quantos bytes ocupam as linhas completas; uma linha
+// sem '\n' no fim (queda no meio da gravação) é ignorada
+fn ler_movimentos(caminho: &str) -> io::Result<(Vec<Movimento>, u64)> {
+    let bytes = match fs::read(caminho_do_estoque(caminho)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e),
+    };
+    let completas = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let movimentos = bytes[..completas]
+        .split(|&b| b == b'\n')
+        .filter_map(|linha| serde_json::from_slice(linha).ok())
+        .collect();
+    Ok((movimentos, completas as u64))
+}
+
+// 🔹 Ficha de estoque do código: todos os movimentos, do mais antigo ao mais
+// recente, com o saldo depois de cada um
+pub fn ficha(caminho: &str, codigo: &str) -> io::Result<Vec<LinhaDaFicha>> {
+    let mut saldo = 0.0;
+    Ok(ler_movimentos(caminho)?
+        .0
+        .into_iter()
+        .filter(|m| m.codigo == codigo)
+        .map(|movimento| {
+            saldo = movimento.aplicar(saldo);
+            LinhaDaFicha { movimento, saldo }
+        })
+        .collect())
+}
+
+// 🔹 Consumo de cada código desde a data: saídas menos devoluções (ajustes de
+// inventário não contam como consumo)
+pub fn consumo_desde(caminho: &str, desde: DateTime<Local>) -> io::Result<HashMap<String, f64>> {
+    let mut consumo = HashMap::new();
+    for m in ler_movimentos(caminho)?.0 {
+        if m.quando < desde {
+            continue;
+        }
+        let sinal = match m.tipo {
+            TipoMovimento::Saida => 1.0,
+            TipoMovimento::Devolucao => -1.0,
+            TipoMovimento::Entrada | TipoMovimento::Ajuste => continue,
+        };
+        *consumo.entry(m.codigo).or_insert(0.0) += sinal * m.quantidade;
+    }
+    consumo.retain(|_, q| *q > 0.0);
+    Ok(consumo)
+}
+
+fn somar(movimentos: &[Movimento]) -> HashMap<String, f64> {
+    let mut saldos = HashMap::new();
+    for m in movimentos {
+        let saldo = saldos.entry(m.codigo.clone()).or_insert(0.0);
+        *saldo = m.aplicar(*saldo);
+    }
+    saldos
+}
+
+// 🔹 Estoque aberto para lançamentos, com o saldo de cada código
+pub struct Estoque {
+    caminho: String,
+    arquivo: File,
+    // Tamanho do arquivo depois da nossa última gravação; se mudou, outro
+    // programa lançou movimentos e os saldos são recalculados
+    tamanho: u64,
+    saldos: HashMap<String, f64>,
+}
+
+impl Estoque {
+    // O arquivo fica travado da leitura ao corte da linha incompleta, para não
+    // cortar um movimento que outro programa está gravando
+    pub fn abrir(caminho: &str) -> io::Result<Estoque> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_do_estoque(caminho))?;
+        let mut estoque = Estoque {
+            caminho: caminho.to_string(),
+            arquivo,
+            tamanho: 0,
+            saldos: HashMap::new(),
+        };
+        estoque.arquivo.lock()?;
+        let resultado = estoque.recuperar();
+        let _ = estoque.arquivo.unlock();
+        resultado.map(|()| estoque)
+    }
+
+    // Relê os movimentos com o arquivo travado. Uma linha incompleta no fim
+    // (queda no meio de uma gravação) é cortada, para não emendar no próximo
+    // movimento.
+    fn recuperar(&mut self) -> io::Result<()> {
+        let (movimentos, completas) = ler_movimentos(&self.caminho)?;
+        if self.arquivo.metadata()?.len() > completas {
+            self.arquivo.set_len(completas)?;
+        }
+        self.saldos = somar(&movimentos);
+        self.tamanho = completas;
+        Ok(())
+    }
+
+    // Relê os movimentos se outro programa gravou desde a nossa última leitura
+    fn atualizar(&mut self) -> io::Result<()> {
+        if self.arquivo.metadata()?.len() != self.tamanho {
+            let (movimentos, completas) = ler_movimentos(&self.caminho)?;
+            self.saldos = somar(&movimentos);
+            self.tamanho = completas;
+        }
+        Ok(())
+    }
+
+    // Saldo do código (0 se nunca teve movimento)
+    pub fn saldo(&mut self, codigo: &str) -> io::Result<f64> {
+        self.atualizar()?;
+        Ok(self.saldos.get(codigo).copied().unwrap_or(0.0))
+    }
+
+    // 🔹 Saldos de todos os códigos que já tiveram movimento
+    pub fn saldos(&mut self) -> io::Result<&HashMap<String, f64>> {
+        self.atualizar()?;
+        Ok(&self.saldos)
+    }
+
+    // 🔹 Lança o movimento e devolve o novo saldo. Uma saída maior que o saldo
+    // é aceita (a mercadoria já saiu); quem chama avisa do saldo negativo.
+    pub fn lancar(
+        &mut self,
+        operador: &str,
+        codigo: &str,
+        tipo: TipoMovimento,
+        quantidade: f64,
+        motivo: &str,
+    ) -> io::Result<f64> {
+        let invalida = match tipo {
+            TipoMovimento::Ajuste => quantidade < 0.0,
+            _ => quantidade <= 0.0,
+        };
+        if invalida || !quantidade.is_finite() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("quantidade inválida para {}: {}", tipo.nome(), quantidade),
+            ));
+        }
+
+        self.arquivo.lock()?;
+        let resultado = self.lancar_travado(Movimento {
+            quando: Local::now(),
+            operador: operador.to_string(),
+            codigo: codigo.to_string(),
+            tipo,
+            quantidade,
+            motivo: motivo.to_string(),
+        });
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    fn lancar_travado(&mut self, movimento: Movimento) -> io::Result<f64> {
+        if self.arquivo.metadata()?.len() != self.tamanho {
+            self.recuperar()?;
+        }
+
+        let mut linha = serde_json::to_vec(&movimento).map_err(io::Error::other)?;
+        linha.push(b'\n');
+        self.arquivo.write_all(&linha)?;
+        self.arquivo.sync_data()?;
+        self.tamanho = self.arquivo.metadata()?.len();
+
+        let saldo = self.saldos.entry(movimento.codigo.clone()).or_insert(0.0);
+        *saldo = movimento.aplicar(*saldo);
+        Ok(*saldo)
+    }
+}
@@ -0,0 +1,502 @@
+// 🔹 Etiquetas de gôndola com nome, código, preço e código de barras: em ZPL,
+// para a impressora térmica, ou em folhas A4 em SVG ou PDF, para a impressora
+// laser. O código de barras é o EAN-13 do produto, se ele tiver; senão, o
+// código interno em Code 128.
+
+use crate::catalogo::{Catalogo, IdProduto};
+use crate::codificacao;
+use crate::codigo_barras;
+use crate::dinheiro::Reais;
+
+// Folha A4 com 3 colunas e 8 linhas de etiquetas de 70 x 37,1 mm (as folhas
+// adesivas mais comuns)
+const FOLHA_LARGURA: f64 = 210.0;
+const FOLHA_ALTURA: f64 = 297.0;
+const COLUNAS: usize = 3;
+const LINHAS: usize = 8;
+pub const ETIQUETAS_POR_FOLHA: usize = COLUNAS * LINHAS;
+
+// Etiqueta térmica de 50 x 30 mm a 203 dpi, em pontos
+const ZPL_LARGURA: u32 = 400;
+const ZPL_ALTURA: u32 = 240;
+
+// Caracteres por linha do nome (duas linhas no máximo)
+const LARGURA_NOME: usize = 34;
+
+// Pontos do PDF por milímetro
+const PONTOS_POR_MM: f64 = 72.0 / 25.4;
+
+// Largura de cada caractere de 32 a 126 na Helvetica, em milésimos do
+// tamanho da fonte (para alinhar o texto no PDF; os demais contam como 556)
+const LARGURAS_HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Etiqueta {
+    pub codigo: String,
+    pub nome: String,
+    pub preco: Option<Reais>,
+    pub gtin: Option<String>,
+}
+
+impl Etiqueta {
+    pub fn do_produto(catalogo: &Catalogo, id: IdProduto) -> Etiqueta {
+        let complemento = catalogo.complemento(id);
+        Etiqueta {
+            codigo: catalogo.codigo(id).to_string(),
+            nome: catalogo.nome(id).to_string(),
+            preco: complemento.preco,
+            gtin: complemento.gtin.clone(),
+        }
+    }
+
+    // EAN-13 para desenhar, se o produto tiver um (EAN-8 e GTIN-14 ficam no
+    // Code 128 do código interno)
+    fn ean13(&self) -> Option<&str> {
+        self.gtin.as_deref().filter(|g| g.len() == 13)
+    }
+}
+
+// Nome em até duas linhas, quebrando entre palavras
+fn linhas_do_nome(nome: &str) -> Vec<String> {
+    let mut linhas: Vec<String> = Vec::new();
+    for palavra in nome.split_whitespace() {
+        match linhas.last_mut() {
+            Some(linha) if linha.chars().count() + 1 + palavra.chars().count() <= LARGURA_NOME => {
+                linha.push(' ');
+                linha.push_str(palavra);
+            }
+            _ => linhas.push(palavra.chars().take(LARGURA_NOME).collect()),
+        }
+    }
+    linhas.truncate(2);
+    linhas
+}
+
+// ^ e ~ são comandos em ZPL, mesmo dentro do texto
+fn texto_zpl(texto: &str) -> String {
+    texto.replace(['^', '~'], " ")
+}
+
+// 🔹 Uma etiqueta ZPL (^XA ... ^XZ) por produto, todas no mesmo arquivo
+pub fn zpl(etiquetas: &[Etiqueta]) -> String {
+    let mut saida = String::new();
+    for e in etiquetas {
+        saida.push_str("^XA\n^CI28\n");
+        saida.push_str(&format!("^PW{}\n^LL{}\n", ZPL_LARGURA, ZPL_ALTURA));
+        saida.push_str(&format!(
+            "^FO16,12^A0N,24,24^FB368,2,0,L^FD{}^FS\n",
+            texto_zpl(&e.nome)
+        ));
+        if let Some(preco) = e.preco {
+            saida.push_str(&format!(
+                "^FO16,68^A0N,44,44^FD{}^FS\n",
+                preco.com_simbolo()
+            ));
+        }
+        saida.push_str(&format!(
+            "^FO260,76^A0N,20,20^FDCód. {}^FS\n",
+            texto_zpl(&e.codigo)
+        ));
+        // O ^BE recebe os 12 primeiros dígitos e calcula o verificador
+        match e.ean13() {
+            Some(gtin) => {
+                saida.push_str(&format!("^FO60,124^BY2^BEN,70,Y,N^FD{}^FS\n", &gtin[..12]))
+            }
+            None => saida.push_str(&format!(
+                "^FO60,124^BY2^BCN,70,Y,N,N^FD{}^FS\n",
+                texto_zpl(&e.codigo)
+            )),
+        }
+        saida.push_str("^XZ\n");
+    }
+    saida
+}
+
+// Onde fica o x do texto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alinhamento {
+    Inicio,
+    Meio,
+    Fim,
+}
+
+// O que vai numa etiqueta, em mm a partir do canto de cima da folha. O SVG e o
+// PDF desenham a mesma lista, então as duas saídas ficam iguais.
+#[derive(Debug, Clone, PartialEq)]
+enum Traco {
+    Moldura {
+        x: f64,
+        y: f64,
+        largura: f64,
+        altura: f64,
+    },
+    // y é a linha de base do texto
+    Texto {
+        x: f64,
+        y: f64,
+        tamanho: f64,
+        negrito: bool,
+        alinhamento: Alinhamento,
+        texto: String,
+    },
+    Barras {
+        barras: Vec<bool>,
+        x: f64,
+        y: f64,
+        modulo: f64,
+        altura: f64,
+    },
+}
+
+fn texto(x: f64, y: f64, tamanho: f64, alinhamento: Alinhamento, texto: String) -> Traco {
+    Traco::Texto {
+        x,
+        y,
+        tamanho,
+        negrito: false,
+        alinhamento,
+        texto,
+    }
+}
+
+fn tracos_da_etiqueta(e: &Etiqueta, x: f64, y: f64, largura: f64, altura: f64) -> Vec<Traco> {
+    let mut tracos = vec![Traco::Moldura {
+        x,
+        y,
+        largura,
+        altura,
+    }];
+    for (i, linha) in linhas_do_nome(&e.nome).into_iter().enumerate() {
+        tracos.push(texto(
+            x + 3.0,
+            y + 5.5 + i as f64 * 3.8,
+            3.2,
+            Alinhamento::Inicio,
+            linha,
+        ));
+    }
+    if let Some(preco) = e.preco {
+        tracos.push(Traco::Texto {
+            x: x + 3.0,
+            y: y + 17.0,
+            tamanho: 7.0,
+            negrito: true,
+            alinhamento: Alinhamento::Inicio,
+            texto: preco.com_simbolo(),
+        });
+    }
+    tracos.push(texto(
+        x + largura - 3.0,
+        y + 17.0,
+        2.8,
+        Alinhamento::Fim,
+        format!("Cód. {}", e.codigo),
+    ));
+
+    let (barras, legenda) = match e.ean13().and_then(codigo_barras::ean13) {
+        Some(barras) => (barras, e.ean13().unwrap_or_default().to_string()),
+        None => (
+            codigo_barras::code128(&e.codigo).unwrap_or_default(),
+            e.codigo.clone(),
+        ),
+    };
+    if !barras.is_empty() {
+        // Módulo de 0,33 mm (tamanho nominal do EAN-13), menor se não couber
+        let modulo = (0.33_f64).min((largura - 6.0) / barras.len() as f64);
+        let inicio = x + (largura - modulo * barras.len() as f64) / 2.0;
+        tracos.push(Traco::Barras {
+            barras,
+            x: inicio,
+            y: y + 20.0,
+            modulo,
+            altura: 11.0,
+        });
+        tracos.push(texto(
+            x + largura / 2.0,
+            y + 34.5,
+            2.8,
+            Alinhamento::Meio,
+            legenda,
+        ));
+    }
+    tracos
+}
+
+// Os traços de cada folha A4, com ETIQUETAS_POR_FOLHA etiquetas em cada
+fn folhas(etiquetas: &[Etiqueta]) -> Vec<Vec<Traco>> {
+    let largura = FOLHA_LARGURA / COLUNAS as f64;
+    let altura = FOLHA_ALTURA / LINHAS as f64;
+    etiquetas
+        .chunks(ETIQUETAS_POR_FOLHA)
+        .map(|folha| {
+            folha
+                .iter()
+                .enumerate()
+                .flat_map(|(i, e)| {
+                    let x = (i % COLUNAS) as f64 * largura;
+                    let y = (i / COLUNAS) as f64 * altura;
+                    tracos_da_etiqueta(e, x, y, largura, altura)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Cada sequência de módulos pretos vira uma barra só: (primeiro módulo, quantos)
+fn faixas(barras: &[bool]) -> Vec<(usize, usize)> {
+    let mut faixas = Vec::new();
+    let mut i = 0;
+    while i < barras.len() {
+        if !barras[i] {
+            i += 1;
+            continue;
+        }
+        let inicio = i;
+        while i < barras.len() && barras[i] {
+            i += 1;
+        }
+        faixas.push((inicio, i - inicio));
+    }
+    faixas
+}
+
+fn texto_svg(texto: &str) -> String {
+    texto
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn traco_svg(traco: &Traco) -> String {
+    match traco {
+        Traco::Moldura {
+            x,
+            y,
+            largura,
+            altura,
+        } => format!(
+            "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" fill=\"none\" stroke=\"#ccc\" stroke-width=\"0.1\"/>\n",
+            x, y, largura, altura
+        ),
+        Traco::Texto {
+            x,
+            y,
+            tamanho,
+            negrito,
+            alinhamento,
+            texto,
+        } => {
+            let negrito = if *negrito {
+                " font-weight=\"bold\""
+            } else {
+                ""
+            };
+            let ancora = match alinhamento {
+                Alinhamento::Inicio => "",
+                Alinhamento::Meio => " text-anchor=\"middle\"",
+                Alinhamento::Fim => " text-anchor=\"end\"",
+            };
+            format!(
+                "<text x=\"{:.3}\" y=\"{:.3}\" font-size=\"{}\"{}{}>{}</text>\n",
+                x,
+                y,
+                tamanho,
+                negrito,
+                ancora,
+                texto_svg(texto)
+            )
+        }
+        Traco::Barras {
+            barras,
+            x,
+            y,
+            modulo,
+            altura,
+        } => {
+            let mut saida = String::new();
+            for (inicio, largura) in faixas(barras) {
+                saida.push_str(&format!(
+                    "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\"/>",
+                    x + inicio as f64 * modulo,
+                    y,
+                    largura as f64 * modulo,
+                    altura
+                ));
+            }
+            saida.push('\n');
+            saida
+        }
+    }
+}
+
+// 🔹 Uma folha A4 em SVG para cada ETIQUETAS_POR_FOLHA etiquetas
+pub fn svg(etiquetas: &[Etiqueta]) -> Vec<String> {
+    folhas(etiquetas)
+        .iter()
+        .map(|tracos| {
+            let mut saida = format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}mm\" height=\"{1}mm\" viewBox=\"0 0 {0} {1}\" font-family=\"Arial, Helvetica, sans-serif\">\n",
+                FOLHA_LARGURA, FOLHA_ALTURA
+            );
+            for traco in tracos {
+                saida.push_str(&traco_svg(traco));
+            }
+            saida.push_str("</svg>\n");
+            saida
+        })
+        .collect()
+}
+
+// Texto em Windows-1252 (a codificação das fontes padrão do PDF) como string
+// PDF; o que não existe nela vira '?'
+fn texto_pdf(texto: &str) -> (String, f64) {
+    let mut saida = String::from("(");
+    let mut largura = 0u32;
+    for c in texto.chars() {
+        let b = codificacao::para_windows_1252(c).unwrap_or(b'?');
+        largura += match b {
+            32..=126 => LARGURAS_HELVETICA[(b - 32) as usize] as u32,
+            _ => 556,
+        };
+        match b {
+            b'(' | b')' | b'\\' => {
+                saida.push('\\');
+                saida.push(b as char);
+            }
+            32..=126 => saida.push(b as char),
+            _ => saida.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    saida.push(')');
+    (saida, largura as f64 / 1000.0)
+}
+
+// Comandos do PDF para o traço, em mm com a origem embaixo da folha
+fn traco_pdf(traco: &Traco) -> String {
+    match traco {
+        Traco::Moldura {
+            x,
+            y,
+            largura,
+            altura,
+        } => format!(
+            "0.8 G 0.1 w {:.3} {:.3} {:.3} {:.3} re S\n",
+            x,
+            FOLHA_ALTURA - y - altura,
+            largura,
+            altura
+        ),
+        Traco::Texto {
+            x,
+            y,
+            tamanho,
+            negrito,
+            alinhamento,
+            texto,
+        } => {
+            let (texto, largura) = texto_pdf(texto);
+            let x = match alinhamento {
+                Alinhamento::Inicio => *x,
+                Alinhamento::Meio => x - largura * tamanho / 2.0,
+                Alinhamento::Fim => x - largura * tamanho,
+            };
+            format!(
+                "BT /{} {} Tf {:.3} {:.3} Td {} Tj ET\n",
+                if *negrito { "F2" } else { "F1" },
+                tamanho,
+                x,
+                FOLHA_ALTURA - y,
+                texto
+            )
+        }
+        Traco::Barras {
+            barras,
+            x,
+            y,
+            modulo,
+            altura,
+        } => {
+            let mut saida = String::new();
+            for (inicio, largura) in faixas(barras) {
+                saida.push_str(&format!(
+                    "{:.3} {:.3} {:.3} {:.3} re ",
+                    x + inicio as f64 * modulo,
+                    FOLHA_ALTURA - y - altura,
+                    largura as f64 * modulo,
+                    altura
+                ));
+            }
+            saida.push_str("f\n");
+            saida
+        }
+    }
+}
+
+// 🔹 Todas as folhas A4 num PDF só, uma página por folha, com as fontes
+// Helvetica que todo leitor de PDF tem (nada embutido)
+pub fn pdf(etiquetas: &[Etiqueta]) -> Vec<u8> {
+    let folhas = folhas(etiquetas);
+    // 1 catálogo, 2 páginas, 3 e 4 fontes; depois página e conteúdo de cada folha
+    let paginas: Vec<String> = (0..folhas.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect();
+    let mut objetos = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            paginas.join(" "),
+            folhas.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (i, tracos) in folhas.iter().enumerate() {
+        objetos.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            FOLHA_LARGURA * PONTOS_POR_MM,
+            FOLHA_ALTURA * PONTOS_POR_MM,
+            6 + 2 * i
+        ));
+        // Tudo em mm: a escala fica no começo da página
+        let mut conteudo = format!("{0:.6} 0 0 {0:.6} 0 0 cm\n", PONTOS_POR_MM);
+        for traco in tracos {
+            conteudo.push_str(&traco_pdf(traco));
+        }
+        objetos.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            conteudo.len(),
+            conteudo
+        ));
+    }
+
+    // A tabela xref guarda onde cada objeto começa
+    let mut saida = String::from("%PDF-1.4\n");
+    let mut posicoes = Vec::new();
+    for (i, objeto) in objetos.iter().enumerate() {
+        posicoes.push(saida.len());
+        saida.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, objeto));
+    }
+    let xref = saida.len();
+    saida.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objetos.len() + 1
+    ));
+    for posicao in posicoes {
+        saida.push_str(&format!("{:010} 00000 n \n", posicao));
+    }
+    saida.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objetos.len() + 1,
+        xref
+    ));
+    saida.into_bytes()
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+// 🔹 Formatos de arquivo aceitos para o catálogo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Formato {
+    // Uma linha "codigo;nome" por produto
+    #[default]
+    Texto,
+    // Lista JSON de objetos {"codigo": ..., "nome": ...}
+    Json,
+    // Um objeto JSON por linha (JSON Lines)
+    JsonLinhas,
+    // Banco SQLite (ver `sqlite`)
+    Sqlite,
+}
+
+impl Formato {
+    // Pela extensão do arquivo (.txt, .json, .jsonl, .ndjson, .db ou .sqlite)
+    pub fn de_caminho(caminho: &str) -> Option<Formato> {
+        let extensao = Path::new(caminho).extension()?.to_str()?.to_lowercase();
+        match extensao.as_str() {
+            "txt" => Some(Formato::Texto),
+            "json" => Some(Formato::Json),
+            "jsonl" | "ndjson" => Some(Formato::JsonLinhas),
+            "db" | "sqlite" | "sqlite3" => Some(Formato::Sqlite),
+            _ => None,
+        }
+    }
+
+    // 🔹 Pela extensão ou, se ela não disser nada, pelo conteúdo: o cabeçalho
+    // do SQLite, '[' no início (lista JSON) ou '{' (JSON Lines)
+    pub fn detectar(caminho: &str, bytes: &[u8]) -> Formato {
+        if let Some(formato) = Formato::de_caminho(caminho) {
+            return formato;
+        }
+        if bytes.starts_with(b"SQLite format 3\0") {
+            return Formato::Sqlite;
+        }
+
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'[') => Formato::Json,
+            Some(b'{') => Formato::JsonLinhas,
+            _ => Formato::Texto,
+        }
+    }
+}
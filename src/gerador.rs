@@ -0,0 +1,283 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, BufWriter, Write};
+
+use crate::produto::Produto;
+use crate::texto::remover_acentos;
+
+// Limite de palavras por nome gerado (evita cadeias longas demais)
+const MAX_PALAVRAS: usize = 8;
+// Quantos nomes recentes ficam disponíveis para virar duplicata
+const JANELA_DUPLICADOS: usize = 4096;
+// Chave de transição usada depois de uma medida ("300ml", "7.1/4\"")
+const CHAVE_MEDIDA: &str = "#medida";
+
+// 🔹 Gerador pseudoaleatório SplitMix64 (pequeno e reproduzível pela semente)
+pub struct Aleatorio {
+    estado: u64,
+}
+
+impl Aleatorio {
+    pub fn new(semente: u64) -> Self {
+        Aleatorio { estado: semente }
+    }
+
+    pub fn proximo(&mut self) -> u64 {
+        self.estado = self.estado.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.estado;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Número entre 0 e limite - 1
+    pub fn abaixo(&mut self, limite: u64) -> u64 {
+        self.proximo() % limite
+    }
+
+    // Verdadeiro com probabilidade `taxa` (0.0 a 1.0)
+    pub fn chance(&mut self, taxa: f64) -> bool {
+        let sorteio = (self.proximo() >> 11) as f64 / (1u64 << 53) as f64;
+        sorteio < taxa
+    }
+}
+
+// 🔹 Distribuição discreta: itens com pesos acumulados para sorteio por busca binária
+struct Distribuicao<T> {
+    itens: Vec<T>,
+    acumulado: Vec<u64>,
+}
+
+impl<T: Ord> Distribuicao<T> {
+    fn de_contagens(contagens: BTreeMap<T, u64>) -> Self {
+        let mut itens = Vec::with_capacity(contagens.len());
+        let mut acumulado = Vec::with_capacity(contagens.len());
+        let mut total = 0;
+
+        for (item, peso) in contagens {
+            total += peso;
+            itens.push(item);
+            acumulado.push(total);
+        }
+
+        Distribuicao { itens, acumulado }
+    }
+
+    fn sortear(&self, rng: &mut Aleatorio) -> Option<&T> {
+        let total = *self.acumulado.last()?;
+        let alvo = rng.abaixo(total);
+        let pos = self.acumulado.partition_point(|&a| a <= alvo);
+        self.itens.get(pos)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Token {
+    Palavra(String),
+    Medida,
+    Fim,
+}
+
+// Formato de uma medida: o que vem antes e depois do número ("M" + "8", "300" + "ml")
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Formato {
+    prefixo: String,
+    sufixo: String,
+}
+
+// 🔹 Separa uma medida em formato e número ("7.1/4\"" -> ("", "7.1/4", "\""))
+fn decompor_medida(token: &str) -> Option<(Formato, String)> {
+    let inicio = token.find(|c: char| c.is_ascii_digit())?;
+    let resto = &token[inicio..];
+    let mut fim = resto
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',' || c == '/'))
+        .unwrap_or(resto.len());
+
+    // Pontuação no final do número pertence ao sufixo ("10." -> "10" + ".")
+    while fim > 0 && !resto.as_bytes()[fim - 1].is_ascii_digit() {
+        fim -= 1;
+    }
+
+    let formato = Formato {
+        prefixo: token[..inicio].to_string(),
+        sufixo: resto[fim..].to_string(),
+    };
+    Some((formato, resto[..fim].to_string()))
+}
+
+// 🔹 Modelo aprendido do catálogo real: palavras iniciais, transições entre palavras
+// (cadeia de Markov) e distribuição das medidas por formato
+pub struct Modelo {
+    iniciais: Distribuicao<String>,
+    transicoes: BTreeMap<String, Distribuicao<Token>>,
+    formatos_por_palavra: BTreeMap<String, Distribuicao<Formato>>,
+    formatos: Distribuicao<Formato>,
+    numeros_por_formato: BTreeMap<Formato, Distribuicao<String>>,
+}
+
+impl Modelo {
+    // Retorna None se nenhum nome aproveitável for encontrado
+    pub fn aprender(produtos: &[Produto]) -> Option<Modelo> {
+        let mut iniciais: BTreeMap<String, u64> = BTreeMap::new();
+        let mut transicoes: BTreeMap<String, BTreeMap<Token, u64>> = BTreeMap::new();
+        let mut formatos_por_palavra: BTreeMap<String, BTreeMap<Formato, u64>> = BTreeMap::new();
+        let mut formatos: BTreeMap<Formato, u64> = BTreeMap::new();
+        let mut numeros_por_formato: BTreeMap<Formato, BTreeMap<String, u64>> = BTreeMap::new();
+
+        for produto in produtos {
+            let mut palavras = produto.nome.split_whitespace();
+            let Some(primeira) = palavras.next() else {
+                continue;
+            };
+            if decompor_medida(primeira).is_some() {
+                continue;
+            }
+
+            *iniciais.entry(primeira.to_string()).or_default() += 1;
+            let mut chave = primeira.to_string();
+
+            for palavra in palavras {
+                let token = match decompor_medida(palavra) {
+                    Some((formato, numero)) => {
+                        *formatos_por_palavra
+                            .entry(primeira.to_string())
+                            .or_default()
+                            .entry(formato.clone())
+                            .or_default() += 1;
+                        *formatos.entry(formato.clone()).or_default() += 1;
+                        *numeros_por_formato
+                            .entry(formato)
+                            .or_default()
+                            .entry(numero)
+                            .or_default() += 1;
+                        Token::Medida
+                    }
+                    None => Token::Palavra(palavra.to_string()),
+                };
+
+                let proxima_chave = match &token {
+                    Token::Palavra(p) => p.clone(),
+                    _ => CHAVE_MEDIDA.to_string(),
+                };
+                *transicoes.entry(chave).or_default().entry(token).or_default() += 1;
+                chave = proxima_chave;
+            }
+
+            *transicoes.entry(chave).or_default().entry(Token::Fim).or_default() += 1;
+        }
+
+        if iniciais.is_empty() {
+            return None;
+        }
+
+        Some(Modelo {
+            iniciais: Distribuicao::de_contagens(iniciais),
+            transicoes: transicoes
+                .into_iter()
+                .map(|(k, v)| (k, Distribuicao::de_contagens(v)))
+                .collect(),
+            formatos_por_palavra: formatos_por_palavra
+                .into_iter()
+                .map(|(k, v)| (k, Distribuicao::de_contagens(v)))
+                .collect(),
+            formatos: Distribuicao::de_contagens(formatos),
+            numeros_por_formato: numeros_por_formato
+                .into_iter()
+                .map(|(k, v)| (k, Distribuicao::de_contagens(v)))
+                .collect(),
+        })
+    }
+
+    // 🔹 Gera um nome seguindo as transições aprendidas
+    pub fn gerar_nome(&self, rng: &mut Aleatorio) -> String {
+        let Some(primeira) = self.iniciais.sortear(rng) else {
+            return String::new();
+        };
+
+        let mut partes = vec![primeira.clone()];
+        let mut chave = primeira.clone();
+
+        while partes.len() < MAX_PALAVRAS {
+            let Some(token) = self.transicoes.get(&chave).and_then(|d| d.sortear(rng)) else {
+                break;
+            };
+
+            match token {
+                Token::Fim => break,
+                Token::Palavra(p) => {
+                    partes.push(p.clone());
+                    chave = p.clone();
+                }
+                Token::Medida => {
+                    if let Some(medida) = self.gerar_medida(primeira, rng) {
+                        partes.push(medida);
+                    }
+                    chave = CHAVE_MEDIDA.to_string();
+                }
+            }
+        }
+
+        partes.join(" ")
+    }
+
+    // Medida no formato típico da família do produto ("Serra" -> polegadas),
+    // com 20% de chance de usar um formato qualquer do catálogo
+    fn gerar_medida(&self, primeira: &str, rng: &mut Aleatorio) -> Option<String> {
+        let formato = match self.formatos_por_palavra.get(primeira) {
+            Some(dist) if !rng.chance(0.2) => dist.sortear(rng)?,
+            _ => self.formatos.sortear(rng)?,
+        };
+        let numero = self.numeros_por_formato.get(formato)?.sortear(rng)?;
+        Some(format!("{}{}{}", formato.prefixo, numero, formato.sufixo))
+    }
+}
+
+// 🔹 Configuração da geração (taxas entre 0.0 e 1.0)
+#[derive(Debug, Clone)]
+pub struct ConfigGerador {
+    pub quantidade: u64,
+    pub semente: u64,
+    pub taxa_duplicados: f64,
+    pub taxa_sem_acento: f64,
+}
+
+impl Default for ConfigGerador {
+    fn default() -> Self {
+        ConfigGerador {
+            quantidade: 1000,
+            semente: 42,
+            taxa_duplicados: 0.0,
+            taxa_sem_acento: 0.0,
+        }
+    }
+}
+
+// 🔹 Escreve `quantidade` produtos no formato "codigo;nome", linha a linha,
+// sem manter o catálogo gerado em memória
+pub fn gerar<W: Write>(modelo: &Modelo, config: &ConfigGerador, saida: W) -> io::Result<u64> {
+    let largura = config.quantidade.to_string().len().max(4);
+    let mut rng = Aleatorio::new(config.semente);
+    let mut recentes: VecDeque<String> = VecDeque::with_capacity(JANELA_DUPLICADOS);
+    let mut saida = BufWriter::new(saida);
+
+    for codigo in 1..=config.quantidade {
+        let nome = if !recentes.is_empty() && rng.chance(config.taxa_duplicados) {
+            let pos = rng.abaixo(recentes.len() as u64) as usize;
+            recentes[pos].clone()
+        } else {
+            let mut nome = modelo.gerar_nome(&mut rng);
+            if rng.chance(config.taxa_sem_acento) {
+                nome = remover_acentos(&nome);
+            }
+            if recentes.len() == JANELA_DUPLICADOS {
+                recentes.pop_front();
+            }
+            recentes.push_back(nome.clone());
+            nome
+        };
+
+        writeln!(saida, "{:0largura$};{}", codigo, nome)?;
+    }
+
+    saida.flush()?;
+    Ok(config.quantidade)
+}
@@ -0,0 +1,72 @@
+// 🔹 Códigos de barras GTIN (EAN-8, EAN-13 e GTIN-14): conferência do dígito
+// verificador e a leitura do que o leitor de código de barras digita, que pode
+// ser tanto o código interno quanto o código de barras do produto.
+
+use crate::catalogo::{Catalogo, IdProduto};
+
+// EAN-8, EAN-13 e GTIN-14 (caixa de embarque)
+pub const TAMANHOS: [usize; 3] = [8, 13, 14];
+
+// 🔹 Dígito verificador (módulo 10) dos dígitos sem ele: da direita para a
+// esquerda, pesos 3, 1, 3, 1...
+pub fn digito_verificador(corpo: &str) -> Option<u8> {
+    let mut soma = 0u32;
+    for (i, c) in corpo.chars().rev().enumerate() {
+        let digito = c.to_digit(10)?;
+        soma += if i % 2 == 0 { digito * 3 } else { digito };
+    }
+    Some(((10 - soma % 10) % 10) as u8)
+}
+
+// Só dígitos, num dos tamanhos de GTIN (sem olhar o dígito verificador)
+pub fn parece_gtin(texto: &str) -> bool {
+    TAMANHOS.contains(&texto.len()) && texto.chars().all(|c| c.is_ascii_digit())
+}
+
+// 🔹 Confere o código de barras digitado; devolve os dígitos sem espaços
+pub fn validar(texto: &str) -> Result<String, String> {
+    let gtin: String = texto.chars().filter(|c| !c.is_whitespace()).collect();
+    if !parece_gtin(&gtin) {
+        return Err(format!(
+            "'{}' não é um código de barras: use 8 (EAN-8), 13 (EAN-13) ou 14 (GTIN-14) dígitos",
+            texto.trim()
+        ));
+    }
+    let (corpo, ultimo) = gtin.split_at(gtin.len() - 1);
+    let esperado = digito_verificador(corpo).unwrap_or_default();
+    if ultimo != esperado.to_string() {
+        return Err(format!(
+            "código de barras '{}' com dígito verificador errado (deveria terminar em {})",
+            gtin, esperado
+        ));
+    }
+    Ok(gtin)
+}
+
+// 🔹 Um código de barras só pode pertencer a um produto
+pub fn conferir_dono(catalogo: &Catalogo, codigo: &str, gtin: &str) -> Result<(), String> {
+    match catalogo.buscar_por_gtin(gtin) {
+        Some(id) if catalogo.codigo(id) != codigo => Err(format!(
+            "o código de barras {} já é do produto {}",
+            gtin,
+            catalogo.codigo(id)
+        )),
+        _ => Ok(()),
+    }
+}
+
+// 🔹 O produto do que foi digitado ou lido pelo leitor. O código interno tem
+// preferência; sem produto com esse código, uma entrada com cara de código de
+// barras é procurada entre os códigos de barras do catálogo. Err se ela tem o tamanho
+// de um código de barras mas o dígito verificador não confere (leitura ruim).
+pub fn localizar(catalogo: &Catalogo, entrada: &str) -> Result<Option<IdProduto>, String> {
+    let entrada = entrada.trim();
+    if let Some(id) = catalogo.buscar_por_codigo(entrada) {
+        return Ok(Some(id));
+    }
+    if !parece_gtin(entrada) {
+        return Ok(None);
+    }
+    let gtin = validar(entrada)?;
+    Ok(catalogo.buscar_por_gtin(&gtin))
+}
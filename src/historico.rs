@@ -0,0 +1,328 @@
+// 🔹 Histórico das edições do catálogo ("<catalogo>.hist"), com desfazer e
+// refazer. Cada linha é um evento em JSON: uma operação feita (com o produto
+// antes e depois de cada código que ela mexeu), um desfazer ou um refazer.
+// As pilhas de desfazer e refazer são remontadas relendo os eventos em ordem.
+// O arquivo é dividido com os outros programas abertos no mesmo catálogo: ele
+// fica travado a cada gravação, e as pilhas são remontadas quando outro
+// programa gravou desde a última vez.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::diario::Alteracao;
+use crate::produto::Produto;
+
+// 🔹 O que uma operação fez com um código (None = não existia / foi removido)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mudanca {
+    pub codigo: String,
+    pub antes: Option<Produto>,
+    pub depois: Option<Produto>,
+}
+
+impl Mudanca {
+    fn alteracao(codigo: &str, produto: &Option<Produto>) -> Alteracao {
+        match produto {
+            Some(produto) => Alteracao::Gravar {
+                produto: Box::new(produto.clone()),
+            },
+            None => Alteracao::Remover {
+                codigo: codigo.to_string(),
+            },
+        }
+    }
+}
+
+// 🔹 Uma edição que se desfaz de uma vez (um produto ou uma alteração em lote)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operacao {
+    pub descricao: String,
+    pub mudancas: Vec<Mudanca>,
+}
+
+impl Operacao {
+    // Alterações que voltam o catálogo para antes da operação
+    pub fn para_desfazer(&self) -> Vec<Alteracao> {
+        self.mudancas
+            .iter()
+            .rev()
+            .map(|m| Mudanca::alteracao(&m.codigo, &m.antes))
+            .collect()
+    }
+
+    // Alterações que aplicam a operação de novo
+    pub fn para_refazer(&self) -> Vec<Alteracao> {
+        self.mudancas
+            .iter()
+            .map(|m| Mudanca::alteracao(&m.codigo, &m.depois))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "acao", rename_all = "lowercase")]
+enum Evento {
+    Fazer {
+        quando: DateTime<Local>,
+        #[serde(default)]
+        operador: String,
+        operacao: Operacao,
+    },
+    Desfazer {
+        quando: DateTime<Local>,
+        #[serde(default)]
+        operador: String,
+    },
+    Refazer {
+        quando: DateTime<Local>,
+        #[serde(default)]
+        operador: String,
+    },
+}
+
+// 🔹 Um nome que o produto teve (None = removido) e quando passou a tê-lo
+#[derive(Debug, Clone)]
+pub struct NomeAnterior {
+    pub quando: DateTime<Local>,
+    pub operador: String,
+    pub nome: Option<String>,
+    // Descrição da operação, com "desfeito: " ou "refeito: " na frente quando for o caso
+    pub motivo: String,
+}
+
+pub fn caminho_do_historico(caminho: &str) -> String {
+    format!("{}.hist", caminho)
+}
+
+// Eventos gravados e quantos bytes ocupam as linhas completas; uma linha sem
+// '\n' no fim (queda no meio da gravação) é ignorada
+fn ler_eventos(caminho: &str) -> io::Result<(Vec<Evento>, u64)> {
+    let bytes = match fs::read(caminho_do_historico(caminho)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e),
+    };
+    let completas = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let eventos = bytes[..completas]
+        .split(|&b| b == b'\n')
+        .filter_map(|linha| serde_json::from_slice(linha).ok())
+        .collect();
+    Ok((eventos, completas as u64))
+}
+
+// 🔹 Todos os nomes que o código já teve, do mais antigo ao mais recente
+pub fn nomes_do_produto(caminho: &str, codigo: &str) -> io::Result<Vec<NomeAnterior>> {
+    let mut nomes = Vec::new();
+    let mut pilhas = Pilhas::default();
+
+    for evento in ler_eventos(caminho)?.0 {
+        let (quando, operador, operacao, prefixo, depois) = match evento {
+            Evento::Fazer {
+                quando,
+                operador,
+                operacao,
+            } => {
+                pilhas.fazer(operacao.clone());
+                (quando, operador, operacao, "", true)
+            }
+            Evento::Desfazer { quando, operador } => match pilhas.desfazer() {
+                Some(operacao) => (quando, operador, operacao, "desfeito: ", false),
+                None => continue,
+            },
+            Evento::Refazer { quando, operador } => match pilhas.refazer() {
+                Some(operacao) => (quando, operador, operacao, "refeito: ", true),
+                None => continue,
+            },
+        };
+
+        let mudancas = operacao.mudancas.iter().filter(|m| m.codigo == codigo);
+        for mudanca in mudancas {
+            let produto = if depois {
+                &mudanca.depois
+            } else {
+                &mudanca.antes
+            };
+            nomes.push(NomeAnterior {
+                quando,
+                operador: operador.clone(),
+                nome: produto.as_ref().map(|p| p.nome.clone()),
+                motivo: format!("{}{}", prefixo, operacao.descricao),
+            });
+        }
+    }
+
+    Ok(nomes)
+}
+
+#[derive(Debug, Default)]
+struct Pilhas {
+    feitas: Vec<Operacao>,
+    desfeitas: Vec<Operacao>,
+}
+
+impl Pilhas {
+    fn montar(eventos: Vec<Evento>) -> Pilhas {
+        let mut pilhas = Pilhas::default();
+        for evento in eventos {
+            match evento {
+                Evento::Fazer { operacao, .. } => pilhas.fazer(operacao),
+                Evento::Desfazer { .. } => {
+                    pilhas.desfazer();
+                }
+                Evento::Refazer { .. } => {
+                    pilhas.refazer();
+                }
+            }
+        }
+        pilhas
+    }
+
+    // Uma operação nova descarta o que poderia ser refeito
+    fn fazer(&mut self, operacao: Operacao) {
+        self.feitas.push(operacao);
+        self.desfeitas.clear();
+    }
+
+    fn desfazer(&mut self) -> Option<Operacao> {
+        let operacao = self.feitas.pop()?;
+        self.desfeitas.push(operacao.clone());
+        Some(operacao)
+    }
+
+    fn refazer(&mut self) -> Option<Operacao> {
+        let operacao = self.desfeitas.pop()?;
+        self.feitas.push(operacao.clone());
+        Some(operacao)
+    }
+}
+
+// 🔹 Histórico aberto para gravação, com as pilhas de desfazer e refazer
+pub struct Historico {
+    caminho: String,
+    arquivo: File,
+    pilhas: Pilhas,
+    // Tamanho do arquivo quando as pilhas foram montadas ou depois da nossa
+    // última gravação; se mudou, outro programa gravou
+    tamanho: u64,
+    travado: bool,
+}
+
+impl Historico {
+    pub fn abrir(caminho: &str) -> io::Result<Historico> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_do_historico(caminho))?;
+        let mut historico = Historico {
+            caminho: caminho.to_string(),
+            arquivo,
+            pilhas: Pilhas::default(),
+            tamanho: u64::MAX,
+            travado: false,
+        };
+        historico.travar()?;
+        historico.liberar();
+        Ok(historico)
+    }
+
+    // 🔹 Trava o arquivo e remonta as pilhas com o que outro programa gravou.
+    // Até `liberar`, ninguém mais grava: quem desfaz confere e grava sobre
+    // as pilhas como estão no arquivo.
+    pub fn travar(&mut self) -> io::Result<()> {
+        self.arquivo.lock()?;
+        if let Err(e) = self.atualizar() {
+            let _ = self.arquivo.unlock();
+            return Err(e);
+        }
+        self.travado = true;
+        Ok(())
+    }
+
+    pub fn liberar(&mut self) {
+        self.travado = false;
+        let _ = self.arquivo.unlock();
+    }
+
+    // Com o arquivo travado, uma linha incompleta no fim só pode ser de uma
+    // queda: ela é cortada, para não emendar no próximo evento
+    fn atualizar(&mut self) -> io::Result<()> {
+        let tamanho = self.arquivo.metadata()?.len();
+        if tamanho == self.tamanho {
+            return Ok(());
+        }
+        let (eventos, completas) = ler_eventos(&self.caminho)?;
+        if tamanho > completas {
+            self.arquivo.set_len(completas)?;
+        }
+        self.pilhas = Pilhas::montar(eventos);
+        self.tamanho = completas;
+        Ok(())
+    }
+
+    // Próxima operação a desfazer e a refazer
+    pub fn a_desfazer(&self) -> Option<&Operacao> {
+        self.pilhas.feitas.last()
+    }
+
+    pub fn a_refazer(&self) -> Option<&Operacao> {
+        self.pilhas.desfeitas.last()
+    }
+
+    pub fn fazer(&mut self, operacao: Operacao, operador: &str) -> io::Result<()> {
+        self.gravar(&Evento::Fazer {
+            quando: Local::now(),
+            operador: operador.to_string(),
+            operacao: operacao.clone(),
+        })?;
+        self.pilhas.fazer(operacao);
+        Ok(())
+    }
+
+    // 🔹 Tira a última operação da pilha de desfazer (quem chama aplica
+    // `para_desfazer` no catálogo)
+    pub fn desfazer(&mut self, operador: &str) -> io::Result<Option<Operacao>> {
+        if self.pilhas.feitas.is_empty() {
+            return Ok(None);
+        }
+        self.gravar(&Evento::Desfazer {
+            quando: Local::now(),
+            operador: operador.to_string(),
+        })?;
+        Ok(self.pilhas.desfazer())
+    }
+
+    pub fn refazer(&mut self, operador: &str) -> io::Result<Option<Operacao>> {
+        if self.pilhas.desfeitas.is_empty() {
+            return Ok(None);
+        }
+        self.gravar(&Evento::Refazer {
+            quando: Local::now(),
+            operador: operador.to_string(),
+        })?;
+        Ok(self.pilhas.refazer())
+    }
+
+    // Sem `travar` antes, o arquivo fica travado só durante a gravação
+    fn gravar(&mut self, evento: &Evento) -> io::Result<()> {
+        if self.travado {
+            return self.gravar_travado(evento);
+        }
+        self.travar()?;
+        let resultado = self.gravar_travado(evento);
+        self.liberar();
+        resultado
+    }
+
+    fn gravar_travado(&mut self, evento: &Evento) -> io::Result<()> {
+        let mut linha = serde_json::to_vec(evento).map_err(io::Error::other)?;
+        linha.push(b'\n');
+        self.arquivo.write_all(&linha)?;
+        self.arquivo.sync_data()?;
+        self.tamanho += linha.len() as u64;
+        Ok(())
+    }
+}
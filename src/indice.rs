@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::time::UNIX_EPOCH;
+
+use memmap2::Mmap;
+
+use crate::codificacao::Codificacao;
+use crate::formato::Formato;
+use crate::produto::validar;
+
+// 🔹 Índices do catálogo num único bloco de u32, com o mesmo layout em memória
+// e no arquivo "<catalogo>.idx" (que é mapeado direto, sem reconstrução):
+//
+//   cabeçalho        CABECALHO palavras (ver CAB_*)
+//   codigos          2 por produto: início e fim do código no texto do catálogo
+//   nomes            2 por produto: início e fim do nome
+//   ordem_por_codigo 1 por produto
+//   ordem_por_nome   1 por produto
+//   palavras         4 por palavra (ordenadas): início/fim no texto das palavras,
+//                    início/fim na lista de ocorrências
+//   ocorrencias      ids de produto de cada palavra
+//   texto_palavras   bytes das palavras em minúsculas, completados até múltiplo de 4
+
+const MAGICO: u32 = u32::from_le_bytes(*b"MSIX");
+const VERSAO: u32 = 4;
+
+const CAB_MAGICO: usize = 0;
+const CAB_VERSAO: usize = 1;
+const CAB_PRODUTOS: usize = 2;
+const CAB_PALAVRAS: usize = 3;
+const CAB_OCORRENCIAS: usize = 4;
+const CAB_BYTES_PALAVRAS: usize = 5;
+const CAB_TAMANHO: usize = 6; // u64
+const CAB_MODIFICADO_SEG: usize = 8; // u64
+const CAB_MODIFICADO_NANO: usize = 10;
+const CAB_CHECKSUM: usize = 11; // u64
+const CAB_CONVERTIDO_DE: usize = 13; // 0 = faixas no próprio arquivo; senão, ver `codigo_da_codificacao`
+const CAB_FORMATO: usize = 14; // 0 = "codigo;nome", 1 = JSON, 2 = JSON Lines, 3 = SQLite
+const CABECALHO: usize = 16;
+
+// 🔹 Identifica o conteúdo do catálogo de onde o índice foi gerado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Assinatura {
+    pub tamanho: u64,
+    pub modificado_seg: u64,
+    pub modificado_nano: u32,
+    pub checksum: u64,
+}
+
+impl Assinatura {
+    // Tamanho e data vêm dos metadados; o checksum é calculado só quando necessário
+    pub fn de_metadados(meta: &fs::Metadata) -> Assinatura {
+        let modificado = meta
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        Assinatura {
+            tamanho: meta.len(),
+            modificado_seg: modificado.as_secs(),
+            modificado_nano: modificado.subsec_nanos(),
+            checksum: 0,
+        }
+    }
+
+    // Mesmo tamanho e mesma data de modificação (o checksum não entra)
+    pub fn mesma_versao(&self, outra: &Assinatura) -> bool {
+        self.tamanho == outra.tamanho
+            && self.modificado_seg == outra.modificado_seg
+            && self.modificado_nano == outra.modificado_nano
+    }
+}
+
+// 🔹 Checksum FNV-1a de 64 bits do texto do catálogo
+pub fn checksum(texto: &[u8]) -> u64 {
+    texto.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+enum Dados {
+    Memoria(Vec<u32>),
+    Mapeado(Mmap),
+}
+
+pub struct Indice {
+    dados: Dados,
+    produtos: usize,
+    palavras: usize,
+    ocorrencias: usize,
+    bytes_palavras: usize,
+}
+
+impl Indice {
+    // 🔹 Monta todos os índices a partir do texto do catálogo ("codigo;nome").
+    // `convertido_de` indica que o texto não é o próprio arquivo, mas o que saiu
+    // dele depois de convertido para UTF-8 (e, se `formato` não for texto, do JSON).
+    pub fn construir(texto: &[u8], formato: Formato, convertido_de: Option<Codificacao>) -> Indice {
+        let (codigos, nomes) = separar_linhas(texto);
+        let produtos = codigos.len() / 2;
+        let ids = 0..produtos as u32;
+        let nome = |id: u32| fatia(texto, &nomes, id);
+        let codigo = |id: u32| fatia(texto, &codigos, id);
+
+        let mut por_codigo: Vec<u32> = ids.clone().collect();
+        por_codigo.sort_by(|&a, &b| codigo(a).cmp(codigo(b)));
+
+        let mut por_nome: Vec<(u64, u32)> = ids
+            .clone()
+            .map(|id| (chave_de_ordenacao(nome(id), 0), id))
+            .collect();
+        ordenar_por_nome(&mut por_nome, 0, &nome);
+
+        let mut indice: HashMap<String, Vec<u32>> = HashMap::new();
+        let mut minuscula = String::new();
+        for id in ids {
+            for palavra in nome(id).split_whitespace() {
+                minuscula.clear();
+                minuscula.extend(palavra.chars().flat_map(char::to_lowercase));
+                match indice.get_mut(minuscula.as_str()) {
+                    Some(lista) if lista.last() == Some(&id) => {}
+                    Some(lista) => lista.push(id),
+                    None => {
+                        indice.insert(minuscula.clone(), vec![id]);
+                    }
+                }
+            }
+        }
+        let mut indice: Vec<(String, Vec<u32>)> = indice.into_iter().collect();
+        indice.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let ocorrencias: usize = indice.iter().map(|(_, ids)| ids.len()).sum();
+        let bytes_palavras: usize = indice.iter().map(|(p, _)| p.len()).sum();
+
+        let mut dados = vec![0u32; CABECALHO];
+        dados[CAB_MAGICO] = MAGICO;
+        dados[CAB_VERSAO] = VERSAO;
+        dados[CAB_PRODUTOS] = produtos as u32;
+        dados[CAB_PALAVRAS] = indice.len() as u32;
+        dados[CAB_OCORRENCIAS] = ocorrencias as u32;
+        dados[CAB_BYTES_PALAVRAS] = bytes_palavras as u32;
+        dados[CAB_CONVERTIDO_DE] = convertido_de.map_or(0, codigo_da_codificacao);
+        dados[CAB_FORMATO] = match formato {
+            Formato::Texto => 0,
+            Formato::Json => 1,
+            Formato::JsonLinhas => 2,
+            Formato::Sqlite => 3,
+        };
+
+        dados.extend_from_slice(&codigos);
+        dados.extend_from_slice(&nomes);
+        dados.extend_from_slice(&por_codigo);
+        dados.extend(por_nome.iter().map(|&(_, id)| id));
+
+        let (mut pos_texto, mut pos_ocorrencia) = (0u32, 0u32);
+        for (palavra, ids) in &indice {
+            let fim_texto = pos_texto + palavra.len() as u32;
+            let fim_ocorrencia = pos_ocorrencia + ids.len() as u32;
+            dados.extend_from_slice(&[pos_texto, fim_texto, pos_ocorrencia, fim_ocorrencia]);
+            pos_texto = fim_texto;
+            pos_ocorrencia = fim_ocorrencia;
+        }
+        for (_, ids) in &indice {
+            dados.extend_from_slice(ids);
+        }
+
+        let mut texto_palavras: Vec<u8> = indice.iter().flat_map(|(p, _)| p.bytes()).collect();
+        texto_palavras.resize(texto_palavras.len().div_ceil(4) * 4, 0);
+        dados.extend(
+            texto_palavras
+                .chunks_exact(4)
+                .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]])),
+        );
+
+        Indice {
+            dados: Dados::Memoria(dados),
+            produtos,
+            palavras: indice.len(),
+            ocorrencias,
+            bytes_palavras,
+        }
+    }
+
+    // 🔹 Mapeia "<catalogo>.idx" se ele corresponder à assinatura do catálogo.
+    // Tamanho e data iguais bastam; com data diferente o checksum decide (e,
+    // se bater, a data nova é gravada no cabeçalho para as próximas aberturas)
+    pub fn carregar(
+        caminho: &str,
+        assinatura: &Assinatura,
+        texto_catalogo: &[u8],
+    ) -> Option<Indice> {
+        let file = File::open(caminho).ok()?;
+        let mapa = unsafe { Mmap::map(&file).ok()? };
+        let mut cabecalho = [0u32; CABECALHO];
+        cabecalho.copy_from_slice(bytemuck::try_cast_slice(mapa.get(..CABECALHO * 4)?).ok()?);
+
+        if cabecalho[CAB_MAGICO] != MAGICO || cabecalho[CAB_VERSAO] != VERSAO {
+            return None;
+        }
+        if ler_u64(&cabecalho, CAB_TAMANHO) != assinatura.tamanho {
+            return None;
+        }
+
+        let mesma_data = ler_u64(&cabecalho, CAB_MODIFICADO_SEG) == assinatura.modificado_seg
+            && cabecalho[CAB_MODIFICADO_NANO] == assinatura.modificado_nano;
+        if !mesma_data {
+            if ler_u64(&cabecalho, CAB_CHECKSUM) != checksum(texto_catalogo) {
+                return None;
+            }
+            let _ = atualizar_data(caminho, assinatura);
+        }
+
+        let indice = Indice {
+            produtos: cabecalho[CAB_PRODUTOS] as usize,
+            palavras: cabecalho[CAB_PALAVRAS] as usize,
+            ocorrencias: cabecalho[CAB_OCORRENCIAS] as usize,
+            bytes_palavras: cabecalho[CAB_BYTES_PALAVRAS] as usize,
+            dados: Dados::Mapeado(mapa),
+        };
+
+        // Arquivo truncado ou desalinhado: melhor reconstruir
+        let dados: &[u32] = match &indice.dados {
+            Dados::Mapeado(m) => bytemuck::try_cast_slice(m).ok()?,
+            Dados::Memoria(v) => v,
+        };
+        if dados.len() != indice.fim_texto_palavras() {
+            return None;
+        }
+        // O checksum do cabeçalho é o do catálogo, não o dos dados do índice:
+        // um .idx corrompido com o tamanho certo também é reconstruído
+        indice.consistente().then_some(indice)
+    }
+
+    // Todo id aponta para um produto e toda faixa do índice invertido cabe na
+    // sua área; assim nenhuma consulta sai dos limites. As faixas de código e
+    // nome não precisam: o catálogo lê o texto com `get`.
+    fn consistente(&self) -> bool {
+        let ids_validos = |ids: &[u32]| ids.iter().all(|&id| (id as usize) < self.produtos);
+        let registros = &self.dados()[self.inicio_palavras()..self.inicio_ocorrencias()];
+        ids_validos(self.ordem_por_codigo())
+            && ids_validos(self.ordem_por_nome())
+            && ids_validos(&self.dados()[self.inicio_ocorrencias()..self.inicio_texto_palavras()])
+            && registros.chunks_exact(4).all(|r| {
+                r[0] <= r[1]
+                    && r[1] as usize <= self.bytes_palavras
+                    && r[2] <= r[3]
+                    && r[3] as usize <= self.ocorrencias
+            })
+    }
+
+    // 🔹 Grava o índice num arquivo temporário e troca pelo definitivo
+    pub fn gravar(&self, caminho: &str, assinatura: &Assinatura) -> io::Result<()> {
+        let mut cabecalho = self.dados()[..CABECALHO].to_vec();
+        escrever_u64(&mut cabecalho, CAB_TAMANHO, assinatura.tamanho);
+        escrever_u64(
+            &mut cabecalho,
+            CAB_MODIFICADO_SEG,
+            assinatura.modificado_seg,
+        );
+        cabecalho[CAB_MODIFICADO_NANO] = assinatura.modificado_nano;
+        escrever_u64(&mut cabecalho, CAB_CHECKSUM, assinatura.checksum);
+
+        let temporario = format!("{}.tmp", caminho);
+        let mut file = File::create(&temporario)?;
+        file.write_all(bytemuck::cast_slice(&cabecalho))?;
+        file.write_all(bytemuck::cast_slice(&self.dados()[CABECALHO..]))?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&temporario, caminho)
+    }
+
+    fn dados(&self) -> &[u32] {
+        match &self.dados {
+            Dados::Memoria(v) => v,
+            Dados::Mapeado(m) => bytemuck::cast_slice(m),
+        }
+    }
+
+    pub fn produtos(&self) -> usize {
+        self.produtos
+    }
+
+    // Codificação do arquivo, quando as faixas apontam para o texto convertido
+    pub fn convertido_de(&self) -> Option<Codificacao> {
+        codificacao_do_codigo(self.dados()[CAB_CONVERTIDO_DE])
+    }
+
+    pub fn formato(&self) -> Formato {
+        match self.dados()[CAB_FORMATO] {
+            1 => Formato::Json,
+            2 => Formato::JsonLinhas,
+            3 => Formato::Sqlite,
+            _ => Formato::Texto,
+        }
+    }
+
+    fn inicio_nomes(&self) -> usize {
+        CABECALHO + 2 * self.produtos
+    }
+
+    fn inicio_ordem_por_codigo(&self) -> usize {
+        CABECALHO + 4 * self.produtos
+    }
+
+    fn inicio_ordem_por_nome(&self) -> usize {
+        CABECALHO + 5 * self.produtos
+    }
+
+    fn inicio_palavras(&self) -> usize {
+        CABECALHO + 6 * self.produtos
+    }
+
+    fn inicio_ocorrencias(&self) -> usize {
+        self.inicio_palavras() + 4 * self.palavras
+    }
+
+    fn inicio_texto_palavras(&self) -> usize {
+        self.inicio_ocorrencias() + self.ocorrencias
+    }
+
+    fn fim_texto_palavras(&self) -> usize {
+        self.inicio_texto_palavras() + self.bytes_palavras.div_ceil(4)
+    }
+
+    // Início e fim (bytes) do código do produto no texto do catálogo
+    pub fn faixa_codigo(&self, id: u32) -> (usize, usize) {
+        let i = CABECALHO + 2 * id as usize;
+        (self.dados()[i] as usize, self.dados()[i + 1] as usize)
+    }
+
+    pub fn faixa_nome(&self, id: u32) -> (usize, usize) {
+        let i = self.inicio_nomes() + 2 * id as usize;
+        (self.dados()[i] as usize, self.dados()[i + 1] as usize)
+    }
+
+    pub fn ordem_por_codigo(&self) -> &[u32] {
+        &self.dados()[self.inicio_ordem_por_codigo()..self.inicio_ordem_por_nome()]
+    }
+
+    pub fn ordem_por_nome(&self) -> &[u32] {
+        &self.dados()[self.inicio_ordem_por_nome()..self.inicio_palavras()]
+    }
+
+    pub fn quantidade_palavras(&self) -> usize {
+        self.palavras
+    }
+
+    // Palavra `i` (em minúsculas) na ordem do índice invertido
+    pub fn palavra(&self, i: usize) -> &str {
+        let registro = &self.dados()[self.inicio_palavras() + 4 * i..];
+        let texto: &[u8] = bytemuck::cast_slice(
+            &self.dados()[self.inicio_texto_palavras()..self.fim_texto_palavras()],
+        );
+        std::str::from_utf8(&texto[registro[0] as usize..registro[1] as usize]).unwrap_or_default()
+    }
+
+    // Ids dos produtos que contêm a palavra `i`
+    pub fn ocorrencias(&self, i: usize) -> &[u32] {
+        let registro = &self.dados()[self.inicio_palavras() + 4 * i..];
+        let ocorrencias = &self.dados()[self.inicio_ocorrencias()..self.inicio_texto_palavras()];
+        &ocorrencias[registro[2] as usize..registro[3] as usize]
+    }
+}
+
+fn codigo_da_codificacao(codificacao: Codificacao) -> u32 {
+    match codificacao {
+        Codificacao::Utf8 => 1,
+        Codificacao::Windows1252 => 2,
+        Codificacao::Latin1 => 3,
+        Codificacao::Utf16Le => 4,
+        Codificacao::Utf16Be => 5,
+    }
+}
+
+fn codificacao_do_codigo(codigo: u32) -> Option<Codificacao> {
+    match codigo {
+        1 => Some(Codificacao::Utf8),
+        2 => Some(Codificacao::Windows1252),
+        3 => Some(Codificacao::Latin1),
+        4 => Some(Codificacao::Utf16Le),
+        5 => Some(Codificacao::Utf16Be),
+        _ => None,
+    }
+}
+
+fn ler_u64(dados: &[u32], pos: usize) -> u64 {
+    dados[pos] as u64 | (dados[pos + 1] as u64) << 32
+}
+
+fn escrever_u64(dados: &mut [u32], pos: usize, valor: u64) {
+    dados[pos] = valor as u32;
+    dados[pos + 1] = (valor >> 32) as u32;
+}
+
+// Regrava só a data de modificação no cabeçalho do índice
+fn atualizar_data(caminho: &str, assinatura: &Assinatura) -> io::Result<()> {
+    let mut campos = [0u32; 3];
+    escrever_u64(&mut campos, 0, assinatura.modificado_seg);
+    campos[2] = assinatura.modificado_nano;
+
+    let mut file = OpenOptions::new().write(true).open(caminho)?;
+    file.seek(SeekFrom::Start((CAB_MODIFICADO_SEG * 4) as u64))?;
+    file.write_all(bytemuck::cast_slice(&campos))
+}
+
+fn fatia<'a>(texto: &'a [u8], faixas: &[u32], id: u32) -> &'a str {
+    let i = 2 * id as usize;
+    std::str::from_utf8(&texto[faixas[i] as usize..faixas[i + 1] as usize]).unwrap_or_default()
+}
+
+// 🔹 Percorre o texto linha a linha guardando as faixas de código e nome
+// (linhas com UTF-8 inválido ou fora das regras de `produto::validar` são ignoradas).
+// Um BOM no início do texto é pulado.
+fn separar_linhas(texto: &[u8]) -> (Vec<u32>, Vec<u32>) {
+    let mut codigos = Vec::new();
+    let mut nomes = Vec::new();
+    let mut inicio = if texto.starts_with(b"\xEF\xBB\xBF") {
+        3
+    } else {
+        0
+    };
+
+    for linha in texto[inicio..].split(|&b| b == b'\n') {
+        let fim = inicio + linha.len();
+        let atual = inicio;
+        inicio = fim + 1;
+
+        if std::str::from_utf8(linha).is_err() {
+            continue;
+        }
+        let Some(sep) = linha.iter().position(|&b| b == b';') else {
+            continue;
+        };
+
+        let codigo = aparar(texto, atual, atual + sep);
+        let nome = aparar(texto, atual + sep + 1, fim);
+        if validar(fatia(texto, &codigo, 0), fatia(texto, &nome, 0)).is_err() {
+            continue;
+        }
+
+        codigos.extend(codigo);
+        nomes.extend(nome);
+    }
+
+    (codigos, nomes)
+}
+
+// Remove espaços e '\r' das pontas da faixa
+fn aparar(texto: &[u8], mut inicio: usize, mut fim: usize) -> [u32; 2] {
+    while inicio < fim && texto[inicio].is_ascii_whitespace() {
+        inicio += 1;
+    }
+    while fim > inicio && texto[fim - 1].is_ascii_whitespace() {
+        fim -= 1;
+    }
+    [inicio as u32, fim as u32]
+}
+
+// 🔹 Ordenação por blocos de 8 bytes do nome em minúsculas: ordena pelos
+// números do bloco atual e só recalcula o bloco seguinte dentro dos empates.
+// Cada nome é lido do texto uma vez por nível, não a cada comparação.
+fn ordenar_por_nome<'a>(ids: &mut [(u64, u32)], nivel: usize, nome: &impl Fn(u32) -> &'a str) {
+    ids.sort_unstable();
+
+    let mut inicio = 0;
+    while inicio < ids.len() {
+        let chave = ids[inicio].0;
+        let fim = inicio + ids[inicio..].partition_point(|&(k, _)| k == chave);
+
+        // Bloco terminado em zero: o nome acabou e os empates são nomes iguais
+        if fim - inicio > 1 && chave & 0xFF != 0 {
+            let empates = &mut ids[inicio..fim];
+            for item in empates.iter_mut() {
+                item.0 = chave_de_ordenacao(nome(item.1), nivel + 1);
+            }
+            ordenar_por_nome(empates, nivel + 1, nome);
+            for item in empates.iter_mut() {
+                item.0 = chave;
+            }
+        }
+
+        inicio = fim;
+    }
+}
+
+// 🔹 Bloco `nivel` (8 bytes) do nome em minúsculas como número; comparar os blocos
+// em sequência dá a mesma ordem de `comparar_sem_caixa`
+fn chave_de_ordenacao(nome: &str, nivel: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    let mut usados = 0;
+    let mut pular = nivel * 8;
+    let mut buf = [0u8; 8];
+
+    for c in nome.chars() {
+        // Caminho rápido para ASCII; demais letras usam a conversão completa
+        let tamanho = if c.is_ascii() {
+            buf[0] = c.to_ascii_lowercase() as u8;
+            1
+        } else {
+            let mut tamanho = 0;
+            for l in c.to_lowercase() {
+                tamanho += l.encode_utf8(&mut buf[tamanho..]).len();
+            }
+            tamanho
+        };
+        for &b in &buf[..tamanho] {
+            if pular > 0 {
+                pular -= 1;
+                continue;
+            }
+            if usados == bytes.len() {
+                return u64::from_be_bytes(bytes);
+            }
+            bytes[usados] = b;
+            usados += 1;
+        }
+    }
+
+    u64::from_be_bytes(bytes)
+}
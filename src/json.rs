@@ -0,0 +1,75 @@
+use std::io::{self, Write};
+
+use serde_json::value::RawValue;
+
+use crate::produto::{Leitura, LinhaInvalida, Produto, validar};
+
+// Produto de um registro JSON, com as mesmas regras do "codigo;nome"
+fn produto_de_registro(registro: &str) -> Result<Produto, String> {
+    let mut produto: Produto =
+        serde_json::from_str(registro).map_err(|e| format!("registro inválido: {}", e))?;
+    produto.codigo = produto.codigo.trim().to_string();
+    produto.nome = produto.nome.trim().to_string();
+    validar(&produto.codigo, &produto.nome)?;
+    Ok(produto)
+}
+
+fn guardar(leitura: &mut Leitura, numero: usize, registro: &str) {
+    match produto_de_registro(registro) {
+        Ok(produto) => leitura.produtos.push(produto),
+        Err(motivo) => leitura.invalidas.push(LinhaInvalida {
+            numero,
+            conteudo: registro.to_string(),
+            motivo,
+        }),
+    }
+}
+
+// 🔹 Lista JSON de produtos. Um erro de sintaxe invalida o arquivo inteiro; um
+// registro sem código ou nome é só rejeitado (com a linha onde começa)
+pub fn ler_json(texto: &str) -> Result<Leitura, String> {
+    let registros: Vec<&RawValue> =
+        serde_json::from_str(texto).map_err(|e| format!("JSON inválido: {}", e))?;
+
+    let mut leitura = Leitura::default();
+    let (mut linha, mut visto) = (1, 0);
+    for registro in registros {
+        // Os registros apontam para dentro do texto, em ordem
+        let inicio = registro.get().as_ptr() as usize - texto.as_ptr() as usize;
+        linha += texto[visto..inicio].matches('\n').count();
+        visto = inicio;
+
+        guardar(&mut leitura, linha, registro.get());
+    }
+
+    Ok(leitura)
+}
+
+// 🔹 JSON Lines: cada linha é um registro independente
+pub fn ler_jsonl(texto: &str) -> Leitura {
+    let mut leitura = Leitura::default();
+    for (i, linha) in texto.lines().enumerate() {
+        if !linha.trim().is_empty() {
+            guardar(&mut leitura, i + 1, linha.trim());
+        }
+    }
+    leitura
+}
+
+// 🔹 Lista JSON com um produto por linha (fácil de comparar num diff)
+pub fn escrever_json<W: Write>(mut saida: W, produtos: &[Produto]) -> io::Result<()> {
+    saida.write_all(b"[")?;
+    for (i, produto) in produtos.iter().enumerate() {
+        saida.write_all(if i == 0 { b"\n  " } else { b",\n  " })?;
+        serde_json::to_writer(&mut saida, produto)?;
+    }
+    saida.write_all(b"\n]\n")
+}
+
+pub fn escrever_jsonl<W: Write>(mut saida: W, produtos: &[Produto]) -> io::Result<()> {
+    for produto in produtos {
+        serde_json::to_writer(&mut saida, produto)?;
+        saida.write_all(b"\n")?;
+    }
+    Ok(())
+}
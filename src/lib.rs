@@ -0,0 +1,33 @@
+// Biblioteca compartilhada entre os executáveis da MegaStore (src/bin)
+pub mod auditoria;
+pub mod balanca;
+pub mod cadastro;
+pub mod catalogo;
+pub mod categorias;
+pub mod codificacao;
+pub mod codigo_barras;
+pub mod csv;
+pub mod diario;
+pub mod diferencas;
+pub mod dinheiro;
+pub mod escpos;
+pub mod estoque;
+pub mod etiquetas;
+pub mod formato;
+pub mod gerador;
+pub mod gtin;
+pub mod historico;
+pub mod indice;
+pub mod json;
+pub mod margem;
+pub mod mesclagem;
+pub mod operadores;
+pub mod orcamento;
+pub mod pix;
+pub mod produto;
+pub mod reajuste;
+pub mod recarga;
+pub mod reposicao;
+pub mod sqlite;
+pub mod texto;
+pub mod venda;
@@ -0,0 +1,278 @@
+// 🔹 Custo, margem e markup dos produtos.
+//
+// A margem é o lucro sobre o preço de venda ((preço - custo) / preço) e o
+// markup é o lucro sobre o custo ((preço - custo) / custo). O preço sugerido
+// aplica um markup ao custo. Só entram os produtos com custo e preço
+// cadastrados.
+
+use std::io::{self, Write};
+
+use crate::catalogo::Catalogo;
+use crate::dinheiro::Reais;
+use crate::estoque::formatar_quantidade;
+use crate::venda::Venda;
+
+// Margem mínima desejada, em %, quando não se informa outra
+pub const MARGEM_META: f64 = 30.0;
+
+// Markup do preço sugerido, em %, quando não se informa outro
+pub const MARKUP_PADRAO: f64 = 60.0;
+
+// 🔹 Margem em % do preço (None sem preço positivo)
+pub fn margem(preco: Reais, custo: Reais) -> Option<f64> {
+    (preco > Reais::ZERO)
+        .then(|| (preco - custo).centavos() as f64 * 100.0 / preco.centavos() as f64)
+}
+
+// 🔹 Markup em % do custo (None sem custo positivo)
+pub fn markup(preco: Reais, custo: Reais) -> Option<f64> {
+    (custo > Reais::ZERO)
+        .then(|| (preco - custo).centavos() as f64 * 100.0 / custo.centavos() as f64)
+}
+
+// 🔹 Preço de venda pelo custo mais o markup
+pub fn preco_sugerido(custo: Reais, markup: f64) -> Reais {
+    custo.vezes(1.0 + markup / 100.0)
+}
+
+// Percentual com vírgula e uma casa ("37,5%")
+pub fn formatar_percentual(percentual: f64) -> String {
+    format!("{:.1}%", percentual).replace('.', ",")
+}
+
+// 🔹 Uma linha do relatório de margens
+#[derive(Debug, Clone)]
+pub struct LinhaDeMargem {
+    pub codigo: String,
+    pub nome: String,
+    pub categoria: Option<String>,
+    pub custo: Reais,
+    pub preco: Reais,
+    pub margem: f64,
+    pub markup: Option<f64>,
+}
+
+// 🔹 Produtos com custo e preço, por código
+pub fn por_produto(catalogo: &Catalogo) -> Vec<LinhaDeMargem> {
+    let mut linhas = Vec::new();
+    for &id in catalogo.ordem_por_codigo() {
+        let complemento = catalogo.complemento(id);
+        let (Some(custo), Some(preco)) = (complemento.custo, complemento.preco) else {
+            continue;
+        };
+        let Some(margem) = margem(preco, custo) else {
+            continue;
+        };
+        linhas.push(LinhaDeMargem {
+            codigo: catalogo.codigo(id).to_string(),
+            nome: catalogo.nome(id).to_string(),
+            categoria: complemento.categoria.clone(),
+            custo,
+            preco,
+            margem,
+            markup: markup(preco, custo),
+        });
+    }
+    linhas
+}
+
+// 🔹 Resumo de uma categoria: a margem e o markup de uma unidade de cada
+// produto, somando custos e preços (produto caro pesa mais que o barato)
+#[derive(Debug, Clone)]
+pub struct MargemDaCategoria {
+    pub categoria: Option<String>,
+    pub produtos: usize,
+    pub margem: Option<f64>,
+    pub markup: Option<f64>,
+    // Produtos abaixo da meta
+    pub abaixo: usize,
+}
+
+// 🔹 Margens por categoria, em ordem alfabética (os sem categoria por último)
+pub fn por_categoria(linhas: &[LinhaDeMargem], meta: f64) -> Vec<MargemDaCategoria> {
+    let mut grupos: Vec<(Option<&str>, Vec<&LinhaDeMargem>)> = Vec::new();
+    for l in linhas {
+        let categoria = l.categoria.as_deref();
+        match grupos.iter_mut().find(|(c, _)| *c == categoria) {
+            Some((_, itens)) => itens.push(l),
+            None => grupos.push((categoria, vec![l])),
+        }
+    }
+    grupos.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) => crate::texto::comparar_sem_caixa(a, b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+
+    grupos
+        .into_iter()
+        .map(|(categoria, itens)| {
+            let custo: Reais = itens.iter().map(|l| l.custo).sum();
+            let preco: Reais = itens.iter().map(|l| l.preco).sum();
+            MargemDaCategoria {
+                categoria: categoria.map(str::to_string),
+                produtos: itens.len(),
+                margem: margem(preco, custo),
+                markup: markup(preco, custo),
+                abaixo: itens.iter().filter(|l| l.margem < meta).count(),
+            }
+        })
+        .collect()
+}
+
+// 🔹 Produto vendido com margem abaixo da meta, somando as vendas
+#[derive(Debug, Clone)]
+pub struct VendaAbaixo {
+    pub codigo: String,
+    pub nome: String,
+    pub quantidade: f64,
+    // A pior margem entre as vendas
+    pub menor_margem: f64,
+    pub vendas: usize,
+}
+
+// 🔹 Itens vendidos abaixo da meta de margem, pelo preço cobrado em cada
+// venda e o custo cadastrado hoje (o custo da época não fica guardado)
+pub fn vendidos_abaixo(vendas: &[Venda], catalogo: &Catalogo, meta: f64) -> Vec<VendaAbaixo> {
+    let mut abaixo: Vec<VendaAbaixo> = Vec::new();
+    for venda in vendas {
+        for item in &venda.itens {
+            let custo = catalogo
+                .buscar_por_codigo(&item.codigo)
+                .and_then(|id| catalogo.complemento(id).custo);
+            let Some(custo) = custo else {
+                continue;
+            };
+            // Etiqueta de balança: o preço efetivo é o total da linha
+            let preco = match item.etiqueta {
+                Some(total) if item.quantidade > 0.0 => {
+                    Reais::de_centavos((total.centavos() as f64 / item.quantidade).round() as i64)
+                }
+                _ => item.preco,
+            };
+            let Some(m) = margem(preco, custo).filter(|&m| m < meta) else {
+                continue;
+            };
+            match abaixo.iter_mut().find(|a| a.codigo == item.codigo) {
+                Some(a) => {
+                    a.quantidade += item.quantidade;
+                    a.menor_margem = a.menor_margem.min(m);
+                    a.vendas += 1;
+                }
+                None => abaixo.push(VendaAbaixo {
+                    codigo: item.codigo.clone(),
+                    nome: item.nome.clone(),
+                    quantidade: item.quantidade,
+                    menor_margem: m,
+                    vendas: 1,
+                }),
+            }
+        }
+    }
+    abaixo.sort_by(|a, b| a.codigo.cmp(&b.codigo));
+    abaixo
+}
+
+// 🔹 Custo com margem, markup e preço sugerido, para a ficha do produto:
+// "R$ 7,50 (margem 42,3%, markup 72,0%; sugerido R$ 12,00 com markup 60,0%)"
+pub fn resumo_do_custo(custo: Reais, preco: Option<Reais>, markup_sugerido: f64) -> String {
+    let percentual = |p: Option<f64>| p.map_or("-".to_string(), formatar_percentual);
+    let margens = match preco {
+        Some(preco) => format!(
+            "margem {}, markup {}; ",
+            percentual(margem(preco, custo)),
+            percentual(markup(preco, custo))
+        ),
+        None => String::new(),
+    };
+    format!(
+        "{} ({}sugerido {} com markup {})",
+        custo.com_simbolo(),
+        margens,
+        preco_sugerido(custo, markup_sugerido).com_simbolo(),
+        formatar_percentual(markup_sugerido)
+    )
+}
+
+// 🔹 Produtos, com "⚠️" nos abaixo da meta e o preço sugerido pelo markup
+pub fn escrever_produtos<W: Write>(
+    mut saida: W,
+    linhas: &[LinhaDeMargem],
+    meta: f64,
+    markup_sugerido: f64,
+) -> io::Result<()> {
+    writeln!(
+        saida,
+        "Margem e markup por produto (meta de margem {}, preço sugerido com markup {}):",
+        formatar_percentual(meta),
+        formatar_percentual(markup_sugerido)
+    )?;
+    for l in linhas {
+        writeln!(
+            saida,
+            "{} [{}] {}  custo {}, preço {}, margem {}, markup {}, sugerido {}",
+            if l.margem < meta { "⚠️" } else { "  " },
+            l.codigo,
+            l.nome,
+            l.custo,
+            l.preco,
+            formatar_percentual(l.margem),
+            l.markup.map_or("-".to_string(), formatar_percentual),
+            preco_sugerido(l.custo, markup_sugerido)
+        )?;
+    }
+    Ok(())
+}
+
+pub fn escrever_categorias<W: Write>(
+    mut saida: W,
+    linhas: &[LinhaDeMargem],
+    meta: f64,
+) -> io::Result<()> {
+    writeln!(saida, "Margem por categoria:")?;
+    for c in por_categoria(linhas, meta) {
+        writeln!(
+            saida,
+            "  {}: {} produtos, margem {}, markup {}, {} abaixo da meta",
+            c.categoria.as_deref().unwrap_or("(sem categoria)"),
+            c.produtos,
+            c.margem.map_or("-".to_string(), formatar_percentual),
+            c.markup.map_or("-".to_string(), formatar_percentual),
+            c.abaixo
+        )?;
+    }
+    Ok(())
+}
+
+pub fn escrever_vendidos<W: Write>(mut saida: W, vendidos: &[VendaAbaixo]) -> io::Result<()> {
+    writeln!(saida, "Vendidos abaixo da meta:")?;
+    for v in vendidos {
+        writeln!(
+            saida,
+            "  [{}] {}  {} em {} vendas, menor margem {}",
+            v.codigo,
+            v.nome,
+            formatar_quantidade(v.quantidade),
+            v.vendas,
+            formatar_percentual(v.menor_margem)
+        )?;
+    }
+    Ok(())
+}
+
+// 🔹 Relatório completo: produtos, categorias e vendas abaixo da meta
+pub fn escrever_relatorio<W: Write>(
+    mut saida: W,
+    linhas: &[LinhaDeMargem],
+    vendidos: &[VendaAbaixo],
+    meta: f64,
+    markup_sugerido: f64,
+) -> io::Result<()> {
+    escrever_produtos(&mut saida, linhas, meta, markup_sugerido)?;
+    writeln!(saida)?;
+    escrever_categorias(&mut saida, linhas, meta)?;
+    if !vendidos.is_empty() {
+        writeln!(saida)?;
+        escrever_vendidos(&mut saida, vendidos)?;
+    }
+    Ok(())
+}
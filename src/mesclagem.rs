@@ -0,0 +1,124 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::produto::Produto;
+
+// 🔹 O que fazer quando o mesmo código aparece com nomes diferentes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegraConflito {
+    // Vale o nome da primeira fonte em que o código aparece
+    Primeiro,
+    // Vale o nome da última fonte (a lista mais recente sobrescreve)
+    #[default]
+    Ultimo,
+    // Interrompe a mesclagem no primeiro conflito
+    Falhar,
+}
+
+// 🔹 O que fazer quando o mesmo nome aparece com códigos diferentes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegraMesmoNome {
+    // Mantém todos os códigos
+    #[default]
+    Manter,
+    // Mantém só o primeiro código que recebeu o nome
+    Descartar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoConflito {
+    MesmoCodigo,
+    MesmoNome,
+}
+
+// Produto que ficou de fora da saída por causa de outro
+#[derive(Debug, Clone)]
+pub struct Conflito {
+    pub tipo: TipoConflito,
+    pub mantido: Produto,
+    pub descartado: Produto,
+}
+
+// 🔹 Resultado da mesclagem: produtos em ordem de código e conflitos resolvidos
+#[derive(Debug, Default)]
+pub struct Mesclagem {
+    pub produtos: Vec<Produto>,
+    pub conflitos: Vec<Conflito>,
+    // Linhas idênticas (mesmo código e nome) vindas de mais de uma fonte
+    pub repetidos: usize,
+}
+
+// 🔹 Junta as fontes na ordem dada. Com `RegraConflito::Falhar`, devolve o
+// primeiro conflito de código encontrado em vez do resultado.
+pub fn mesclar(
+    fontes: &[Vec<Produto>],
+    conflito: RegraConflito,
+    mesmo_nome: RegraMesmoNome,
+) -> Result<Mesclagem, Box<Conflito>> {
+    // Código -> (ordem de chegada, produto)
+    let mut por_codigo: BTreeMap<&str, (usize, &Produto)> = BTreeMap::new();
+    let mut mesclagem = Mesclagem::default();
+    let mut chegada = 0;
+
+    for produto in fontes.iter().flatten() {
+        chegada += 1;
+        let Some(&(ordem, atual)) = por_codigo.get(produto.codigo.as_str()) else {
+            por_codigo.insert(&produto.codigo, (chegada, produto));
+            continue;
+        };
+
+        if atual.nome == produto.nome {
+            mesclagem.repetidos += 1;
+            continue;
+        }
+
+        let (mantido, descartado) = match conflito {
+            RegraConflito::Primeiro => (atual, produto),
+            RegraConflito::Ultimo => (produto, atual),
+            RegraConflito::Falhar => {
+                return Err(Box::new(Conflito {
+                    tipo: TipoConflito::MesmoCodigo,
+                    mantido: atual.clone(),
+                    descartado: produto.clone(),
+                }));
+            }
+        };
+        por_codigo.insert(&produto.codigo, (ordem, mantido));
+        mesclagem.conflitos.push(Conflito {
+            tipo: TipoConflito::MesmoCodigo,
+            mantido: mantido.clone(),
+            descartado: descartado.clone(),
+        });
+    }
+
+    // Nomes repetidos: o código que chegou primeiro fica com o nome
+    let mut descartados = vec![false; chegada + 1];
+    if mesmo_nome == RegraMesmoNome::Descartar {
+        let mut por_chegada: Vec<(usize, &Produto)> = por_codigo.values().copied().collect();
+        por_chegada.sort_unstable_by_key(|&(ordem, _)| ordem);
+
+        let mut dono: HashMap<String, &Produto> = HashMap::new();
+        for (ordem, produto) in por_chegada {
+            match dono.get(&produto.nome.to_lowercase()) {
+                Some(&mantido) => {
+                    descartados[ordem] = true;
+                    mesclagem.conflitos.push(Conflito {
+                        tipo: TipoConflito::MesmoNome,
+                        mantido: mantido.clone(),
+                        descartado: produto.clone(),
+                    });
+                }
+                None => {
+                    dono.insert(produto.nome.to_lowercase(), produto);
+                }
+            }
+        }
+    }
+
+    mesclagem.produtos = por_codigo
+        .into_values()
+        .filter(|&(ordem, _)| !descartados[ordem])
+        .map(|(_, produto)| produto.clone())
+        .collect();
+
+    Ok(mesclagem)
+}
@@ -0,0 +1,420 @@
+// 🔹 Contas de operador ("operadores.json", na pasta do catálogo) e o que cada
+// perfil pode fazer. As senhas ficam só como hash PBKDF2-HMAC-SHA256 com sal
+// aleatório próprio. Sem nenhuma conta cadastrada, o programa funciona como
+// antes, sem login. Cada conta incluída, removida ou com senha trocada fica
+// na trilha de auditoria do catálogo.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use pbkdf2::pbkdf2_hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::auditoria::{self, Auditoria, Evento};
+
+pub const ARQUIVO_OPERADORES: &str = "operadores.json";
+
+// Operações das contas na trilha de auditoria
+const INCLUIR: &str = "operador-incluir";
+const REMOVER: &str = "operador-remover";
+const SENHA: &str = "operador-senha";
+// A gravação do arquivo falhou: a descrição começa com quantas entradas
+// anteriores do mesmo operador (o lote inteiro de `salvar`) não valeram
+const FALHA: &str = "operador-falha";
+
+// Rodadas do PBKDF2 para senhas novas (as antigas guardam as suas)
+const ITERACOES: u32 = 100_000;
+const TAMANHO_SAL: usize = 16;
+const TAMANHO_MINIMO_SENHA: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Papel {
+    Caixa,
+    Estoquista,
+    Gerente,
+}
+
+// 🔹 O que pode ser liberado ou bloqueado por perfil
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permissao {
+    // Buscas, listagens e histórico
+    Consultar,
+    Vender,
+    // Incluir e renomear produtos, importar listas
+    Cadastrar,
+    MovimentarEstoque,
+    Exportar,
+    Remover,
+    AlterarPreco,
+    Desfazer,
+    // Compactar o diário e conferir a auditoria
+    Manutencao,
+    GerenciarOperadores,
+}
+
+impl Papel {
+    pub fn de_nome(nome: &str) -> Option<Papel> {
+        match nome.to_lowercase().as_str() {
+            "caixa" => Some(Papel::Caixa),
+            "estoquista" => Some(Papel::Estoquista),
+            "gerente" => Some(Papel::Gerente),
+            _ => None,
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            Papel::Caixa => "caixa",
+            Papel::Estoquista => "estoquista",
+            Papel::Gerente => "gerente",
+        }
+    }
+
+    // 🔹 Caixa consulta e vende; estoquista cadastra, movimenta o estoque e
+    // exporta; só o gerente remove, muda preço, desfaz e cuida das contas
+    pub fn pode(&self, permissao: Permissao) -> bool {
+        use Permissao::*;
+        match self {
+            Papel::Caixa => matches!(permissao, Consultar | Vender),
+            Papel::Estoquista => matches!(
+                permissao,
+                Consultar | Cadastrar | MovimentarEstoque | Exportar
+            ),
+            Papel::Gerente => true,
+        }
+    }
+}
+
+impl Permissao {
+    // Para a mensagem "o perfil caixa não pode <descrição>"
+    pub fn descricao(&self) -> &'static str {
+        match self {
+            Permissao::Consultar => "consultar o catálogo",
+            Permissao::Vender => "registrar vendas",
+            Permissao::Cadastrar => "incluir ou renomear produtos",
+            Permissao::MovimentarEstoque => "movimentar o estoque",
+            Permissao::Exportar => "exportar o catálogo",
+            Permissao::Remover => "remover produtos",
+            Permissao::AlterarPreco => "alterar preços",
+            Permissao::Desfazer => "desfazer ou refazer alterações",
+            Permissao::Manutencao => "fazer a manutenção dos arquivos",
+            Permissao::GerenciarOperadores => "gerenciar operadores",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operador {
+    pub nome: String,
+    pub papel: Papel,
+    sal: String,
+    hash: String,
+    iteracoes: u32,
+}
+
+impl Operador {
+    fn confere(&self, senha: &str) -> bool {
+        let (Some(sal), Some(hash)) = (de_hex(&self.sal), de_hex(&self.hash)) else {
+            return false;
+        };
+        // Comparação sem parar no primeiro byte diferente
+        let calculado = derivar(senha, &sal, self.iteracoes);
+        calculado.len() == hash.len()
+            && calculado
+                .iter()
+                .zip(&hash)
+                .fold(0, |dif, (a, b)| dif | (a ^ b))
+                == 0
+    }
+}
+
+// 🔹 Quem está usando o programa
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sessao {
+    pub nome: String,
+    pub papel: Papel,
+}
+
+impl Sessao {
+    // Sem contas cadastradas: o usuário do sistema, com acesso a tudo. Quem
+    // apaga `operadores.json` e também a trilha de auditoria volta a este
+    // modo; só permissões do sistema nas pastas impedem isso.
+    pub fn sem_cadastro() -> Sessao {
+        Sessao {
+            nome: auditoria::operador_do_sistema(),
+            papel: Papel::Gerente,
+        }
+    }
+
+    pub fn pode(&self, permissao: Permissao) -> bool {
+        self.papel.pode(permissao)
+    }
+
+    // 🔹 Mensagem de recusa, ou Ok se o perfil pode
+    pub fn exigir(&self, permissao: Permissao) -> Result<(), String> {
+        if self.pode(permissao) {
+            Ok(())
+        } else {
+            Err(format!(
+                "O perfil {} não pode {}.",
+                self.papel.nome(),
+                permissao.descricao()
+            ))
+        }
+    }
+}
+
+// 🔹 As contas valem para todos os catálogos da mesma pasta
+pub fn caminho_dos_operadores(catalogo: &str) -> String {
+    Path::new(catalogo)
+        .with_file_name(ARQUIVO_OPERADORES)
+        .to_string_lossy()
+        .into_owned()
+}
+
+// 🔹 As contas cadastradas
+pub struct Operadores {
+    catalogo: String,
+    caminho: String,
+    lista: Vec<Operador>,
+    // Alterações ainda não gravadas, para a trilha de auditoria
+    eventos: Vec<Evento>,
+}
+
+impl Operadores {
+    // 🔹 As contas da pasta do catálogo. Arquivo inexistente = nenhuma conta,
+    // a não ser que a trilha de auditoria diga que ainda havia contas: aí o
+    // arquivo foi apagado ou esvaziado por fora e o programa não abre sem login.
+    pub fn abrir(catalogo: &str) -> io::Result<Operadores> {
+        let caminho = caminho_dos_operadores(catalogo);
+        let lista: Vec<Operador> = match fs::read_to_string(&caminho) {
+            Ok(texto) => serde_json::from_str(&texto)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        if lista.is_empty() {
+            let contas = contas_na_trilha(catalogo)?;
+            if contas > 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "a trilha de auditoria mostra contas de operador ativas ({}), mas o arquivo está vazio ou foi apagado; restaure-o do backup",
+                        contas
+                    ),
+                ));
+            }
+        }
+        Ok(Operadores {
+            catalogo: catalogo.to_string(),
+            caminho,
+            lista,
+            eventos: Vec::new(),
+        })
+    }
+
+    pub fn caminho(&self) -> &str {
+        &self.caminho
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lista.is_empty()
+    }
+
+    pub fn listar(&self) -> &[Operador] {
+        &self.lista
+    }
+
+    // 🔹 Sessão do operador, se nome e senha conferem
+    pub fn autenticar(&self, nome: &str, senha: &str) -> Option<Sessao> {
+        self.lista
+            .iter()
+            .find(|o| o.nome == nome && o.confere(senha))
+            .map(|o| Sessao {
+                nome: o.nome.clone(),
+                papel: o.papel,
+            })
+    }
+
+    // 🔹 Cadastra a conta (a primeira precisa ser de gerente, para alguém
+    // poder cadastrar as demais)
+    pub fn adicionar(&mut self, nome: &str, papel: Papel, senha: &str) -> Result<(), String> {
+        if nome.is_empty() || nome.contains(char::is_whitespace) {
+            return Err("o nome do operador não pode ser vazio nem ter espaços".to_string());
+        }
+        if self.lista.iter().any(|o| o.nome == nome) {
+            return Err(format!("o operador '{}' já existe", nome));
+        }
+        if self.lista.is_empty() && papel != Papel::Gerente {
+            return Err("o primeiro operador cadastrado precisa ser gerente".to_string());
+        }
+
+        let mut operador = Operador {
+            nome: nome.to_string(),
+            papel,
+            sal: String::new(),
+            hash: String::new(),
+            iteracoes: ITERACOES,
+        };
+        definir_senha(&mut operador, senha)?;
+        self.lista.push(operador);
+        self.eventos.push(Evento {
+            operacao: INCLUIR.to_string(),
+            descricao: format!("operador '{}' ({}) cadastrado", nome, papel.nome()),
+            ..Evento::default()
+        });
+        Ok(())
+    }
+
+    pub fn trocar_senha(&mut self, nome: &str, senha: &str) -> Result<(), String> {
+        let operador = self
+            .lista
+            .iter_mut()
+            .find(|o| o.nome == nome)
+            .ok_or_else(|| format!("operador '{}' não encontrado", nome))?;
+        definir_senha(operador, senha)?;
+        self.eventos.push(Evento {
+            operacao: SENHA.to_string(),
+            descricao: format!("senha de '{}' trocada", nome),
+            ..Evento::default()
+        });
+        Ok(())
+    }
+
+    // 🔹 Tira a conta; o último gerente não pode sair enquanto houver outras contas
+    pub fn remover(&mut self, nome: &str) -> Result<(), String> {
+        let pos = self
+            .lista
+            .iter()
+            .position(|o| o.nome == nome)
+            .ok_or_else(|| format!("operador '{}' não encontrado", nome))?;
+        let gerentes = self
+            .lista
+            .iter()
+            .filter(|o| o.papel == Papel::Gerente)
+            .count();
+        if self.lista[pos].papel == Papel::Gerente && gerentes == 1 && self.lista.len() > 1 {
+            return Err("não é possível remover o único gerente".to_string());
+        }
+        let removido = self.lista.remove(pos);
+        self.eventos.push(Evento {
+            operacao: REMOVER.to_string(),
+            descricao: format!(
+                "operador '{}' ({}) removido",
+                removido.nome,
+                removido.papel.nome()
+            ),
+            ..Evento::default()
+        });
+        Ok(())
+    }
+
+    // 🔹 Registra as alterações na trilha de auditoria do catálogo em nome de
+    // `operador` e grava num temporário e troca, como o catálogo. Sem trilha,
+    // nada é gravado; se a gravação falhar depois, uma entrada "operador-falha"
+    // desfaz, para a contagem de contas, o lote inteiro.
+    pub fn salvar(&mut self, operador: &str) -> io::Result<()> {
+        let mut auditoria = Auditoria::abrir(&self.catalogo)?;
+        let descricoes: Vec<String> = self.eventos.iter().map(|e| e.descricao.clone()).collect();
+        auditoria.registrar(operador, std::mem::take(&mut self.eventos))?;
+
+        let resultado = self.gravar();
+        if let Err(e) = &resultado {
+            let _ = auditoria.registrar(
+                operador,
+                vec![Evento {
+                    operacao: FALHA.to_string(),
+                    descricao: format!(
+                        "{} não gravada(s): {} ({})",
+                        descricoes.len(),
+                        descricoes.join(", "),
+                        e
+                    ),
+                    ..Evento::default()
+                }],
+            );
+        }
+        resultado
+    }
+
+    fn gravar(&self) -> io::Result<()> {
+        let texto = serde_json::to_string_pretty(&self.lista).map_err(io::Error::other)?;
+        let temporario = format!("{}.tmp", self.caminho);
+        fs::write(&temporario, texto)?;
+        fs::rename(&temporario, &self.caminho)
+    }
+}
+
+// Quantas contas a trilha diz que existem: cada inclusão soma e cada remoção
+// tira. Uma "operador-falha" anula as últimas entradas de conta do mesmo
+// operador, tantas quantas a descrição diz (não chegaram ao arquivo).
+fn contas_na_trilha(catalogo: &str) -> io::Result<usize> {
+    // (operador, variação) de cada entrada de conta, na ordem da trilha
+    let mut variacoes: Vec<(String, isize)> = Vec::new();
+    for entrada in auditoria::ler(catalogo)? {
+        let variacao = match entrada.operacao.as_str() {
+            INCLUIR => 1,
+            REMOVER => -1,
+            SENHA => 0,
+            FALHA => {
+                let mut anuladas = entrada
+                    .descricao
+                    .split_once(' ')
+                    .and_then(|(n, _)| n.parse::<usize>().ok())
+                    .unwrap_or(0);
+                let mut i = variacoes.len();
+                while anuladas > 0 && i > 0 {
+                    i -= 1;
+                    if variacoes[i].0 == entrada.operador {
+                        variacoes.remove(i);
+                        anuladas -= 1;
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        variacoes.push((entrada.operador, variacao));
+    }
+    let contas: isize = variacoes.iter().map(|(_, v)| v).sum();
+    Ok(contas.max(0) as usize)
+}
+
+fn definir_senha(operador: &mut Operador, senha: &str) -> Result<(), String> {
+    if senha.chars().count() < TAMANHO_MINIMO_SENHA {
+        return Err(format!(
+            "a senha precisa ter pelo menos {} caracteres",
+            TAMANHO_MINIMO_SENHA
+        ));
+    }
+    let mut sal = [0u8; TAMANHO_SAL];
+    getrandom::fill(&mut sal).map_err(|e| format!("sem gerador aleatório: {}", e))?;
+
+    operador.iteracoes = ITERACOES;
+    operador.sal = hex(&sal);
+    operador.hash = hex(&derivar(senha, &sal, ITERACOES));
+    Ok(())
+}
+
+fn derivar(senha: &str, sal: &[u8], iteracoes: u32) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(senha.as_bytes(), sal, iteracoes, &mut hash);
+    hash
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn de_hex(texto: &str) -> Option<Vec<u8>> {
+    if !texto.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..texto.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(texto.get(i..i + 2)?, 16).ok())
+        .collect()
+}
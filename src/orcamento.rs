@@ -0,0 +1,388 @@
+// 🔹 Orçamentos ("<catalogo>.orcamentos.jsonl"): a lista de itens com os preços
+// do dia, um número e a validade. Cada mudança (reserva, venda) grava o
+// orçamento de novo no fim do arquivo; vale a última versão de cada número.
+// Os preços ficam congelados: a venda feita a partir do orçamento usa os
+// preços gravados nele, mesmo que o cadastro tenha mudado.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::dinheiro::Reais;
+use crate::estoque::{Estoque, formatar_quantidade};
+use crate::venda::{
+    Carrinho, Item, LARGURA_CUPOM, LOJA, Pagamento, Venda, Vendas, centralizado, linha_com_valor,
+};
+
+// Validade quando ninguém diz outra
+pub const DIAS_DE_VALIDADE: u64 = 10;
+
+// 🔹 Em que pé o orçamento está
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Situacao {
+    Aberto,
+    // Os itens ficam separados no estoque até a validade
+    Reservado,
+    // Virou a venda com este número
+    Vendido(u64),
+}
+
+impl Situacao {
+    pub fn nome(&self) -> String {
+        match self {
+            Situacao::Aberto => "aberto".to_string(),
+            Situacao::Reservado => "reservado".to_string(),
+            Situacao::Vendido(venda) => format!("vendido (venda {})", venda),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Orcamento {
+    pub numero: u64,
+    pub quando: DateTime<Local>,
+    // Último dia em que vale
+    pub validade: NaiveDate,
+    pub operador: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cliente: Option<String>,
+    pub itens: Vec<Item>,
+    pub total: Reais,
+    pub situacao: Situacao,
+}
+
+impl Orcamento {
+    pub fn vencido(&self, hoje: NaiveDate) -> bool {
+        hoje > self.validade
+    }
+
+    // Carrinho com os itens e os preços do orçamento
+    pub fn carrinho(&self) -> Carrinho {
+        let mut carrinho = Carrinho::default();
+        for item in &self.itens {
+            carrinho.adicionar(item.clone());
+        }
+        carrinho
+    }
+}
+
+pub fn caminho_dos_orcamentos(caminho: &str) -> String {
+    format!("{}.orcamentos.jsonl", caminho)
+}
+
+// 🔹 A última versão de cada orçamento, por número (arquivo inexistente = nenhum)
+pub fn ler(caminho: &str) -> io::Result<Vec<Orcamento>> {
+    let texto = match fs::read_to_string(caminho_dos_orcamentos(caminho)) {
+        Ok(texto) => texto,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut por_numero = BTreeMap::new();
+    for orcamento in texto
+        .lines()
+        .filter_map(|linha| serde_json::from_str::<Orcamento>(linha).ok())
+    {
+        por_numero.insert(orcamento.numero, orcamento);
+    }
+    Ok(por_numero.into_values().collect())
+}
+
+pub fn buscar(caminho: &str, numero: u64) -> io::Result<Option<Orcamento>> {
+    Ok(ler(caminho)?.into_iter().find(|o| o.numero == numero))
+}
+
+// 🔹 Quantidade reservada de cada produto pelos orçamentos reservados que
+// ainda valem (os vencidos liberam a reserva sozinhos)
+pub fn reservado(caminho: &str, hoje: NaiveDate) -> io::Result<HashMap<String, f64>> {
+    reservado_exceto(caminho, hoje, None)
+}
+
+// As reservas sem as do orçamento `exceto` (o que está sendo conferido)
+fn reservado_exceto(
+    caminho: &str,
+    hoje: NaiveDate,
+    exceto: Option<u64>,
+) -> io::Result<HashMap<String, f64>> {
+    let mut reservado = HashMap::new();
+    for orcamento in ler(caminho)? {
+        if orcamento.situacao != Situacao::Reservado
+            || orcamento.vencido(hoje)
+            || Some(orcamento.numero) == exceto
+        {
+            continue;
+        }
+        for item in &orcamento.itens {
+            *reservado.entry(item.codigo.clone()).or_insert(0.0) += item.quantidade;
+        }
+    }
+    Ok(reservado)
+}
+
+// 🔹 Itens do orçamento que o estoque não cobre, descontadas as reservas dos
+// outros orçamentos, com a quantidade disponível de cada um
+pub fn faltas<'a>(
+    caminho: &str,
+    estoque: &mut Estoque,
+    orcamento: &'a Orcamento,
+) -> io::Result<Vec<(&'a Item, f64)>> {
+    let reservado = reservado_exceto(caminho, Local::now().date_naive(), Some(orcamento.numero))?;
+    let mut faltas = Vec::new();
+    for item in &orcamento.itens {
+        let disponivel =
+            estoque.saldo(&item.codigo)? - reservado.get(&item.codigo).copied().unwrap_or(0.0);
+        if disponivel < item.quantidade {
+            faltas.push((item, disponivel));
+        }
+    }
+    Ok(faltas)
+}
+
+// 🔹 Recusa a venda que tiraria itens reservados para outros orçamentos
+// (`orcamento` é o que está sendo vendido, se for o caso). Produto sem
+// reserva sai mesmo com o saldo curto, como sempre.
+pub(crate) fn conferir_reservas(
+    caminho: &str,
+    estoque: &mut Estoque,
+    itens: &[Item],
+    orcamento: Option<u64>,
+) -> io::Result<()> {
+    let reservado = reservado_exceto(caminho, Local::now().date_naive(), orcamento)?;
+    // O mesmo produto em mais de uma linha conta junto
+    let mut pedido: HashMap<&str, f64> = HashMap::new();
+    for item in itens {
+        *pedido.entry(&item.codigo).or_insert(0.0) += item.quantidade;
+    }
+
+    for item in itens {
+        let Some(quantidade) = pedido.remove(item.codigo.as_str()) else {
+            continue;
+        };
+        let Some(&reserva) = reservado.get(&item.codigo) else {
+            continue;
+        };
+        let disponivel = estoque.saldo(&item.codigo)? - reserva;
+        if disponivel < quantidade {
+            return Err(recusar(format!(
+                "[{}] {}: pedido {}, disponível {} (reservado {} em orçamentos)",
+                item.codigo,
+                item.nome,
+                formatar_quantidade(quantidade),
+                formatar_quantidade(disponivel.max(0.0)),
+                formatar_quantidade(reserva)
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn recusar(motivo: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, motivo)
+}
+
+// 🔹 Arquivo de orçamentos aberto para gravação
+pub struct Orcamentos {
+    caminho: String,
+    arquivo: File,
+}
+
+impl Orcamentos {
+    // Como nas vendas, uma linha incompleta no fim é cortada
+    pub fn abrir(caminho: &str) -> io::Result<Orcamentos> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_dos_orcamentos(caminho))?;
+        let bytes = fs::read(caminho_dos_orcamentos(caminho))?;
+        let completas = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if bytes.len() > completas {
+            arquivo.set_len(completas as u64)?;
+        }
+        Ok(Orcamentos {
+            caminho: caminho.to_string(),
+            arquivo,
+        })
+    }
+
+    fn anexar(&mut self, orcamento: &Orcamento) -> io::Result<()> {
+        let mut linha = serde_json::to_vec(orcamento).map_err(io::Error::other)?;
+        linha.push(b'\n');
+        self.arquivo.write_all(&linha)?;
+        self.arquivo.sync_data()
+    }
+
+    // 🔹 Grava um orçamento novo com o próximo número, valendo por `dias`
+    pub fn criar(
+        &mut self,
+        operador: &str,
+        cliente: Option<String>,
+        carrinho: &Carrinho,
+        dias: u64,
+    ) -> io::Result<Orcamento> {
+        if carrinho.is_empty() {
+            return Err(recusar("nenhum item no orçamento".to_string()));
+        }
+        let quando = Local::now();
+        let validade = quando
+            .date_naive()
+            .checked_add_days(Days::new(dias))
+            .ok_or_else(|| recusar(format!("validade de {} dias inválida", dias)))?;
+
+        self.arquivo.lock()?;
+        let resultado = ler(&self.caminho).and_then(|orcamentos| {
+            let orcamento = Orcamento {
+                numero: orcamentos.last().map_or(0, |o| o.numero) + 1,
+                quando,
+                validade,
+                operador: operador.to_string(),
+                cliente,
+                itens: carrinho.itens().to_vec(),
+                total: carrinho.total(),
+                situacao: Situacao::Aberto,
+            };
+            self.anexar(&orcamento).map(|()| orcamento)
+        });
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    // Só um orçamento aberto e dentro da validade pode mudar de situação
+    fn conferir(&self, numero: u64, hoje: NaiveDate) -> io::Result<Orcamento> {
+        let Some(orcamento) = buscar(&self.caminho, numero)? else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("orçamento {} não existe", numero),
+            ));
+        };
+        if let Situacao::Vendido(venda) = orcamento.situacao {
+            return Err(recusar(format!(
+                "o orçamento {} já virou a venda {}",
+                numero, venda
+            )));
+        }
+        if orcamento.vencido(hoje) {
+            return Err(recusar(format!(
+                "o orçamento {} venceu em {}",
+                numero,
+                orcamento.validade.format("%d/%m/%Y")
+            )));
+        }
+        Ok(orcamento)
+    }
+
+    // 🔹 Reserva os itens no estoque até a validade. O arquivo fica travado da
+    // conferência até a gravação, para dois caixas não mexerem no mesmo
+    // orçamento ao mesmo tempo.
+    pub fn reservar(&mut self, numero: u64) -> io::Result<Orcamento> {
+        self.arquivo.lock()?;
+        let resultado = self.reservar_travado(numero);
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    fn reservar_travado(&mut self, numero: u64) -> io::Result<Orcamento> {
+        let mut orcamento = self.conferir(numero, Local::now().date_naive())?;
+        if orcamento.situacao == Situacao::Reservado {
+            return Err(recusar(format!("o orçamento {} já está reservado", numero)));
+        }
+        orcamento.situacao = Situacao::Reservado;
+        self.anexar(&orcamento)?;
+        Ok(orcamento)
+    }
+
+    // 🔹 Transforma o orçamento em venda, pelos preços dele, e o marca como
+    // vendido (a reserva, se havia, deixa de contar). Travado como a reserva:
+    // o mesmo orçamento não vira duas vendas.
+    pub fn vender(
+        &mut self,
+        numero: u64,
+        vendas: &mut Vendas,
+        estoque: &mut Estoque,
+        operador: &str,
+        pago: Reais,
+        pagamento: Pagamento,
+    ) -> io::Result<(Orcamento, Venda)> {
+        self.arquivo.lock()?;
+        let resultado = self.vender_travado(numero, vendas, estoque, operador, pago, pagamento);
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    fn vender_travado(
+        &mut self,
+        numero: u64,
+        vendas: &mut Vendas,
+        estoque: &mut Estoque,
+        operador: &str,
+        pago: Reais,
+        pagamento: Pagamento,
+    ) -> io::Result<(Orcamento, Venda)> {
+        let mut orcamento = self.conferir(numero, Local::now().date_naive())?;
+        let venda = vendas.registrar_do_orcamento(
+            estoque,
+            operador,
+            &orcamento.carrinho(),
+            pago,
+            pagamento,
+            numero,
+        )?;
+        orcamento.situacao = Situacao::Vendido(venda.numero);
+        self.anexar(&orcamento)?;
+        Ok((orcamento, venda))
+    }
+}
+
+// 🔹 Orçamento em texto simples, na largura do cupom
+pub fn texto(orcamento: &Orcamento) -> String {
+    let traco = "-".repeat(LARGURA_CUPOM);
+    let mut linhas = vec![
+        centralizado(LOJA),
+        centralizado(&format!("ORÇAMENTO {:06}", orcamento.numero)),
+        centralizado(&orcamento.quando.format("%d/%m/%Y %H:%M").to_string()),
+        centralizado(&format!("Vendedor: {}", orcamento.operador)),
+    ];
+    if let Some(cliente) = &orcamento.cliente {
+        linhas.push(centralizado(&format!("Cliente: {}", cliente)));
+    }
+    linhas.push(traco.clone());
+    linhas.push("Código Descrição".to_string());
+    linhas.push(linha_com_valor("    Qtd x Preço", "Total"));
+    linhas.push(traco.clone());
+
+    for item in &orcamento.itens {
+        linhas.push(
+            format!("{} {}", item.codigo, item.nome)
+                .chars()
+                .take(LARGURA_CUPOM)
+                .collect(),
+        );
+        linhas.push(linha_com_valor(
+            &format!(
+                "    {} x {}",
+                formatar_quantidade(item.quantidade),
+                item.preco
+            ),
+            &item.total().to_string(),
+        ));
+    }
+
+    linhas.push(traco.clone());
+    linhas.push(linha_com_valor(
+        &format!("TOTAL ({} itens)", orcamento.itens.len()),
+        &orcamento.total.com_simbolo(),
+    ));
+    linhas.push(traco);
+    linhas.push(centralizado(&format!(
+        "Válido até {}",
+        orcamento.validade.format("%d/%m/%Y")
+    )));
+    linhas.push(centralizado("Não é documento fiscal"));
+
+    let mut texto = linhas.join("\n");
+    texto.push('\n');
+    texto
+}
@@ -0,0 +1,195 @@
+// 🔹 PIX estático: o BR Code ("copia e cola") com a chave da loja e o valor da
+// venda, no formato EMV (campos ID + tamanho + valor) com CRC16 no fim, e o QR
+// code dele no terminal (com meios-blocos) ou em SVG. Tudo sem internet: o
+// banco só entra quando o cliente paga.
+
+use std::fs;
+use std::io;
+
+use qrcode::{Color, EcLevel, QrCode};
+use serde::Deserialize;
+
+use crate::dinheiro::Reais;
+use crate::texto::remover_acentos;
+
+pub const ARQUIVO_PIX: &str = "pix.json";
+
+// Limites do manual do BR Code
+const TAMANHO_CHAVE: usize = 77;
+const TAMANHO_NOME: usize = 25;
+const TAMANHO_CIDADE: usize = 15;
+const TAMANHO_TXID: usize = 25;
+
+// Módulos claros em volta do QR code, para o leitor achar as bordas
+const MARGEM: usize = 4;
+
+// 🔹 Recebedor ("pix.json"): a chave PIX da loja, o nome e a cidade
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recebedor {
+    pub chave: String,
+    pub nome: String,
+    pub cidade: String,
+}
+
+impl Recebedor {
+    // Arquivo inexistente = PIX não configurado (None)
+    pub fn abrir(caminho: &str) -> io::Result<Option<Recebedor>> {
+        let recebedor: Recebedor = match fs::read_to_string(caminho) {
+            Ok(texto) => serde_json::from_str(&texto)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        recebedor
+            .validar()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Some(recebedor))
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        let chave = self.chave.trim();
+        if chave.is_empty() || chave.chars().count() > TAMANHO_CHAVE {
+            return Err(format!(
+                "a chave PIX precisa ter de 1 a {} caracteres",
+                TAMANHO_CHAVE
+            ));
+        }
+        if texto_emv(&self.nome, TAMANHO_NOME).is_empty()
+            || texto_emv(&self.cidade, TAMANHO_CIDADE).is_empty()
+        {
+            return Err("o nome e a cidade do recebedor são obrigatórios".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Nome e cidade vão sem acento, em maiúsculas e cortados no limite do campo
+fn texto_emv(texto: &str, limite: usize) -> String {
+    remover_acentos(texto.trim())
+        .to_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .take(limite)
+        .collect()
+}
+
+// Um campo EMV: ID, tamanho com 2 dígitos e o valor
+fn campo(id: &str, valor: &str) -> String {
+    format!("{}{:02}{}", id, valor.len(), valor)
+}
+
+// 🔹 CRC16-CCITT (polinômio 0x1021, início 0xFFFF), como pede o BR Code
+pub fn crc16(dados: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in dados {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+// 🔹 O "copia e cola" do PIX estático para o valor. O txid identifica o
+// pagamento no extrato (só letras e números; sem ele, "***").
+pub fn br_code(recebedor: &Recebedor, valor: Reais, txid: Option<&str>) -> Result<String, String> {
+    recebedor.validar()?;
+    if valor <= Reais::ZERO {
+        return Err("o valor do PIX precisa ser maior que zero".to_string());
+    }
+    let txid = match txid {
+        Some(txid) => {
+            if txid.is_empty()
+                || txid.len() > TAMANHO_TXID
+                || !txid.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(format!(
+                    "o identificador do PIX precisa ter de 1 a {} letras ou números",
+                    TAMANHO_TXID
+                ));
+            }
+            txid.to_string()
+        }
+        None => "***".to_string(),
+    };
+
+    let conta = campo("00", "br.gov.bcb.pix") + &campo("01", recebedor.chave.trim());
+    let mut codigo = [
+        campo("00", "01"),
+        campo("26", &conta),
+        campo("52", "0000"),
+        campo("53", "986"),
+        campo(
+            "54",
+            &format!("{}.{:02}", valor.centavos() / 100, valor.centavos() % 100),
+        ),
+        campo("58", "BR"),
+        campo("59", &texto_emv(&recebedor.nome, TAMANHO_NOME)),
+        campo("60", &texto_emv(&recebedor.cidade, TAMANHO_CIDADE)),
+        campo("62", &campo("05", &txid)),
+    ]
+    .concat();
+    // O CRC cobre tudo, inclusive o "6304" do próprio campo
+    codigo.push_str("6304");
+    codigo.push_str(&format!("{:04X}", crc16(codigo.as_bytes())));
+    Ok(codigo)
+}
+
+// Módulos do QR code (true = escuro), com a margem, e a largura
+fn modulos(texto: &str) -> Result<(Vec<bool>, usize), String> {
+    let qr = QrCode::with_error_correction_level(texto, EcLevel::M)
+        .map_err(|e| format!("não foi possível montar o QR code: {}", e))?;
+    let largura = qr.width();
+    let total = largura + 2 * MARGEM;
+    let mut modulos = vec![false; total * total];
+    for (i, cor) in qr.to_colors().into_iter().enumerate() {
+        let (x, y) = (i % largura + MARGEM, i / largura + MARGEM);
+        modulos[y * total + x] = cor == Color::Dark;
+    }
+    Ok((modulos, total))
+}
+
+// 🔹 QR code para o terminal: cada caractere tem dois módulos, um em cima do
+// outro (▀ ▄ █). As cores são fixas (preto no branco), para funcionar também
+// em terminal de fundo escuro.
+pub fn qr_terminal(texto: &str) -> Result<String, String> {
+    let (modulos, total) = modulos(texto)?;
+    let escuro = |x: usize, y: usize| y < total && modulos[y * total + x];
+    let mut saida = String::new();
+    for y in (0..total).step_by(2) {
+        saida.push_str("\x1b[30;107m");
+        for x in 0..total {
+            saida.push(match (escuro(x, y), escuro(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        saida.push_str("\x1b[0m\n");
+    }
+    Ok(saida)
+}
+
+// 🔹 QR code em SVG, um quadrado por módulo escuro
+pub fn qr_svg(texto: &str) -> Result<String, String> {
+    let (modulos, total) = modulos(texto)?;
+    let mut saida = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}mm\" height=\"{0}mm\" viewBox=\"0 0 {1} {1}\" shape-rendering=\"crispEdges\">\n<rect width=\"{1}\" height=\"{1}\" fill=\"#fff\"/>\n",
+        total / 2,
+        total
+    );
+    for i in (0..modulos.len()).filter(|&i| modulos[i]) {
+        saida.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\"/>",
+            i % total,
+            i / total
+        ));
+    }
+    saida.push_str("\n</svg>\n");
+    Ok(saida)
+}
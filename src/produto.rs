@@ -0,0 +1,218 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::cadastro::{self, Complemento};
+use crate::codificacao::{self, Codificacao, Conversao};
+use crate::formato::Formato;
+use crate::json;
+use crate::sqlite;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Registro")]
+pub struct Produto {
+    pub codigo: String,
+    pub nome: String,
+    // Preço, custo, código de barras etc. (ver `cadastro`); no JSON, campos do
+    // próprio registro
+    #[serde(flatten)]
+    pub complemento: Complemento,
+    // Campos que outros sistemas anexam ao produto no JSON; não são usados
+    // aqui, mas voltam para o arquivo quando ele é regravado em JSON
+    #[serde(flatten)]
+    pub extras: Map<String, Value>,
+}
+
+// Produto como vem do JSON. Os dois `flatten` recebem todos os campos que
+// sobram, então os do complemento precisam sair dos extras (senão seriam
+// gravados duas vezes)
+#[derive(Deserialize)]
+struct Registro {
+    codigo: String,
+    nome: String,
+    #[serde(flatten)]
+    complemento: Complemento,
+    #[serde(flatten)]
+    extras: Map<String, Value>,
+}
+
+impl From<Registro> for Produto {
+    fn from(registro: Registro) -> Produto {
+        let mut extras = registro.extras;
+        for campo in Complemento::CAMPOS {
+            extras.remove(*campo);
+        }
+        Produto {
+            codigo: registro.codigo,
+            nome: registro.nome,
+            complemento: registro.complemento,
+            extras,
+        }
+    }
+}
+
+impl Produto {
+    pub fn new(codigo: &str, nome: &str) -> Self {
+        Produto {
+            codigo: codigo.to_string(),
+            nome: nome.to_string(),
+            complemento: Complemento::default(),
+            extras: Map::new(),
+        }
+    }
+
+    // 🔹 Interpreta uma linha "codigo;nome" (o nome pode conter ';')
+    pub fn de_linha(linha: &str) -> Result<Produto, String> {
+        let Some((codigo, nome)) = linha.split_once(';') else {
+            return Err("linha sem ';' separando código e nome".to_string());
+        };
+        let (codigo, nome) = (codigo.trim(), nome.trim());
+        validar(codigo, nome)?;
+        Ok(Produto::new(codigo, nome))
+    }
+}
+
+// 🔹 Regras de um produto válido no catálogo (valem para todo formato de entrada)
+pub fn validar(codigo: &str, nome: &str) -> Result<(), String> {
+    if codigo.is_empty() {
+        return Err("código vazio".to_string());
+    }
+    if codigo.contains(|c: char| c.is_whitespace() || c == ';') {
+        return Err(format!("código '{}' com espaço ou ';'", codigo));
+    }
+    if nome.is_empty() {
+        return Err(format!("produto {} sem nome", codigo));
+    }
+    if nome.contains(['\n', '\r']) {
+        return Err(format!("nome do produto {} com quebra de linha", codigo));
+    }
+    Ok(())
+}
+
+// Linha do arquivo que não virou produto
+#[derive(Debug, Clone)]
+pub struct LinhaInvalida {
+    pub numero: usize,
+    pub conteudo: String,
+    pub motivo: String,
+}
+
+// 🔹 Resultado da leitura de um catálogo: produtos válidos e linhas rejeitadas
+#[derive(Debug, Default)]
+pub struct Leitura {
+    pub produtos: Vec<Produto>,
+    pub invalidas: Vec<LinhaInvalida>,
+    pub conversao: Conversao,
+}
+
+// 🔹 Lê o catálogo guardando o motivo de cada linha rejeitada. O formato
+// ("codigo;nome", JSON ou JSON Lines) vem da extensão ou do conteúdo, e o
+// texto é convertido para UTF-8 antes (ver `codificacao::decodificar`). Os
+// dados complementares guardados fora do catálogo entram nos produtos.
+pub fn ler_produtos(caminho: &str, codificacao: Option<Codificacao>) -> io::Result<Leitura> {
+    let mut leitura = ler_arquivo(caminho, codificacao)?;
+    cadastro::completar(caminho, &mut leitura.produtos)?;
+    Ok(leitura)
+}
+
+fn ler_arquivo(caminho: &str, codificacao: Option<Codificacao>) -> io::Result<Leitura> {
+    if Formato::de_caminho(caminho) == Some(Formato::Sqlite) {
+        return ler_sqlite(caminho);
+    }
+
+    let bytes = fs::read(caminho)?;
+    let formato = Formato::detectar(caminho, &bytes);
+    if formato == Formato::Sqlite {
+        return ler_sqlite(caminho);
+    }
+
+    let (texto, conversao) = codificacao::decodificar(&bytes, codificacao);
+    let mut leitura = match formato {
+        Formato::Sqlite | Formato::Texto => ler_texto(&texto),
+        Formato::Json => json::ler_json(&texto)
+            .map_err(|motivo| io::Error::new(io::ErrorKind::InvalidData, motivo))?,
+        Formato::JsonLinhas => json::ler_jsonl(&texto),
+    };
+    leitura.conversao = conversao;
+    Ok(leitura)
+}
+
+// O banco só guarda produtos já validados
+fn ler_sqlite(caminho: &str) -> io::Result<Leitura> {
+    Ok(Leitura {
+        produtos: sqlite::ler_produtos(caminho)?,
+        ..Leitura::default()
+    })
+}
+
+// Catálogo no formato "codigo;nome", uma linha por produto
+fn ler_texto(texto: &str) -> Leitura {
+    let mut leitura = Leitura::default();
+
+    for (i, linha) in texto.lines().enumerate() {
+        if linha.trim().is_empty() {
+            continue;
+        }
+        match Produto::de_linha(linha) {
+            Ok(produto) => leitura.produtos.push(produto),
+            Err(motivo) => leitura.invalidas.push(LinhaInvalida {
+                numero: i + 1,
+                conteudo: linha.trim_end().to_string(),
+                motivo,
+            }),
+        }
+    }
+
+    leitura
+}
+
+// 🔹 Carrega produtos do arquivo produtos.txt (formato: "codigo;nome")
+pub fn carregar_produtos(caminho: &str) -> Vec<Produto> {
+    match ler_produtos(caminho, None) {
+        Ok(leitura) => {
+            leitura.conversao.relatar(caminho);
+            leitura.produtos
+        }
+        Err(_) => {
+            println!("⚠️ Não foi possível abrir '{}'", caminho);
+            Vec::new()
+        }
+    }
+}
+
+// 🔹 Grava o catálogo no formato indicado pela extensão (".json", ".jsonl", ".db"
+// ou, para qualquer outra, "codigo;nome"). Escreve num arquivo temporário e troca
+// pelo definitivo, para nunca deixar um catálogo pela metade (o banco SQLite
+// troca os produtos numa transação). Os dados complementares vão no registro
+// JSON, nas colunas do banco ou, no "codigo;nome", para o arquivo do cadastro.
+pub fn salvar_produtos(caminho: &str, produtos: &[Produto]) -> io::Result<()> {
+    let formato = Formato::de_caminho(caminho).unwrap_or_default();
+    if formato == Formato::Sqlite {
+        sqlite::salvar_produtos(caminho, produtos)?;
+        return cadastro::gravar_complementos(caminho, &[]);
+    }
+
+    let temporario = format!("{}.tmp", caminho);
+    let mut saida = BufWriter::new(File::create(&temporario)?);
+
+    match formato {
+        Formato::Texto | Formato::Sqlite => {
+            for p in produtos {
+                writeln!(saida, "{};{}", p.codigo, p.nome)?;
+            }
+        }
+        Formato::Json => json::escrever_json(&mut saida, produtos)?,
+        Formato::JsonLinhas => json::escrever_jsonl(&mut saida, produtos)?,
+    }
+
+    saida.into_inner()?.sync_all()?;
+    fs::rename(&temporario, caminho)?;
+
+    let fora_do_catalogo = match formato {
+        Formato::Texto | Formato::Sqlite => produtos,
+        Formato::Json | Formato::JsonLinhas => &[],
+    };
+    cadastro::gravar_complementos(caminho, fora_do_catalogo)
+}
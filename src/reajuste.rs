@@ -0,0 +1,148 @@
+// 🔹 Reajuste de preços em lote: uma variação (percentual ou em reais) aplicada
+// aos produtos escolhidos, com arredondamento opcional para os finais
+// comerciais (x,90 ou x,99). A prévia mostra o antes e o depois de cada
+// produto, e é exatamente ela que vai para o catálogo.
+
+use crate::catalogo::{Catalogo, IdProduto};
+use crate::dinheiro::Reais;
+use crate::produto::Produto;
+
+// 🔹 Quanto o preço muda
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variacao {
+    // "+10%", "-5%"
+    Percentual(f64),
+    // "+1,50", "-0,30"
+    Valor(Reais),
+}
+
+impl Variacao {
+    // "10%" ou "+10%" sobem 10%; "1,50" sobe R$ 1,50; com "-", descem
+    pub fn de_texto(texto: &str) -> Option<Variacao> {
+        let texto = texto.trim();
+        match texto.strip_suffix('%') {
+            Some(numero) => numero
+                .trim()
+                .trim_start_matches('+')
+                .replace(',', ".")
+                .parse::<f64>()
+                .ok()
+                .filter(|p| p.is_finite() && *p > -100.0)
+                .map(Variacao::Percentual),
+            None => Reais::de_texto(texto.trim_start_matches('+')).map(Variacao::Valor),
+        }
+    }
+
+    pub fn descricao(&self) -> String {
+        match self {
+            Variacao::Percentual(p) => format!("{:+}%", p).replace('.', ","),
+            Variacao::Valor(v) if *v < Reais::ZERO => format!("-R$ {}", Reais::ZERO - *v),
+            Variacao::Valor(v) => format!("+R$ {}", v),
+        }
+    }
+}
+
+// 🔹 Como os centavos ficam depois do reajuste
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Arredondamento {
+    // Como a conta der, ao centavo
+    #[default]
+    Exato,
+    // x,90
+    Noventa,
+    // x,99
+    NoventaENove,
+}
+
+impl Arredondamento {
+    pub fn de_nome(nome: &str) -> Option<Arredondamento> {
+        match nome.trim().trim_start_matches(',') {
+            "90" => Some(Arredondamento::Noventa),
+            "99" => Some(Arredondamento::NoventaENove),
+            "exato" | "nenhum" => Some(Arredondamento::Exato),
+            _ => None,
+        }
+    }
+
+    // Sobe até o primeiro preço com o final (nunca desce, para um reajuste do
+    // fornecedor não virar prejuízo por causa do arredondamento)
+    pub fn arredondar(&self, preco: Reais) -> Reais {
+        let centavos_do_final = match self {
+            Arredondamento::Exato => return preco,
+            Arredondamento::Noventa => 90,
+            Arredondamento::NoventaENove => 99,
+        };
+        let c = preco.centavos();
+        let candidato = c - c.rem_euclid(100) + centavos_do_final;
+        Reais::de_centavos(if candidato < c {
+            candidato + 100
+        } else {
+            candidato
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reajuste {
+    pub variacao: Variacao,
+    pub arredondamento: Arredondamento,
+}
+
+impl Reajuste {
+    pub fn aplicar(&self, preco: Reais) -> Reais {
+        let novo = match self.variacao {
+            Variacao::Percentual(p) => preco.vezes(1.0 + p / 100.0),
+            Variacao::Valor(v) => preco + v,
+        };
+        self.arredondamento.arredondar(novo)
+    }
+}
+
+// 🔹 Uma linha da prévia
+#[derive(Debug, Clone, PartialEq)]
+pub struct NovoPreco {
+    pub codigo: String,
+    pub nome: String,
+    pub antes: Reais,
+    pub depois: Reais,
+}
+
+impl NovoPreco {
+    // Variação em %, para a prévia
+    pub fn percentual(&self) -> f64 {
+        if self.antes == Reais::ZERO {
+            return 0.0;
+        }
+        (self.depois.centavos() - self.antes.centavos()) as f64 * 100.0
+            / self.antes.centavos() as f64
+    }
+}
+
+// 🔹 Prévia do reajuste: o novo preço de cada produto escolhido que tem preço
+// cadastrado (os sem preço ficam de fora; ninguém sabe de onde partir)
+pub fn previa(catalogo: &Catalogo, ids: &[IdProduto], reajuste: &Reajuste) -> Vec<NovoPreco> {
+    ids.iter()
+        .filter_map(|&id| {
+            let antes = catalogo.preco(id)?;
+            Some(NovoPreco {
+                codigo: catalogo.codigo(id).to_string(),
+                nome: catalogo.nome(id).to_string(),
+                antes,
+                depois: reajuste.aplicar(antes),
+            })
+        })
+        .collect()
+}
+
+// 🔹 Os produtos com o preço novo, para gravar no catálogo
+pub fn aplicar(catalogo: &Catalogo, novos: &[NovoPreco]) -> Vec<Produto> {
+    novos
+        .iter()
+        .filter_map(|novo| {
+            let id = catalogo.buscar_por_codigo(&novo.codigo)?;
+            let mut produto = catalogo.produto(id);
+            produto.complemento.preco = Some(novo.depois);
+            Some(produto)
+        })
+        .collect()
+}
@@ -0,0 +1,551 @@
+use std::fs;
+use std::io;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use crate::auditoria::{self, Auditoria, Evento};
+use crate::cadastro;
+use crate::catalogo::Catalogo;
+use crate::codificacao::Codificacao;
+use crate::diario::{self, Alteracao, Diario, Recuperacao};
+use crate::diferencas::{self, Diferencas};
+use crate::formato::Formato;
+use crate::historico::{Historico, Mudanca, Operacao};
+use crate::indice::Assinatura;
+use crate::produto::{self, Produto};
+use crate::sqlite;
+
+// 🔹 Catálogo que acompanha as edições do arquivo enquanto o programa roda.
+// Quem está usando o catálogo segura um `Arc` próprio: a troca por uma versão
+// nova não interrompe uma listagem em andamento.
+//
+// As edições vão para o diário ("<caminho>.log") e só chegam ao arquivo do
+// catálogo na compactação; cada uma fica no histórico ("<caminho>.hist") para
+// poder ser desfeita.
+pub struct CatalogoVivo {
+    caminho: String,
+    codificacao: Option<Codificacao>,
+    atual: RwLock<Arc<Catalogo>>,
+    versao: Mutex<Versao>,
+    diario: Mutex<Diario>,
+    recuperacao: Recuperacao,
+    historico: Mutex<Historico>,
+    auditoria: Mutex<Auditoria>,
+    operador: RwLock<String>,
+}
+
+impl CatalogoVivo {
+    // `codificacao` vale para a primeira leitura e para as recargas
+    pub fn abrir(caminho: &str, codificacao: Option<Codificacao>) -> io::Result<CatalogoVivo> {
+        // O diário é aberto antes, para um fim corrompido já ter sido cortado
+        // quando o catálogo reaplicar as alterações
+        let (diario, recuperacao) = Diario::abrir(caminho)?;
+        let historico = Historico::abrir(caminho)?;
+        let auditoria = Auditoria::abrir(caminho)?;
+        let do_diario = assinatura_do_diario(caminho);
+        let (catalogo, assinatura) = Catalogo::abrir_sem_bloquear(caminho, codificacao)?;
+        Ok(CatalogoVivo {
+            caminho: caminho.to_string(),
+            codificacao,
+            atual: RwLock::new(Arc::new(catalogo)),
+            versao: Mutex::new(Versao {
+                catalogo: assinatura,
+                diario: do_diario,
+            }),
+            diario: Mutex::new(diario),
+            recuperacao,
+            historico: Mutex::new(historico),
+            auditoria: Mutex::new(auditoria),
+            operador: RwLock::new(auditoria::operador_do_sistema()),
+        })
+    }
+
+    // 🔹 Quem aparece na trilha de auditoria (o usuário do sistema, por padrão)
+    pub fn definir_operador(&self, operador: &str) {
+        *self.operador.write().unwrap() = operador.to_string();
+    }
+
+    pub fn operador(&self) -> String {
+        self.operador.read().unwrap().clone()
+    }
+
+    // 🔹 Registra na trilha de auditoria uma operação que não altera o catálogo
+    // (busca por código, exportação, movimento de estoque)
+    pub fn registrar_evento(&self, operacao: &str, descricao: String) -> io::Result<()> {
+        self.auditar(vec![Evento {
+            operacao: operacao.to_string(),
+            descricao,
+            ..Evento::default()
+        }])
+    }
+
+    fn auditar(&self, eventos: Vec<Evento>) -> io::Result<()> {
+        self.auditoria
+            .lock()
+            .unwrap()
+            .registrar(&self.operador(), eventos)
+    }
+
+    // O que foi reaplicado (ou descartado) do diário ao abrir
+    pub fn recuperacao(&self) -> &Recuperacao {
+        &self.recuperacao
+    }
+
+    pub fn caminho(&self) -> &str {
+        &self.caminho
+    }
+
+    // Versão do catálogo em uso neste momento
+    pub fn atual(&self) -> Arc<Catalogo> {
+        Arc::clone(&self.atual.read().unwrap())
+    }
+
+    fn versao_em_disco(&self) -> Option<Versao> {
+        let meta = fs::metadata(&self.caminho).ok()?;
+        Some(Versao {
+            catalogo: Assinatura::de_metadados(&meta),
+            diario: assinatura_do_diario(&self.caminho),
+        })
+    }
+
+    fn mudou(&self, versao: &Versao) -> bool {
+        !self.versao.lock().unwrap().mesma(versao)
+    }
+
+    // 🔹 Relê o arquivo e o diário e troca o catálogo em uso. Um arquivo sem
+    // nenhum produto (por exemplo, no meio de um salvamento) é ignorado.
+    pub fn recarregar(&self) -> io::Result<Option<Diferencas>> {
+        // Com o diário travado, uma edição deste programa não se perde na troca
+        let _diario = self.diario.lock().unwrap();
+        // Lida antes: o que outro programa acrescentar durante a leitura
+        // aparece como mudança na próxima verificação
+        let do_diario = assinatura_do_diario(&self.caminho);
+        let (novo, assinatura) = Catalogo::abrir_sem_bloquear(&self.caminho, self.codificacao)?;
+        *self.versao.lock().unwrap() = Versao {
+            catalogo: assinatura,
+            diario: do_diario,
+        };
+
+        if novo.is_empty() {
+            return Ok(None);
+        }
+
+        let antigo = self.atual();
+        let diferencas = diferencas::comparar(&antigo, &novo);
+        *self.atual.write().unwrap() = Arc::new(novo);
+
+        Ok(Some(diferencas))
+    }
+
+    // 🔹 Inclui o produto ou troca o nome do que tem o mesmo código (sem
+    // dados complementares, o produto fica com os que já tinha)
+    pub fn gravar(&self, produto: Produto) -> io::Result<()> {
+        produto::validar(&produto.codigo, &produto.nome)
+            .map_err(|motivo| io::Error::new(io::ErrorKind::InvalidInput, motivo))?;
+
+        let mut historico = self.historico.lock().unwrap();
+        let antes = self.produto_atual(&produto.codigo);
+        let produto = completar(produto, antes.as_ref());
+        let descricao = match &antes {
+            Some(antes) => format!(
+                "[{}] renomeado de {} para {}",
+                produto.codigo, antes.nome, produto.nome
+            ),
+            None => format!("[{}] {} incluído", produto.codigo, produto.nome),
+        };
+        self.executar(
+            &mut historico,
+            Operacao {
+                descricao,
+                mudancas: vec![Mudanca {
+                    codigo: produto.codigo.clone(),
+                    antes,
+                    depois: Some(produto),
+                }],
+            },
+        )
+    }
+
+    // 🔹 Remove o produto; falso se não havia nenhum com o código
+    pub fn remover(&self, codigo: &str) -> io::Result<bool> {
+        let mut historico = self.historico.lock().unwrap();
+        let Some(antes) = self.produto_atual(codigo) else {
+            return Ok(false);
+        };
+        let descricao = format!("[{}] {} removido", codigo, antes.nome);
+        self.executar(
+            &mut historico,
+            Operacao {
+                descricao,
+                mudancas: vec![Mudanca {
+                    codigo: codigo.to_string(),
+                    antes: Some(antes),
+                    depois: None,
+                }],
+            },
+        )?;
+        Ok(true)
+    }
+
+    // 🔹 Grava vários produtos numa única operação (desfeita de uma vez só).
+    // Produtos iguais aos do catálogo ficam de fora; devolve quantos mudaram.
+    // Como em `gravar`, quem vem sem dados complementares fica com os que tinha.
+    pub fn gravar_lote(&self, descricao: &str, produtos: Vec<Produto>) -> io::Result<usize> {
+        self.alterar_lote(descricao, produtos, true)
+    }
+
+    // 🔹 Como `gravar_lote`, mas os dados complementares são exatamente os dos
+    // produtos recebidos (para mudar preço, categoria etc. de produtos que já
+    // estão no catálogo, inclusive apagando o que havia)
+    pub fn substituir(&self, descricao: &str, produtos: Vec<Produto>) -> io::Result<usize> {
+        self.alterar_lote(descricao, produtos, false)
+    }
+
+    fn alterar_lote(
+        &self,
+        descricao: &str,
+        produtos: Vec<Produto>,
+        manter_complemento: bool,
+    ) -> io::Result<usize> {
+        for p in &produtos {
+            produto::validar(&p.codigo, &p.nome)
+                .map_err(|motivo| io::Error::new(io::ErrorKind::InvalidInput, motivo))?;
+        }
+
+        let mut historico = self.historico.lock().unwrap();
+        let mudancas: Vec<Mudanca> = produtos
+            .into_iter()
+            .filter_map(|p| {
+                let antes = self.produto_atual(&p.codigo);
+                let p = if manter_complemento {
+                    completar(p, antes.as_ref())
+                } else {
+                    p
+                };
+                let igual = antes
+                    .as_ref()
+                    .is_some_and(|a| a.nome == p.nome && a.complemento == p.complemento);
+                (!igual).then(|| Mudanca {
+                    codigo: p.codigo.clone(),
+                    antes,
+                    depois: Some(p),
+                })
+            })
+            .collect();
+        let total = mudancas.len();
+        if total > 0 {
+            let descricao = match total {
+                1 => descricao.to_string(),
+                _ => format!("{} ({} produtos)", descricao, total),
+            };
+            self.executar(
+                &mut historico,
+                Operacao {
+                    descricao,
+                    mudancas,
+                },
+            )?;
+        }
+        Ok(total)
+    }
+
+    // 🔹 Volta a última operação; None se não há o que desfazer. O histórico
+    // fica travado do começo ao fim, e a operação é a última do arquivo (um
+    // desfazer de outro terminal já conta).
+    pub fn desfazer(&self) -> io::Result<Option<Operacao>> {
+        let mut historico = self.historico.lock().unwrap();
+        historico.travar()?;
+        let resultado = self.desfazer_travado(&mut historico);
+        historico.liberar();
+        let operacao = resultado?;
+        self.compactar_se_cheio()?;
+        Ok(operacao)
+    }
+
+    fn desfazer_travado(&self, historico: &mut Historico) -> io::Result<Option<Operacao>> {
+        let Some(operacao) = historico.a_desfazer() else {
+            return Ok(None);
+        };
+        self.conferir_produtos(operacao, true)?;
+        self.auditar(eventos_da_operacao(operacao, "desfazer"))?;
+        let descricao = format!("desfazer {}", operacao.descricao);
+        self.depois_de_auditar(&descricao, || {
+            let Some(operacao) = historico.desfazer(&self.operador())? else {
+                return Ok(None);
+            };
+            self.alterar(&operacao.para_desfazer())?;
+            Ok(Some(operacao))
+        })
+    }
+
+    // 🔹 Aplica de novo a última operação desfeita, travado como `desfazer`
+    pub fn refazer(&self) -> io::Result<Option<Operacao>> {
+        let mut historico = self.historico.lock().unwrap();
+        historico.travar()?;
+        let resultado = self.refazer_travado(&mut historico);
+        historico.liberar();
+        let operacao = resultado?;
+        self.compactar_se_cheio()?;
+        Ok(operacao)
+    }
+
+    fn refazer_travado(&self, historico: &mut Historico) -> io::Result<Option<Operacao>> {
+        let Some(operacao) = historico.a_refazer() else {
+            return Ok(None);
+        };
+        self.conferir_produtos(operacao, false)?;
+        self.auditar(eventos_da_operacao(operacao, "refazer"))?;
+        let descricao = format!("refazer {}", operacao.descricao);
+        self.depois_de_auditar(&descricao, || {
+            let Some(operacao) = historico.refazer(&self.operador())? else {
+                return Ok(None);
+            };
+            self.alterar(&operacao.para_refazer())?;
+            Ok(Some(operacao))
+        })
+    }
+
+    // Desfazer só volta o produto se ele ainda está como a operação deixou
+    // (refazer, se ainda está como estava antes dela); senão, uma edição mais
+    // nova, deste programa ou de outro, seria apagada sem ninguém ver. O
+    // catálogo é relido antes se outro programa mexeu no arquivo ou no diário.
+    fn conferir_produtos(&self, operacao: &Operacao, desfazendo: bool) -> io::Result<()> {
+        if self.versao_em_disco().is_some_and(|v| self.mudou(&v)) {
+            self.recarregar()?;
+        }
+        for mudanca in &operacao.mudancas {
+            let esperado = if desfazendo {
+                &mudanca.depois
+            } else {
+                &mudanca.antes
+            };
+            let atual = self.produto_atual(&mudanca.codigo);
+            let igual = match (&atual, esperado) {
+                (None, None) => true,
+                (Some(a), Some(e)) => a.nome == e.nome && a.complemento == e.complemento,
+                _ => false,
+            };
+            if !igual {
+                let agora = atual.map_or("removido".to_string(), |p| p.nome);
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "o produto [{}] mudou depois de \"{}\" (agora: {}); {} recusado",
+                        mudanca.codigo,
+                        operacao.descricao,
+                        agora,
+                        if desfazendo { "desfazer" } else { "refazer" }
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn produto_atual(&self, codigo: &str) -> Option<Produto> {
+        let catalogo = self.atual();
+        catalogo
+            .buscar_por_codigo(codigo)
+            .map(|id| catalogo.produto(id))
+    }
+
+    // A auditoria vem primeiro: sem ela, a edição é recusada. O histórico é
+    // gravado antes do diário: se o programa cair entre os dois, desfazer a
+    // operação só regrava o que o catálogo já tinha.
+    fn executar(&self, historico: &mut Historico, operacao: Operacao) -> io::Result<()> {
+        self.auditar(eventos_da_operacao(&operacao, ""))?;
+        self.depois_de_auditar(&operacao.descricao, || {
+            historico.fazer(operacao.clone(), &self.operador())?;
+            self.alterar(&operacao.para_refazer())
+        })?;
+        self.compactar_se_cheio()
+    }
+
+    // A trilha já diz que a operação foi feita: se o histórico ou o diário
+    // falharem, uma entrada "falha" registra que ela não chegou ao catálogo
+    fn depois_de_auditar<T>(
+        &self,
+        descricao: &str,
+        gravar: impl FnOnce() -> io::Result<T>,
+    ) -> io::Result<T> {
+        let resultado = gravar();
+        if let Err(e) = &resultado {
+            let _ = self.auditar(vec![Evento {
+                operacao: "falha".to_string(),
+                descricao: format!("não gravado: {} ({})", descricao, e),
+                ..Evento::default()
+            }]);
+        }
+        resultado
+    }
+
+    // Grava no diário e só então troca o catálogo em uso. O diário fica travado
+    // durante a troca para que duas edições não partam da mesma versão.
+    fn alterar(&self, alteracoes: &[Alteracao]) -> io::Result<()> {
+        let mut diario = self.diario.lock().unwrap();
+        // Se outro programa já tinha mexido no diário, a assinatura antiga fica
+        // para que a verificação recarregue as edições dele
+        let de_fora = !self
+            .versao
+            .lock()
+            .unwrap()
+            .diario
+            .mesma_versao(&assinatura_do_diario(&self.caminho));
+        for alteracao in alteracoes {
+            diario.registrar(alteracao)?;
+        }
+        if !de_fora {
+            self.versao.lock().unwrap().diario = assinatura_do_diario(&self.caminho);
+        }
+
+        let novo = self.atual().com_alteracoes(alteracoes);
+        *self.atual.write().unwrap() = Arc::new(novo);
+        Ok(())
+    }
+
+    // Depois da edição gravada: uma falha aqui não desfaz a edição, que já
+    // está no diário
+    fn compactar_se_cheio(&self) -> io::Result<()> {
+        let mut diario = self.diario.lock().unwrap();
+        if diario.len() >= diario::LIMITE_COMPACTACAO {
+            self.compactar_com(&mut diario)?;
+        }
+        Ok(())
+    }
+
+    // 🔹 Regrava o catálogo com as alterações do diário e esvazia o diário;
+    // devolve quantas eram (inclusive as gravadas por outro programa)
+    pub fn compactar(&self) -> io::Result<usize> {
+        let mut diario = self.diario.lock().unwrap();
+        self.compactar_com(&mut diario)
+    }
+
+    // Parte do arquivo (e não do catálogo em memória) para manter os campos
+    // extras do JSON. Uma queda depois de gravar e antes de limpar o diário só
+    // faz as alterações serem reaplicadas sem efeito na próxima abertura.
+    fn compactar_com(&self, diario: &mut Diario) -> io::Result<usize> {
+        let total = diario.compactar(|alteracoes| {
+            // O banco recebe só as alterações; o arquivo é regravado inteiro. Um
+            // banco com dados ainda no arquivo do cadastro (de uma versão
+            // anterior) é regravado uma vez, para eles passarem para as colunas.
+            let banco = Formato::de_caminho(&self.caminho) == Some(Formato::Sqlite)
+                && cadastro::ler_complementos(&self.caminho)?.is_empty();
+            if banco {
+                return sqlite::aplicar_alteracoes(&self.caminho, alteracoes);
+            }
+            let mut produtos = produto::ler_produtos(&self.caminho, self.codificacao)?.produtos;
+            for alteracao in alteracoes {
+                alteracao.aplicar(&mut produtos);
+            }
+            produto::salvar_produtos(&self.caminho, &produtos)
+        })?;
+
+        // A gravação é nossa: não deve parecer uma edição de fora do programa
+        if let Some(versao) = self.versao_em_disco() {
+            *self.versao.lock().unwrap() = versao;
+        }
+        Ok(total)
+    }
+
+    // 🔹 Verifica o arquivo e o diário a cada `intervalo` numa thread separada
+    // (o `megastore gravar` de outro terminal só acrescenta ao diário). A
+    // recarga só acontece quando os dois ficam uma verificação inteira sem
+    // mudar, para não ler um salvamento pela metade.
+    pub fn vigiar<F>(self: &Arc<Self>, intervalo: Duration, ao_recarregar: F)
+    where
+        F: Fn(&Catalogo, &Diferencas) + Send + 'static,
+    {
+        let vivo = Arc::clone(self);
+
+        thread::spawn(move || {
+            let mut candidata: Option<Versao> = None;
+
+            loop {
+                thread::sleep(intervalo);
+
+                let Some(versao) = vivo.versao_em_disco() else {
+                    continue;
+                };
+                if !vivo.mudou(&versao) {
+                    candidata = None;
+                    continue;
+                }
+                if !candidata.is_some_and(|c| c.mesma(&versao)) {
+                    candidata = Some(versao);
+                    continue;
+                }
+
+                candidata = None;
+                if let Ok(Some(diferencas)) = vivo.recarregar()
+                    && !diferencas.is_empty()
+                {
+                    ao_recarregar(&vivo.atual(), &diferencas);
+                }
+            }
+        });
+    }
+}
+
+// Produto sem dados complementares fica com os do que ele substitui
+fn completar(mut produto: Produto, antes: Option<&Produto>) -> Produto {
+    if let Some(antes) = antes
+        && produto.complemento.is_empty()
+    {
+        produto.complemento = antes.complemento.clone();
+    }
+    produto
+}
+
+// Catálogo e diário como estavam na última leitura; sem diário, a assinatura
+// fica a padrão
+#[derive(Debug, Clone, Copy)]
+struct Versao {
+    catalogo: Assinatura,
+    diario: Assinatura,
+}
+
+impl Versao {
+    fn mesma(&self, outra: &Versao) -> bool {
+        self.catalogo.mesma_versao(&outra.catalogo) && self.diario.mesma_versao(&outra.diario)
+    }
+}
+
+fn assinatura_do_diario(caminho: &str) -> Assinatura {
+    fs::metadata(diario::caminho_do_diario(caminho))
+        .map(|meta| Assinatura::de_metadados(&meta))
+        .unwrap_or_default()
+}
+
+// Uma entrada de auditoria por produto mexido. Com `acao` "desfazer", antes e
+// depois trocam de lugar (o produto volta ao que era).
+fn eventos_da_operacao(operacao: &Operacao, acao: &str) -> Vec<Evento> {
+    operacao
+        .mudancas
+        .iter()
+        .map(|m| {
+            let (antes, depois) = if acao == "desfazer" {
+                (m.depois.clone(), m.antes.clone())
+            } else {
+                (m.antes.clone(), m.depois.clone())
+            };
+            // Com o mesmo nome, mudaram só os dados complementares
+            let operacao_produto = match (&m.antes, &m.depois) {
+                (None, _) => "incluir",
+                (_, None) => "remover",
+                (Some(antes), Some(depois)) if antes.nome == depois.nome => "alterar",
+                _ => "renomear",
+            };
+            Evento {
+                operacao: if acao.is_empty() {
+                    operacao_produto.to_string()
+                } else {
+                    format!("{} {}", acao, operacao_produto)
+                },
+                descricao: operacao.descricao.clone(),
+                antes,
+                depois,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,115 @@
+// 🔹 Produtos abaixo do estoque mínimo e quanto comprar de cada um.
+//
+// A compra sugerida leva o saldo até o mínimo mais o consumo previsto para o
+// mesmo número de dias usado na média (o que saiu nos últimos dias deve sair de
+// novo), sem passar do máximo quando ele está definido.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::cadastro::Cadastro;
+use crate::catalogo::Catalogo;
+use crate::estoque::formatar_quantidade;
+
+// Dias de consumo considerados quando não se informa outro período
+pub const DIAS_DE_CONSUMO: u32 = 30;
+
+// 🔹 Uma linha do relatório "Produtos abaixo do mínimo"
+#[derive(Debug, Clone)]
+pub struct Sugestao {
+    pub codigo: String,
+    pub nome: String,
+    pub fornecedor: Option<String>,
+    pub saldo: f64,
+    pub minimo: f64,
+    pub maximo: Option<f64>,
+    // Consumo no período, já descontadas as devoluções
+    pub consumo: f64,
+    pub comprar: f64,
+}
+
+// 🔹 Produtos do catálogo com saldo abaixo do mínimo, por código.
+// `saldos` e `consumo` vêm do estoque (ver `estoque::consumo_desde`).
+pub fn abaixo_do_minimo(
+    catalogo: &Catalogo,
+    cadastro: &Cadastro,
+    saldos: &HashMap<String, f64>,
+    consumo: &HashMap<String, f64>,
+) -> Vec<Sugestao> {
+    let mut sugestoes = Vec::new();
+
+    for &id in catalogo.ordem_por_codigo() {
+        let codigo = catalogo.codigo(id);
+        let complemento = catalogo.complemento(id);
+        let niveis = cadastro.niveis(complemento);
+        let Some(minimo) = niveis.minimo else {
+            continue;
+        };
+        let saldo = saldos.get(codigo).copied().unwrap_or(0.0);
+        if saldo >= minimo {
+            continue;
+        }
+
+        let consumido = consumo.get(codigo).copied().unwrap_or(0.0);
+        let mut alvo = minimo + consumido;
+        if let Some(maximo) = niveis.maximo {
+            alvo = alvo.min(maximo).max(minimo);
+        }
+
+        sugestoes.push(Sugestao {
+            codigo: codigo.to_string(),
+            nome: catalogo.nome(id).to_string(),
+            fornecedor: complemento.fornecedor.clone(),
+            saldo,
+            minimo,
+            maximo: niveis.maximo,
+            consumo: consumido,
+            // Compra em unidades inteiras, mesmo de produto fracionado
+            comprar: (alvo - saldo).ceil(),
+        });
+    }
+
+    sugestoes
+}
+
+// 🔹 A mesma lista agrupada por fornecedor (em ordem alfabética, os sem
+// fornecedor por último) para virar pedido de compra
+pub fn por_fornecedor(sugestoes: &[Sugestao]) -> Vec<(Option<&str>, Vec<&Sugestao>)> {
+    let mut grupos: Vec<(Option<&str>, Vec<&Sugestao>)> = Vec::new();
+    for s in sugestoes {
+        let fornecedor = s.fornecedor.as_deref();
+        match grupos.iter_mut().find(|(f, _)| *f == fornecedor) {
+            Some((_, itens)) => itens.push(s),
+            None => grupos.push((fornecedor, vec![s])),
+        }
+    }
+    grupos.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) => crate::texto::comparar_sem_caixa(a, b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+    grupos
+}
+
+// 🔹 Lista de compras em texto, um bloco por fornecedor
+pub fn escrever_lista<W: Write>(mut saida: W, sugestoes: &[Sugestao]) -> io::Result<()> {
+    for (fornecedor, itens) in por_fornecedor(sugestoes) {
+        writeln!(
+            saida,
+            "Fornecedor: {}",
+            fornecedor.unwrap_or("(sem fornecedor)")
+        )?;
+        for s in itens {
+            writeln!(
+                saida,
+                "  [{}] {}  saldo {}, mínimo {}, consumo {}  ->  comprar {}",
+                s.codigo,
+                s.nome,
+                formatar_quantidade(s.saldo),
+                formatar_quantidade(s.minimo),
+                formatar_quantidade(s.consumo),
+                formatar_quantidade(s.comprar)
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,333 @@
+// 🔹 Catálogo guardado num banco SQLite (compilado só com a feature "sqlite").
+// O banco aceita edições concorrentes e buscas sem carregar o catálogo inteiro:
+// o código tem índice próprio e o nome é pesquisado pela tabela FTS5.
+
+use std::io;
+
+use crate::diario::Alteracao;
+use crate::produto::Produto;
+
+#[cfg(feature = "sqlite")]
+pub use banco::Banco;
+
+// 🔹 Todos os produtos do banco, na ordem em que foram gravados
+pub fn ler_produtos(caminho: &str) -> io::Result<Vec<Produto>> {
+    #[cfg(feature = "sqlite")]
+    {
+        Banco::abrir(caminho)?.produtos()
+    }
+    #[cfg(not(feature = "sqlite"))]
+    {
+        Err(sem_suporte(caminho))
+    }
+}
+
+// 🔹 Substitui o conteúdo do banco pelos produtos (numa única transação)
+pub fn salvar_produtos(caminho: &str, produtos: &[Produto]) -> io::Result<()> {
+    #[cfg(feature = "sqlite")]
+    {
+        Banco::abrir(caminho)?.substituir(produtos)
+    }
+    #[cfg(not(feature = "sqlite"))]
+    {
+        let _ = produtos;
+        Err(sem_suporte(caminho))
+    }
+}
+
+// 🔹 Aplica as alterações no banco sem regravar o catálogo inteiro
+pub fn aplicar_alteracoes(caminho: &str, alteracoes: &[Alteracao]) -> io::Result<()> {
+    #[cfg(feature = "sqlite")]
+    {
+        Banco::abrir(caminho)?.aplicar(alteracoes)
+    }
+    #[cfg(not(feature = "sqlite"))]
+    {
+        let _ = alteracoes;
+        Err(sem_suporte(caminho))
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sem_suporte(caminho: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "'{}' é um banco SQLite, mas o programa foi compilado sem a feature \"sqlite\"",
+            caminho
+        ),
+    )
+}
+
+#[cfg(feature = "sqlite")]
+mod banco {
+    use std::io;
+    use std::time::Duration;
+
+    use rusqlite::{Connection, OptionalExtension, Row, params};
+    use serde_json::{Map, Value};
+
+    use crate::cadastro::{Complemento, Niveis};
+    use crate::diario::Alteracao;
+    use crate::dinheiro::Reais;
+    use crate::produto::Produto;
+
+    // Cada item leva o banco da versão anterior para a seguinte (PRAGMA user_version).
+    // Novos campos do `Produto` entram como uma nova migração no fim da lista.
+    const MIGRACOES: &[&str] = &[
+        // 1: produtos, na ordem de gravação, com índice por código
+        "CREATE TABLE produtos (
+             id     INTEGER PRIMARY KEY,
+             codigo TEXT NOT NULL,
+             nome   TEXT NOT NULL
+         );
+         CREATE INDEX produtos_codigo ON produtos (codigo);",
+        // 2: busca por palavras do nome (sem diferenciar acentos), mantida por gatilhos
+        "CREATE VIRTUAL TABLE produtos_fts USING fts5 (
+             nome, content = 'produtos', content_rowid = 'id',
+             tokenize = 'unicode61 remove_diacritics 2'
+         );
+         INSERT INTO produtos_fts (rowid, nome) SELECT id, nome FROM produtos;
+         CREATE TRIGGER produtos_ai AFTER INSERT ON produtos BEGIN
+             INSERT INTO produtos_fts (rowid, nome) VALUES (new.id, new.nome);
+         END;
+         CREATE TRIGGER produtos_ad AFTER DELETE ON produtos BEGIN
+             INSERT INTO produtos_fts (produtos_fts, rowid, nome) VALUES ('delete', old.id, old.nome);
+         END;
+         CREATE TRIGGER produtos_au AFTER UPDATE ON produtos BEGIN
+             INSERT INTO produtos_fts (produtos_fts, rowid, nome) VALUES ('delete', old.id, old.nome);
+             INSERT INTO produtos_fts (rowid, nome) VALUES (new.id, new.nome);
+         END;",
+        // 3: campos extras vindos do JSON (objeto JSON em texto)
+        "ALTER TABLE produtos ADD COLUMN extras TEXT NOT NULL DEFAULT '{}';",
+        // 4: dados complementares (preço e custo em centavos), com índice pelo
+        // código de barras
+        "ALTER TABLE produtos ADD COLUMN preco INTEGER;
+         ALTER TABLE produtos ADD COLUMN custo INTEGER;
+         ALTER TABLE produtos ADD COLUMN gtin TEXT;
+         ALTER TABLE produtos ADD COLUMN categoria TEXT;
+         ALTER TABLE produtos ADD COLUMN fornecedor TEXT;
+         ALTER TABLE produtos ADD COLUMN minimo REAL;
+         ALTER TABLE produtos ADD COLUMN maximo REAL;
+         CREATE INDEX produtos_gtin ON produtos (gtin);",
+    ];
+
+    const COLUNAS: &str =
+        "codigo, nome, extras, preco, custo, gtin, categoria, fornecedor, minimo, maximo";
+    // Na inclusão, um id None deixa o SQLite escolher o próximo
+    const COLUNAS_COM_ID: &str =
+        "codigo, nome, extras, preco, custo, gtin, categoria, fornecedor, minimo, maximo, id";
+
+    pub struct Banco {
+        conexao: Connection,
+    }
+
+    impl Banco {
+        // 🔹 Abre (ou cria) o banco e aplica as migrações que faltarem
+        pub fn abrir(caminho: &str) -> io::Result<Banco> {
+            let mut conexao = Connection::open(caminho).map_err(erro)?;
+            // Outro programa gravando: espera em vez de falhar na hora
+            conexao.busy_timeout(Duration::from_secs(5)).map_err(erro)?;
+
+            let versao = conexao
+                .pragma_query_value(None, "user_version", |r| r.get::<_, i64>(0))
+                .map_err(erro)? as usize;
+            if versao > MIGRACOES.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "banco '{}' na versão {}, mais nova que a deste programa ({})",
+                        caminho,
+                        versao,
+                        MIGRACOES.len()
+                    ),
+                ));
+            }
+
+            for (i, migracao) in MIGRACOES.iter().enumerate().skip(versao) {
+                let transacao = conexao.transaction().map_err(erro)?;
+                transacao.execute_batch(migracao).map_err(erro)?;
+                transacao
+                    .pragma_update(None, "user_version", (i + 1) as i64)
+                    .map_err(erro)?;
+                transacao.commit().map_err(erro)?;
+            }
+
+            Ok(Banco { conexao })
+        }
+
+        pub fn produtos(&self) -> io::Result<Vec<Produto>> {
+            self.consultar(&format!("SELECT {} FROM produtos ORDER BY id", COLUNAS), [])
+        }
+
+        // 🔹 Busca exata por código (índice produtos_codigo)
+        pub fn buscar_por_codigo(&self, codigo: &str) -> io::Result<Option<Produto>> {
+            self.conexao
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM produtos WHERE codigo = ?1 ORDER BY id LIMIT 1",
+                        COLUNAS
+                    ),
+                    [codigo],
+                    produto_da_linha,
+                )
+                .optional()
+                .map_err(erro)
+        }
+
+        // 🔹 Busca exata pelo código de barras (índice produtos_gtin)
+        pub fn buscar_por_gtin(&self, gtin: &str) -> io::Result<Option<Produto>> {
+            self.conexao
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM produtos WHERE gtin = ?1 ORDER BY id LIMIT 1",
+                        COLUNAS
+                    ),
+                    [gtin],
+                    produto_da_linha,
+                )
+                .optional()
+                .map_err(erro)
+        }
+
+        // 🔹 Produtos com palavras do nome começando com cada palavra do termo
+        // ("serra circ" acha "Serra Circular 7.1/4\""), em ordem alfabética
+        pub fn buscar_por_nome(&self, termo: &str) -> io::Result<Vec<Produto>> {
+            let consulta: Vec<String> = termo
+                .split_whitespace()
+                .map(|palavra| format!("\"{}\"*", palavra.replace('"', "\"\"")))
+                .collect();
+            if consulta.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            self.consultar(
+                &format!(
+                    "SELECT {} FROM produtos
+                     WHERE id IN (SELECT rowid FROM produtos_fts WHERE produtos_fts MATCH ?1)
+                     ORDER BY nome COLLATE NOCASE, id",
+                    COLUNAS
+                ),
+                [consulta.join(" ")],
+            )
+        }
+
+        // 🔹 Troca todos os produtos de uma vez (importação de um arquivo)
+        pub fn substituir(&mut self, produtos: &[Produto]) -> io::Result<()> {
+            let transacao = self.conexao.transaction().map_err(erro)?;
+            transacao
+                .execute("DELETE FROM produtos", [])
+                .map_err(erro)?;
+            for p in produtos {
+                gravar_linha(&transacao, None, p).map_err(erro)?;
+            }
+            transacao.commit().map_err(erro)
+        }
+
+        // 🔹 Aplica as alterações do diário uma a uma, numa transação, sem
+        // regravar os outros produtos (o mesmo que `Alteracao::aplicar`)
+        pub fn aplicar(&mut self, alteracoes: &[Alteracao]) -> io::Result<()> {
+            let transacao = self.conexao.transaction().map_err(erro)?;
+            for alteracao in alteracoes {
+                match alteracao {
+                    Alteracao::Gravar { produto } => {
+                        let atual = transacao
+                            .query_row(
+                                "SELECT id, extras FROM produtos WHERE codigo = ?1 ORDER BY id LIMIT 1",
+                                [&produto.codigo],
+                                |l| Ok((l.get::<_, i64>(0)?, l.get::<_, String>(1)?)),
+                            )
+                            .optional()
+                            .map_err(erro)?;
+                        let mut novo = Produto::clone(produto);
+                        // Campos extras que o novo não traz continuam os do substituído
+                        if let Some((_, extras)) = &atual {
+                            let antigos: Map<String, Value> =
+                                serde_json::from_str(extras).unwrap_or_default();
+                            for (campo, valor) in antigos {
+                                novo.extras.entry(campo).or_insert(valor);
+                            }
+                        }
+                        gravar_linha(&transacao, atual.map(|(id, _)| id), &novo).map_err(erro)?;
+                    }
+                    Alteracao::Remover { codigo } => {
+                        transacao
+                            .execute("DELETE FROM produtos WHERE codigo = ?1", [codigo])
+                            .map_err(erro)?;
+                    }
+                }
+            }
+            transacao.commit().map_err(erro)
+        }
+
+        fn consultar(
+            &self,
+            sql: &str,
+            parametros: impl rusqlite::Params,
+        ) -> io::Result<Vec<Produto>> {
+            let mut consulta = self.conexao.prepare(sql).map_err(erro)?;
+            let linhas = consulta
+                .query_map(parametros, produto_da_linha)
+                .map_err(erro)?;
+            linhas.collect::<Result<_, _>>().map_err(erro)
+        }
+    }
+
+    // Inclui o produto ou, com `id`, regrava a linha dele
+    fn gravar_linha(conexao: &Connection, id: Option<i64>, p: &Produto) -> rusqlite::Result<()> {
+        let sql = match id {
+            Some(_) => "UPDATE produtos SET codigo = ?1, nome = ?2, extras = ?3, preco = ?4,
+                            custo = ?5, gtin = ?6, categoria = ?7, fornecedor = ?8,
+                            minimo = ?9, maximo = ?10
+                        WHERE id = ?11"
+                .to_string(),
+            None => format!(
+                "INSERT INTO produtos ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                COLUNAS_COM_ID
+            ),
+        };
+        let extras = Value::Object(p.extras.clone()).to_string();
+        let c = &p.complemento;
+        conexao.prepare_cached(&sql)?.execute(params![
+            p.codigo,
+            p.nome,
+            extras,
+            c.preco.map(|r| r.centavos()),
+            c.custo.map(|r| r.centavos()),
+            c.gtin,
+            c.categoria,
+            c.fornecedor,
+            c.niveis.minimo,
+            c.niveis.maximo,
+            id
+        ])?;
+        Ok(())
+    }
+
+    fn produto_da_linha(linha: &Row) -> rusqlite::Result<Produto> {
+        let extras: String = linha.get(2)?;
+        let reais = |i| -> rusqlite::Result<Option<Reais>> {
+            Ok(linha.get::<_, Option<i64>>(i)?.map(Reais::de_centavos))
+        };
+        Ok(Produto {
+            codigo: linha.get(0)?,
+            nome: linha.get(1)?,
+            complemento: Complemento {
+                preco: reais(3)?,
+                custo: reais(4)?,
+                gtin: linha.get(5)?,
+                categoria: linha.get(6)?,
+                fornecedor: linha.get(7)?,
+                niveis: Niveis {
+                    minimo: linha.get(8)?,
+                    maximo: linha.get(9)?,
+                },
+            },
+            extras: serde_json::from_str::<Map<String, Value>>(&extras).unwrap_or_default(),
+        })
+    }
+
+    fn erro(e: rusqlite::Error) -> io::Error {
+        io::Error::other(format!("SQLite: {}", e))
+    }
+}
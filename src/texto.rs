@@ -0,0 +1,39 @@
+use std::cmp::Ordering;
+
+// 🔹 Troca letras acentuadas pela versão sem acento ("Aço" -> "Aco")
+pub fn remover_acentos(texto: &str) -> String {
+    texto.chars().map(sem_acento).collect()
+}
+
+fn sem_acento(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        _ => c,
+    }
+}
+
+// 🔹 Compara dois textos sem diferenciar maiúsculas e minúsculas, sem alocar
+pub fn comparar_sem_caixa(a: &str, b: &str) -> Ordering {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+// 🔹 Verifica se `texto` começa com `prefixo` (já em minúsculas), ignorando a caixa
+pub fn comeca_com_sem_caixa(texto: &str, prefixo: &str) -> bool {
+    let mut letras = texto.chars().flat_map(char::to_lowercase);
+    prefixo.chars().all(|c| letras.next() == Some(c))
+}
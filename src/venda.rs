@@ -0,0 +1,370 @@
+// 🔹 Vendas do ponto de venda ("<catalogo>.vendas.jsonl"): o carrinho que o
+// caixa monta, o registro de cada venda fechada (uma linha JSON, com número
+// sequencial) e o cupom impresso a partir dele.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::dinheiro::Reais;
+use crate::estoque::{Estoque, TipoMovimento, formatar_quantidade};
+use crate::orcamento;
+
+// Nome no topo do cupom
+pub const LOJA: &str = "MegaStore";
+
+// Colunas do cupom (bobina de 80 mm em fonte normal)
+pub const LARGURA_CUPOM: usize = 40;
+
+// 🔹 Uma linha do carrinho ou da venda
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub codigo: String,
+    pub nome: String,
+    pub quantidade: f64,
+    // Preço unitário cobrado
+    pub preco: Reais,
+    // Preço total impresso na etiqueta da balança: vale como total da linha,
+    // mesmo que quantidade x preço dê um centavo diferente
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etiqueta: Option<Reais>,
+}
+
+impl Item {
+    pub fn total(&self) -> Reais {
+        self.etiqueta
+            .unwrap_or_else(|| self.preco.vezes(self.quantidade))
+    }
+}
+
+// 🔹 Itens da venda em andamento
+#[derive(Debug, Clone, Default)]
+pub struct Carrinho {
+    itens: Vec<Item>,
+}
+
+impl Carrinho {
+    pub fn itens(&self) -> &[Item] {
+        &self.itens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.itens.is_empty()
+    }
+
+    // O mesmo produto pelo mesmo preço soma na linha que já existe; cada
+    // etiqueta de balança com preço fica na sua linha
+    pub fn adicionar(&mut self, item: Item) {
+        match self.itens.iter_mut().find(|i| {
+            i.codigo == item.codigo
+                && i.preco == item.preco
+                && i.etiqueta.is_none()
+                && item.etiqueta.is_none()
+        }) {
+            Some(existente) => existente.quantidade += item.quantidade,
+            None => self.itens.push(item),
+        }
+    }
+
+    // Pela posição mostrada ao caixa (começando em 1)
+    pub fn remover(&mut self, numero: usize) -> Option<Item> {
+        (1..=self.itens.len())
+            .contains(&numero)
+            .then(|| self.itens.remove(numero - 1))
+    }
+
+    pub fn total(&self) -> Reais {
+        self.itens.iter().map(Item::total).sum()
+    }
+}
+
+// Como o cliente pagou
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pagamento {
+    #[default]
+    Dinheiro,
+    Pix,
+}
+
+impl Pagamento {
+    pub fn nome(&self) -> &'static str {
+        match self {
+            Pagamento::Dinheiro => "dinheiro",
+            Pagamento::Pix => "PIX",
+        }
+    }
+}
+
+// 🔹 Uma venda fechada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Venda {
+    pub numero: u64,
+    pub quando: DateTime<Local>,
+    pub operador: String,
+    pub itens: Vec<Item>,
+    pub total: Reais,
+    pub pago: Reais,
+    pub troco: Reais,
+    // Vendas gravadas antes do PIX não têm o campo: foram em dinheiro
+    #[serde(default)]
+    pub pagamento: Pagamento,
+}
+
+pub fn caminho_das_vendas(caminho: &str) -> String {
+    format!("{}.vendas.jsonl", caminho)
+}
+
+// 🔹 Todas as vendas registradas, em ordem (arquivo inexistente = nenhuma)
+pub fn ler(caminho: &str) -> io::Result<Vec<Venda>> {
+    let texto = match fs::read_to_string(caminho_das_vendas(caminho)) {
+        Ok(texto) => texto,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    Ok(texto
+        .lines()
+        .filter_map(|linha| serde_json::from_str(linha).ok())
+        .collect())
+}
+
+pub fn buscar(caminho: &str, numero: u64) -> io::Result<Option<Venda>> {
+    Ok(ler(caminho)?.into_iter().find(|v| v.numero == numero))
+}
+
+// 🔹 Registro de vendas aberto para gravação
+pub struct Vendas {
+    caminho: String,
+    arquivo: File,
+}
+
+impl Vendas {
+    // Uma linha incompleta no fim (queda no meio da gravação) é cortada, para
+    // não emendar na próxima venda
+    pub fn abrir(caminho: &str) -> io::Result<Vendas> {
+        let arquivo = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(caminho_das_vendas(caminho))?;
+        let bytes = fs::read(caminho_das_vendas(caminho))?;
+        let completas = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if bytes.len() > completas {
+            arquivo.set_len(completas as u64)?;
+        }
+        Ok(Vendas {
+            caminho: caminho.to_string(),
+            arquivo,
+        })
+    }
+
+    // 🔹 Fecha a venda: dá baixa de cada item no estoque e grava o registro
+    // com o próximo número. O arquivo fica travado do começo ao fim, para dois
+    // caixas não usarem o mesmo número nem venderem o mesmo saldo. A venda não
+    // pode levar o que está reservado para um orçamento. Com erro, a venda não
+    // foi gravada e as baixas já lançadas foram estornadas.
+    pub fn registrar(
+        &mut self,
+        estoque: &mut Estoque,
+        operador: &str,
+        carrinho: &Carrinho,
+        pago: Reais,
+        pagamento: Pagamento,
+    ) -> io::Result<Venda> {
+        self.fechar(estoque, operador, carrinho, pago, pagamento, None)
+    }
+
+    // Venda de um orçamento: a reserva dele mesmo pode ser usada
+    pub(crate) fn registrar_do_orcamento(
+        &mut self,
+        estoque: &mut Estoque,
+        operador: &str,
+        carrinho: &Carrinho,
+        pago: Reais,
+        pagamento: Pagamento,
+        orcamento: u64,
+    ) -> io::Result<Venda> {
+        self.fechar(
+            estoque,
+            operador,
+            carrinho,
+            pago,
+            pagamento,
+            Some(orcamento),
+        )
+    }
+
+    fn fechar(
+        &mut self,
+        estoque: &mut Estoque,
+        operador: &str,
+        carrinho: &Carrinho,
+        pago: Reais,
+        pagamento: Pagamento,
+        orcamento: Option<u64>,
+    ) -> io::Result<Venda> {
+        let total = carrinho.total();
+        if carrinho.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "nenhum item no carrinho",
+            ));
+        }
+        if pago < total {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "valor pago ({}) menor que o total ({})",
+                    pago.com_simbolo(),
+                    total.com_simbolo()
+                ),
+            ));
+        }
+
+        self.arquivo.lock()?;
+        let resultado =
+            orcamento::conferir_reservas(&self.caminho, estoque, carrinho.itens(), orcamento)
+                .and_then(|()| {
+                    let venda = Venda {
+                        numero: ler(&self.caminho)?.last().map_or(0, |v| v.numero) + 1,
+                        quando: Local::now(),
+                        operador: operador.to_string(),
+                        itens: carrinho.itens().to_vec(),
+                        total,
+                        pago,
+                        troco: pago - total,
+                        pagamento,
+                    };
+                    self.registrar_travado(estoque, venda)
+                });
+        let _ = self.arquivo.unlock();
+        resultado
+    }
+
+    // As baixas vêm antes do registro: uma venda gravada sempre tem o estoque
+    // baixado. Se uma baixa ou o registro falham, as baixas lançadas são
+    // estornadas com entradas; o erro diz quais estornos não deram certo.
+    fn registrar_travado(&mut self, estoque: &mut Estoque, venda: Venda) -> io::Result<Venda> {
+        let motivo = format!("venda {}", venda.numero);
+        let mut baixados = 0;
+        let mut resultado = Ok(());
+        for item in &venda.itens {
+            resultado = estoque
+                .lancar(
+                    &venda.operador,
+                    &item.codigo,
+                    TipoMovimento::Saida,
+                    item.quantidade,
+                    &motivo,
+                )
+                .map(|_| ());
+            if resultado.is_err() {
+                break;
+            }
+            baixados += 1;
+        }
+        if resultado.is_ok() {
+            resultado = serde_json::to_vec(&venda)
+                .map_err(io::Error::other)
+                .and_then(|mut linha| {
+                    linha.push(b'\n');
+                    self.arquivo.write_all(&linha)?;
+                    self.arquivo.sync_data()
+                });
+        }
+        let Err(e) = resultado else {
+            return Ok(venda);
+        };
+
+        let motivo = format!("estorno da venda {} (não gravada)", venda.numero);
+        let sem_estorno: Vec<String> = venda.itens[..baixados]
+            .iter()
+            .filter(|item| {
+                estoque
+                    .lancar(
+                        &venda.operador,
+                        &item.codigo,
+                        TipoMovimento::Entrada,
+                        item.quantidade,
+                        &motivo,
+                    )
+                    .is_err()
+            })
+            .map(|item| format!("[{}] {}", item.codigo, formatar_quantidade(item.quantidade)))
+            .collect();
+        if sem_estorno.is_empty() {
+            return Err(e);
+        }
+        Err(io::Error::new(
+            e.kind(),
+            format!(
+                "{}; a venda não foi gravada, mas a baixa destes itens ficou no estoque sem estorno: {}",
+                e,
+                sem_estorno.join(", ")
+            ),
+        ))
+    }
+}
+
+// Texto à esquerda e valor à direita, na largura do cupom
+pub(crate) fn linha_com_valor(texto: &str, valor: &str) -> String {
+    let espaco = LARGURA_CUPOM.saturating_sub(valor.chars().count());
+    format!("{:<espaco$}{}", texto, valor)
+}
+
+pub(crate) fn centralizado(texto: &str) -> String {
+    format!("{:^LARGURA_CUPOM$}", texto).trim_end().to_string()
+}
+
+// 🔹 Cupom da venda em texto simples, com LARGURA_CUPOM colunas
+pub fn cupom(venda: &Venda) -> String {
+    let traco = "-".repeat(LARGURA_CUPOM);
+    let mut linhas = vec![
+        centralizado(LOJA),
+        centralizado(&format!(
+            "{}  Venda {:06}",
+            venda.quando.format("%d/%m/%Y %H:%M:%S"),
+            venda.numero
+        )),
+        centralizado(&format!("Operador: {}", venda.operador)),
+        traco.clone(),
+        "Código Descrição".to_string(),
+        linha_com_valor("    Qtd x Preço", "Total"),
+        traco.clone(),
+    ];
+
+    for item in &venda.itens {
+        let descricao: String = format!("{} {}", item.codigo, item.nome)
+            .chars()
+            .take(LARGURA_CUPOM)
+            .collect();
+        linhas.push(descricao);
+        linhas.push(linha_com_valor(
+            &format!(
+                "    {} x {}",
+                formatar_quantidade(item.quantidade),
+                item.preco
+            ),
+            &item.total().to_string(),
+        ));
+    }
+
+    linhas.push(traco.clone());
+    linhas.push(linha_com_valor(
+        &format!("TOTAL ({} itens)", venda.itens.len()),
+        &venda.total.com_simbolo(),
+    ));
+    linhas.push(linha_com_valor(
+        &format!("Pago ({})", venda.pagamento.nome()),
+        &venda.pago.com_simbolo(),
+    ));
+    linhas.push(linha_com_valor("Troco", &venda.troco.com_simbolo()));
+    linhas.push(traco);
+    linhas.push(centralizado("Obrigado pela preferência!"));
+
+    let mut texto = linhas.join("\n");
+    texto.push('\n');
+    texto
+}
@@ -0,0 +1,174 @@
+// Recuperação e compactação do diário de alterações do catálogo
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use projeto_rust::diario::{self, Alteracao, Diario};
+use projeto_rust::produto::Produto;
+use projeto_rust::recarga::CatalogoVivo;
+
+// Pasta vazia só do teste, dentro da pasta temporária do sistema
+fn pasta(nome: &str) -> PathBuf {
+    let pasta = std::env::temp_dir().join(format!("diario_{}_{}", nome, std::process::id()));
+    let _ = fs::remove_dir_all(&pasta);
+    fs::create_dir_all(&pasta).unwrap();
+    pasta
+}
+
+fn catalogo(pasta: &Path, linhas: &str) -> String {
+    let caminho = pasta.join("produtos.txt");
+    fs::write(&caminho, linhas).unwrap();
+    caminho.to_string_lossy().into_owned()
+}
+
+fn gravar(codigo: &str, nome: &str) -> Alteracao {
+    Alteracao::Gravar {
+        produto: Box::new(Produto::new(codigo, nome)),
+    }
+}
+
+fn remover(codigo: &str) -> Alteracao {
+    Alteracao::Remover {
+        codigo: codigo.to_string(),
+    }
+}
+
+// Grava as alterações num diário novo e devolve o tamanho de cada linha
+fn escrever(caminho: &str, alteracoes: &[Alteracao]) -> Vec<usize> {
+    let (mut diario, _) = Diario::abrir(caminho).unwrap();
+    let mut tamanhos = Vec::new();
+    for alteracao in alteracoes {
+        let antes = fs::metadata(diario::caminho_do_diario(caminho))
+            .unwrap()
+            .len();
+        diario.registrar(alteracao).unwrap();
+        let depois = fs::metadata(diario::caminho_do_diario(caminho))
+            .unwrap()
+            .len();
+        tamanhos.push((depois - antes) as usize);
+    }
+    tamanhos
+}
+
+#[test]
+fn registro_pela_metade_no_fim_e_cortado() {
+    let pasta = pasta("metade");
+    let caminho = catalogo(&pasta, "0001;Arroz\n");
+    let tamanhos = escrever(&caminho, &[gravar("0002", "Feijão"), remover("0001")]);
+
+    // Queda no meio da terceira gravação: linha sem '\n' no fim
+    let log = diario::caminho_do_diario(&caminho);
+    let pedaco = b"0badc0de {\"op\":\"gravar\",\"produto\"";
+    let mut arquivo = OpenOptions::new().append(true).open(&log).unwrap();
+    arquivo.write_all(pedaco).unwrap();
+    drop(arquivo);
+
+    let (mut diario, recuperacao) = Diario::abrir(&caminho).unwrap();
+    assert_eq!(
+        recuperacao.alteracoes,
+        vec![gravar("0002", "Feijão"), remover("0001")]
+    );
+    assert_eq!(recuperacao.descartados as usize, pedaco.len());
+    assert_eq!(
+        fs::metadata(&log).unwrap().len() as usize,
+        tamanhos.iter().sum::<usize>()
+    );
+
+    // O próximo registro começa numa linha própria e é lido de volta
+    diario.registrar(&gravar("0003", "Sal")).unwrap();
+    let lidas = diario::ler(&caminho).unwrap();
+    assert_eq!(lidas.alteracoes.len(), 3);
+    assert_eq!(lidas.descartados, 0);
+}
+
+#[test]
+fn crc_errado_descarta_o_registro_e_o_resto() {
+    let pasta = pasta("crc");
+    let caminho = catalogo(&pasta, "0001;Arroz\n");
+    escrever(
+        &caminho,
+        &[
+            gravar("0002", "Feijão"),
+            gravar("0003", "Sal"),
+            remover("0001"),
+        ],
+    );
+
+    // Um byte trocado no conteúdo da segunda linha
+    let log = diario::caminho_do_diario(&caminho);
+    let mut bytes = fs::read(&log).unwrap();
+    let segunda = bytes.iter().position(|&b| b == b'\n').unwrap() + 1;
+    let sal = segunda
+        + bytes[segunda..]
+            .windows(3)
+            .position(|j| j == b"Sal")
+            .unwrap();
+    bytes[sal] = b'S' ^ 0x20;
+    fs::write(&log, &bytes).unwrap();
+
+    let (_, recuperacao) = Diario::abrir(&caminho).unwrap();
+    assert_eq!(recuperacao.alteracoes, vec![gravar("0002", "Feijão")]);
+    assert_eq!(recuperacao.descartados as usize, bytes.len() - segunda);
+    assert_eq!(fs::metadata(&log).unwrap().len() as usize, segunda);
+}
+
+#[test]
+fn alteracoes_sao_reaplicadas_ao_abrir() {
+    let pasta = pasta("reaplicar");
+    let caminho = catalogo(&pasta, "0001;Arroz\n0002;Feijão\n");
+    escrever(&caminho, &[gravar("0003", "Sal"), remover("0002")]);
+
+    let vivo = CatalogoVivo::abrir(&caminho, None).unwrap();
+    assert_eq!(vivo.recuperacao().alteracoes.len(), 2);
+    let catalogo = vivo.atual();
+    assert!(catalogo.buscar_por_codigo("0003").is_some());
+    assert!(catalogo.buscar_por_codigo("0002").is_none());
+}
+
+#[test]
+fn compactacao_regrava_o_catalogo_e_esvazia_o_diario() {
+    let pasta = pasta("compactar");
+    let caminho = catalogo(&pasta, "0001;Arroz\n0002;Feijão\n");
+
+    let vivo = CatalogoVivo::abrir(&caminho, None).unwrap();
+    vivo.gravar(Produto::new("0003", "Sal")).unwrap();
+    vivo.gravar(Produto::new("0001", "Arroz Integral")).unwrap();
+    vivo.remover("0002").unwrap();
+    assert_eq!(vivo.compactar().unwrap(), 3);
+    drop(vivo);
+
+    assert_eq!(
+        fs::metadata(diario::caminho_do_diario(&caminho))
+            .unwrap()
+            .len(),
+        0
+    );
+    let texto = fs::read_to_string(&caminho).unwrap();
+    assert!(texto.contains("0001;Arroz Integral"));
+    assert!(texto.contains("0003;Sal"));
+    assert!(!texto.contains("0002"));
+
+    let vivo = CatalogoVivo::abrir(&caminho, None).unwrap();
+    assert!(vivo.recuperacao().alteracoes.is_empty());
+    let catalogo = vivo.atual();
+    assert_eq!(catalogo.len(), 2);
+    let id = catalogo.buscar_por_codigo("0001").unwrap();
+    assert_eq!(catalogo.nome(id), "Arroz Integral");
+}
+
+#[test]
+fn compactacao_leva_o_que_outro_programa_gravou() {
+    let pasta = pasta("outro");
+    let caminho = catalogo(&pasta, "0001;Arroz\n");
+
+    let vivo = CatalogoVivo::abrir(&caminho, None).unwrap();
+    vivo.gravar(Produto::new("0002", "Feijão")).unwrap();
+    // Outro terminal grava no mesmo diário depois de este programa abrir
+    escrever(&caminho, &[gravar("0003", "Sal")]);
+
+    assert_eq!(vivo.compactar().unwrap(), 2);
+    let texto = fs::read_to_string(&caminho).unwrap();
+    assert!(texto.contains("0002;Feijão"));
+    assert!(texto.contains("0003;Sal"));
+}
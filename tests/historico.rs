@@ -0,0 +1,70 @@
+// Desfazer e refazer com dois programas abertos no mesmo catálogo
+
+use std::fs;
+use std::path::PathBuf;
+
+use projeto_rust::produto::Produto;
+use projeto_rust::recarga::CatalogoVivo;
+
+// Catálogo numa pasta vazia só do teste, dentro da pasta temporária do sistema
+fn catalogo(nome: &str, linhas: &str) -> String {
+    let pasta: PathBuf =
+        std::env::temp_dir().join(format!("historico_{}_{}", nome, std::process::id()));
+    let _ = fs::remove_dir_all(&pasta);
+    fs::create_dir_all(&pasta).unwrap();
+    let caminho = pasta.join("produtos.txt");
+    fs::write(&caminho, linhas).unwrap();
+    caminho.to_string_lossy().into_owned()
+}
+
+fn nome(vivo: &CatalogoVivo, codigo: &str) -> Option<String> {
+    let catalogo = vivo.atual();
+    catalogo
+        .buscar_por_codigo(codigo)
+        .map(|id| catalogo.nome(id).to_string())
+}
+
+#[test]
+fn desfazer_de_outro_programa_conta_nas_pilhas() {
+    let caminho = catalogo("pilhas", "0001;Arroz\n");
+    let menu = CatalogoVivo::abrir(&caminho, None).unwrap();
+    menu.gravar(Produto::new("0001", "Arroz Tipo 1")).unwrap();
+    menu.gravar(Produto::new("0002", "Feijão")).unwrap();
+
+    // Outro terminal desfaz a inclusão do feijão
+    let terminal = CatalogoVivo::abrir(&caminho, None).unwrap();
+    let desfeita = terminal.desfazer().unwrap().unwrap();
+    assert_eq!(desfeita.mudancas[0].codigo, "0002");
+
+    // O menu desfaz a operação anterior, e não a do feijão de novo
+    let desfeita = menu.desfazer().unwrap().unwrap();
+    assert_eq!(desfeita.mudancas[0].codigo, "0001");
+    assert_eq!(nome(&menu, "0001").as_deref(), Some("Arroz"));
+    assert_eq!(nome(&menu, "0002"), None);
+
+    // E refaz as duas, na ordem em que foram desfeitas
+    assert_eq!(menu.refazer().unwrap().unwrap().mudancas[0].codigo, "0001");
+    assert_eq!(menu.refazer().unwrap().unwrap().mudancas[0].codigo, "0002");
+    assert!(menu.refazer().unwrap().is_none());
+}
+
+#[test]
+fn desfazer_nao_apaga_edicao_mais_nova() {
+    let caminho = catalogo("edicao", "0001;Arroz\n");
+    let menu = CatalogoVivo::abrir(&caminho, None).unwrap();
+    menu.gravar(Produto::new("0001", "Arroz Tipo 1")).unwrap();
+    menu.compactar().unwrap();
+
+    // Alguém corrige o nome direto no arquivo, fora do histórico
+    fs::write(&caminho, "0001;Arroz Premium\n").unwrap();
+
+    // O menu ainda não recarregou, mas o desfazer confere o que está em disco
+    let erro = menu.desfazer().unwrap_err();
+    assert!(erro.to_string().contains("[0001] mudou"), "{}", erro);
+    assert_eq!(nome(&menu, "0001").as_deref(), Some("Arroz Premium"));
+
+    // Voltando o nome que a operação deixou, o desfazer é aceito
+    fs::write(&caminho, "0001;Arroz Tipo 1\n").unwrap();
+    menu.desfazer().unwrap().unwrap();
+    assert_eq!(nome(&menu, "0001").as_deref(), Some("Arroz"));
+}
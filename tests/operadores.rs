@@ -0,0 +1,45 @@
+// Contas de operador conferidas pela trilha de auditoria
+
+use std::fs;
+use std::path::PathBuf;
+
+use projeto_rust::operadores::{self, Operadores, Papel};
+
+// Catálogo numa pasta vazia só do teste, dentro da pasta temporária do sistema
+fn catalogo(nome: &str) -> String {
+    let pasta: PathBuf =
+        std::env::temp_dir().join(format!("operadores_{}_{}", nome, std::process::id()));
+    let _ = fs::remove_dir_all(&pasta);
+    fs::create_dir_all(&pasta).unwrap();
+    let caminho = pasta.join("produtos.txt");
+    fs::write(&caminho, "0001;Arroz\n").unwrap();
+    caminho.to_string_lossy().into_owned()
+}
+
+#[test]
+fn falha_na_gravacao_anula_o_lote_inteiro() {
+    let caminho = catalogo("lote");
+    let mut contas = Operadores::abrir(&caminho).unwrap();
+    contas.adicionar("ana", Papel::Gerente, "segredo1").unwrap();
+    contas.adicionar("bia", Papel::Caixa, "segredo2").unwrap();
+
+    // Uma pasta no lugar do arquivo faz a troca do temporário falhar
+    let arquivo = operadores::caminho_dos_operadores(&caminho);
+    fs::create_dir_all(PathBuf::from(&arquivo).join("ocupado")).unwrap();
+    assert!(contas.salvar("admin").is_err());
+
+    // As duas contas não chegaram ao arquivo: abrir sem ele continua valendo
+    fs::remove_dir_all(&arquivo).unwrap();
+    assert!(Operadores::abrir(&caminho).unwrap().is_empty());
+}
+
+#[test]
+fn arquivo_apagado_com_contas_na_trilha_e_recusado() {
+    let caminho = catalogo("apagado");
+    let mut contas = Operadores::abrir(&caminho).unwrap();
+    contas.adicionar("ana", Papel::Gerente, "segredo1").unwrap();
+    contas.salvar("admin").unwrap();
+
+    fs::remove_file(operadores::caminho_dos_operadores(&caminho)).unwrap();
+    assert!(Operadores::abrir(&caminho).is_err());
+}